@@ -0,0 +1,53 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use neo4rs::testkit::{encode_prepared_run, encode_run};
+use neo4rs::types::{BoltMap, PreparedParams};
+use neo4rs::Version;
+
+const QUERY: &str = "MATCH (n:Person {tenant: $tenant}) WHERE n.id = $id RETURN n";
+
+fn shared_params() -> BoltMap {
+    (0..20)
+        .map(|i| {
+            (
+                format!("s{}", i).into(),
+                format!("value {}", i).repeat(10).into(),
+            )
+        })
+        .collect()
+}
+
+fn query_params(id: i64) -> BoltMap {
+    let mut params = BoltMap::default();
+    params.put("id".into(), id.into());
+    params
+}
+
+fn encode_run_params(c: &mut Criterion) {
+    let shared = shared_params();
+    let prepared = PreparedParams::new(shared.clone()).unwrap();
+
+    let mut group = c.benchmark_group("run with 20 shared params");
+    group.bench_function("prepared", |b| {
+        b.iter(|| {
+            encode_prepared_run(
+                Version::V4_4,
+                QUERY,
+                &prepared,
+                query_params(black_box(7)),
+                BoltMap::default(),
+            )
+            .unwrap()
+        })
+    });
+    group.bench_function("re-encoded", |b| {
+        b.iter(|| {
+            let mut params = shared.clone();
+            params.put("id".into(), black_box(7).into());
+            encode_run(Version::V4_4, QUERY, params, BoltMap::default()).unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, encode_run_params);
+criterion_main!(benches);
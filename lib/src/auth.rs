@@ -0,0 +1,179 @@
+use crate::types::*;
+use std::fmt;
+
+/// The authentication token sent to the server when a connection is established, see
+/// [`ConfigBuilder::auth`]
+#[derive(Clone, PartialEq)]
+pub enum Auth {
+    /// Connects without authentication, for servers with authentication disabled
+    None,
+    /// Authenticates with a user name and password
+    Basic {
+        principal: String,
+        credentials: String,
+    },
+    /// Authenticates with a token issued by an identity provider, e.g. an OIDC access token
+    Bearer { token: String },
+    /// Authenticates with a base64 encoded kerberos ticket
+    Kerberos { ticket: String },
+    /// An arbitrary scheme, the fields are sent along with the scheme as they are
+    Custom { scheme: String, fields: BoltMap },
+}
+
+impl Auth {
+    pub fn basic(user: &str, password: &str) -> Self {
+        Auth::Basic {
+            principal: user.to_owned(),
+            credentials: password.to_owned(),
+        }
+    }
+
+    pub fn none() -> Self {
+        Auth::None
+    }
+
+    pub fn bearer(token: &str) -> Self {
+        Auth::Bearer {
+            token: token.to_owned(),
+        }
+    }
+
+    pub fn kerberos(ticket: &str) -> Self {
+        Auth::Kerberos {
+            ticket: ticket.to_owned(),
+        }
+    }
+
+    /// A scheme without builtin support, e.g. one provided by a server plugin
+    pub fn custom(scheme: &str, fields: BoltMap) -> Self {
+        Auth::Custom {
+            scheme: scheme.to_owned(),
+            fields,
+        }
+    }
+
+    /// The token as it is sent in the HELLO message
+    pub(crate) fn to_map(&self) -> BoltMap {
+        let mut token = BoltMap::default();
+        match self {
+            Auth::None => {
+                token.put("scheme".into(), "none".into());
+            }
+            Auth::Basic {
+                principal,
+                credentials,
+            } => {
+                token.put("scheme".into(), "basic".into());
+                token.put("principal".into(), principal.as_str().into());
+                token.put("credentials".into(), credentials.as_str().into());
+            }
+            Auth::Bearer { token: bearer } => {
+                token.put("scheme".into(), "bearer".into());
+                token.put("credentials".into(), bearer.as_str().into());
+            }
+            Auth::Kerberos { ticket } => {
+                token.put("scheme".into(), "kerberos".into());
+                token.put("credentials".into(), ticket.as_str().into());
+            }
+            Auth::Custom { scheme, fields } => {
+                token.put("scheme".into(), scheme.as_str().into());
+                for (key, value) in fields.value.iter() {
+                    token.put(key.clone(), value.clone());
+                }
+            }
+        }
+        token
+    }
+}
+
+/// Credentials are left out to keep them out of logs.
+impl fmt::Debug for Auth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Auth::None => write!(f, "None"),
+            Auth::Basic { principal, .. } => f
+                .debug_struct("Basic")
+                .field("principal", principal)
+                .finish_non_exhaustive(),
+            Auth::Bearer { .. } => write!(f, "Bearer {{ .. }}"),
+            Auth::Kerberos { .. } => write!(f, "Kerberos {{ .. }}"),
+            Auth::Custom { scheme, .. } => f
+                .debug_struct("Custom")
+                .field("scheme", scheme)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_build_basic_token() {
+        let token = Auth::basic("neo4j", "secret").to_map();
+
+        assert_eq!(token.get::<String>("scheme"), Some("basic".to_owned()));
+        assert_eq!(token.get::<String>("principal"), Some("neo4j".to_owned()));
+        assert_eq!(
+            token.get::<String>("credentials"),
+            Some("secret".to_owned())
+        );
+        assert_eq!(token.len(), 3);
+    }
+
+    #[test]
+    fn should_build_none_token() {
+        let token = Auth::none().to_map();
+
+        assert_eq!(token.get::<String>("scheme"), Some("none".to_owned()));
+        assert_eq!(token.len(), 1);
+    }
+
+    #[test]
+    fn should_build_bearer_token() {
+        let token = Auth::bearer("eyJ0").to_map();
+
+        let keys: Vec<&str> = token.value.keys().map(|k| k.value.as_str()).collect();
+        assert_eq!(keys, vec!["scheme", "credentials"]);
+        assert_eq!(token.get::<String>("scheme"), Some("bearer".to_owned()));
+        assert_eq!(token.get::<String>("credentials"), Some("eyJ0".to_owned()));
+    }
+
+    #[test]
+    fn should_build_kerberos_token() {
+        let token = Auth::kerberos("dGlja2V0").to_map();
+
+        let keys: Vec<&str> = token.value.keys().map(|k| k.value.as_str()).collect();
+        assert_eq!(keys, vec!["scheme", "credentials"]);
+        assert_eq!(token.get::<String>("scheme"), Some("kerberos".to_owned()));
+        assert_eq!(
+            token.get::<String>("credentials"),
+            Some("dGlja2V0".to_owned())
+        );
+    }
+
+    #[test]
+    fn should_build_custom_token() {
+        let mut fields = BoltMap::default();
+        fields.put("principal".into(), "neo4j".into());
+        fields.put("realm".into(), "ldap".into());
+
+        let token = Auth::custom("plugin", fields).to_map();
+
+        let keys: Vec<&str> = token.value.keys().map(|k| k.value.as_str()).collect();
+        assert_eq!(keys, vec!["scheme", "principal", "realm"]);
+        assert_eq!(token.get::<String>("scheme"), Some("plugin".to_owned()));
+        assert_eq!(token.get::<String>("realm"), Some("ldap".to_owned()));
+    }
+
+    #[test]
+    fn should_not_debug_print_credentials() {
+        let debug = format!("{:?}", Auth::basic("neo4j", "secret"));
+        assert!(debug.contains("neo4j"));
+        assert!(!debug.contains("secret"));
+
+        assert!(!format!("{:?}", Auth::bearer("secret")).contains("secret"));
+        assert!(!format!("{:?}", Auth::kerberos("secret")).contains("secret"));
+    }
+}
@@ -0,0 +1,167 @@
+use crate::errors::{Error, Result};
+use bytes::*;
+use std::mem;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+pub const MAX_CHUNK_SIZE: usize = 65_535 - mem::size_of::<u16>();
+const END_MARKER: [u8; 2] = [0, 0];
+
+/// Reassembles a bolt message from the chunks it was split into.
+///
+/// Every chunk is prefixed with its size as a big endian u16, the message ends with a zero sized
+/// chunk. Values are free to span chunk boundaries, so the chunks are concatenated into a single
+/// contiguous buffer before they are handed to the decoders. Zero sized chunks between messages
+/// are NOOPs the server sends to keep the connection alive, they are skipped.
+pub struct ChunkedReader<'a, R> {
+    reader: &'a mut R,
+    limit: Option<usize>,
+}
+
+impl<'a, R: AsyncRead + Unpin> ChunkedReader<'a, R> {
+    pub fn new(reader: &'a mut R) -> Self {
+        ChunkedReader {
+            reader,
+            limit: None,
+        }
+    }
+
+    /// Fails with [`Error::MessageTooLarge`] before buffering a chunk which would make the
+    /// message larger than `limit` bytes. The rest of the message is left unread.
+    pub fn with_limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub async fn read_message(&mut self) -> Result<Bytes> {
+        let mut bytes = BytesMut::new();
+        let mut chunk_size = 0;
+        while chunk_size == 0 {
+            chunk_size = self.reader.read_u16().await?;
+        }
+
+        while chunk_size > 0 {
+            let start = bytes.len();
+            if let Some(limit) = self.limit.filter(|&l| start + chunk_size as usize > l) {
+                return Err(Error::MessageTooLarge { limit });
+            }
+            bytes.resize(start + chunk_size as usize, 0);
+            self.reader.read_exact(&mut bytes[start..]).await?;
+            chunk_size = self.reader.read_u16().await?;
+        }
+
+        Ok(bytes.freeze())
+    }
+}
+
+/// Splits a serialized bolt message into chunks of at most [`MAX_CHUNK_SIZE`] bytes followed by
+/// the end marker.
+pub struct ChunkedWriter<'a, W> {
+    writer: &'a mut W,
+}
+
+impl<'a, W: AsyncWrite + Unpin> ChunkedWriter<'a, W> {
+    pub fn new(writer: &'a mut W) -> Self {
+        ChunkedWriter { writer }
+    }
+
+    pub async fn write_message(&mut self, bytes: &[u8]) -> Result<()> {
+        self.writer.write_all(&chunk(bytes)).await?;
+        self.writer.flush().await?;
+        Ok(())
+    }
+}
+
+/// The chunks of a serialized message as [`ChunkedWriter`] writes them, followed by the end
+/// marker
+pub fn chunk(message: &[u8]) -> Bytes {
+    let chunks = message.len().div_ceil(MAX_CHUNK_SIZE);
+    let mut bytes = BytesMut::with_capacity(message.len() + 2 * chunks + END_MARKER.len());
+    for c in message.chunks(MAX_CHUNK_SIZE) {
+        bytes.put_u16(c.len() as u16);
+        bytes.put_slice(c);
+    }
+    bytes.put_slice(&END_MARKER);
+    bytes.freeze()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BoltString;
+    use crate::version::Version;
+
+    #[tokio::test]
+    async fn should_read_value_spanning_two_chunks() {
+        let serialized = BoltString::new("hello").into_bytes(Version::V4_1).unwrap();
+        let (first, second) = serialized.split_at(3);
+        let mut input = BytesMut::new();
+        input.put_u16(first.len() as u16);
+        input.put_slice(first);
+        input.put_u16(second.len() as u16);
+        input.put_slice(second);
+        input.put_slice(&END_MARKER);
+        let mut input: &[u8] = &input;
+
+        let mut message = ChunkedReader::new(&mut input).read_message().await.unwrap();
+
+        assert!(input.is_empty());
+        let s = BoltString::parse(Version::V4_1, &mut message).unwrap();
+        assert_eq!(s, "hello".into());
+    }
+
+    #[tokio::test]
+    async fn should_read_consecutive_messages() {
+        let mut input: &[u8] = &[
+            0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x02, 0x02, 0x03, 0x00, 0x00,
+        ];
+        let mut reader = ChunkedReader::new(&mut input);
+
+        assert_eq!(&reader.read_message().await.unwrap()[..], &[0x01]);
+        assert_eq!(&reader.read_message().await.unwrap()[..], &[0x02, 0x03]);
+    }
+
+    #[tokio::test]
+    async fn should_skip_noop_between_messages() {
+        let mut input: &[u8] = &[
+            0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00,
+        ];
+        let mut reader = ChunkedReader::new(&mut input);
+
+        assert_eq!(&reader.read_message().await.unwrap()[..], &[0x01]);
+        assert_eq!(&reader.read_message().await.unwrap()[..], &[0x02]);
+        assert!(input.is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_reject_message_above_limit() {
+        let mut input: &[u8] = &[0x00, 0x02, 0x01, 0x02, 0x00, 0x02, 0x03, 0x04, 0x00, 0x00];
+        let result = ChunkedReader::new(&mut input)
+            .with_limit(Some(3))
+            .read_message()
+            .await;
+
+        assert!(matches!(result, Err(Error::MessageTooLarge { limit: 3 })));
+        assert_eq!(input, &[0x03, 0x04, 0x00, 0x00]);
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_truncated_chunk() {
+        let mut input: &[u8] = &[0x00, 0x05, 0x01, 0x02];
+        assert!(ChunkedReader::new(&mut input).read_message().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn should_round_trip_large_message() {
+        let message = vec![7; MAX_CHUNK_SIZE + 10];
+        let mut output = Vec::new();
+        ChunkedWriter::new(&mut output)
+            .write_message(&message)
+            .await
+            .unwrap();
+
+        assert_eq!(&output[..2], &(MAX_CHUNK_SIZE as u16).to_be_bytes());
+        let mut input: &[u8] = &output;
+        let read = ChunkedReader::new(&mut input).read_message().await.unwrap();
+        assert_eq!(&read[..], &message[..]);
+    }
+}
@@ -1,17 +1,34 @@
+use crate::auth::Auth;
 pub use crate::errors::*;
+use crate::retry::Backoff;
+use crate::types::IntegerEncoding;
+use crate::uri::parse_uri;
+use crate::version::Version;
+use std::time::Duration;
 
 const DEFAULT_FETCH_SIZE: usize = 200;
 const DEFAULT_MAX_CONNECTIONS: usize = 16;
+const DEFAULT_USER_AGENT: &str = "neo4rs";
 
 /// The configuration used to connect to the database, see [`Graph::connect`]
 #[derive(Debug, Clone)]
 pub struct Config {
     pub(crate) uri: String,
-    pub(crate) user: String,
-    pub(crate) password: String,
+    pub(crate) auth: Auth,
+    pub(crate) user_agent: String,
     pub(crate) max_connections: usize,
     pub(crate) db: String,
     pub(crate) fetch_size: usize,
+    pub(crate) versions: Vec<Version>,
+    pub(crate) checkout_timeout: Option<Duration>,
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) read_timeout: Option<Duration>,
+    pub(crate) max_message_bytes: Option<usize>,
+    pub(crate) keepalive_interval: Option<Duration>,
+    pub(crate) integer_encoding: IntegerEncoding,
+    pub(crate) backoff: Backoff,
+    #[cfg(feature = "tls")]
+    pub(crate) ca_cert: Option<std::path::PathBuf>,
 }
 
 /// A builder to override default configurations and build the [`Config`]
@@ -19,9 +36,21 @@ pub struct ConfigBuilder {
     uri: Option<String>,
     user: Option<String>,
     password: Option<String>,
+    auth: Option<Auth>,
+    user_agent: String,
     db: Option<String>,
     fetch_size: Option<usize>,
     max_connections: Option<usize>,
+    versions: Vec<Version>,
+    checkout_timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    max_message_bytes: Option<usize>,
+    keepalive_interval: Option<Duration>,
+    integer_encoding: IntegerEncoding,
+    backoff: Backoff,
+    #[cfg(feature = "tls")]
+    ca_cert: Option<std::path::PathBuf>,
 }
 
 impl ConfigBuilder {
@@ -43,7 +72,20 @@ impl ConfigBuilder {
         self
     }
 
-    ///the name of the database, defaults to "neo4j" if not configured.
+    ///the authentication token sent to the server, takes precedence over `user` and `password`
+    pub fn auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    ///identifies the client to the server, defaults to "neo4rs". Since bolt 5.3 the driver
+    ///describes itself with its name, version and platform in a separate `bolt_agent` as well.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_owned();
+        self
+    }
+
+    ///the name of the database, the home database of the user is used if not configured.
     pub fn db(mut self, db: &str) -> Self {
         self.db = Some(db.to_owned());
         self
@@ -51,7 +93,7 @@ impl ConfigBuilder {
 
     ///fetch_size indicates the number of rows to fetch from server in one request, it is
     ///recommended to use a large fetch_size if you are working with large data sets.
-    ///default fetch_size is 200
+    ///default fetch_size is 200, [`crate::Query::fetch_size`] overrides it for a single query
     pub fn fetch_size(mut self, fetch_size: usize) -> Self {
         self.fetch_size = Some(fetch_size);
         self
@@ -63,25 +105,129 @@ impl ConfigBuilder {
         self
     }
 
+    ///how long to wait for a connection from the pool once all `max_connections` are in use,
+    ///waits indefinitely if not configured.
+    pub fn checkout_timeout(mut self, timeout: Duration) -> Self {
+        self.checkout_timeout = Some(timeout);
+        self
+    }
+
+    ///how long to wait for a new connection to be established including the handshake, waits
+    ///indefinitely if not configured.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    ///how long to wait for each response of the server, waits indefinitely if not configured.
+    ///queries which take longer than this before their first response fail. if not configured,
+    ///the `connection.recv_timeout_seconds` hint of the server is used when it sends one.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    ///the size a single message of the server may have, larger messages fail with
+    ///[`Error::MessageTooLarge`] instead of being buffered. messages of any size are accepted if
+    ///not configured.
+    pub fn max_message_bytes(mut self, bytes: usize) -> Self {
+        self.max_message_bytes = Some(bytes);
+        self
+    }
+
+    ///how often idle connections of the pool are reset to keep them alive, only connections which
+    ///were idle for longer than the interval are reset and those which fail the reset are
+    ///replaced. idle connections are not pinged if not configured.
+    pub fn keepalive_interval(mut self, interval: Duration) -> Self {
+        self.keepalive_interval = Some(interval);
+        self
+    }
+
+    ///how the integers of queries and other requests are encoded, defaults to the smallest
+    ///marker which fits. [`IntegerEncoding::AlwaysI64`] helps debugging with tooling that expects
+    ///a fixed width, or a server plugin which mishandles tiny integers.
+    pub fn integer_encoding(mut self, encoding: IntegerEncoding) -> Self {
+        self.integer_encoding = encoding;
+        self
+    }
+
+    ///how often a transaction of [`Graph::execute_read`] or [`Graph::execute_write`] is retried
+    ///after a retriable error, defaults to 5
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.backoff.max_retries = max_retries;
+        self
+    }
+
+    ///the delay before the first retry of a transaction, defaults to 1 second
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.backoff.initial_delay = delay;
+        self
+    }
+
+    ///the factor the retry delay grows by after every retry, defaults to 2
+    pub fn retry_multiplier(mut self, multiplier: f64) -> Self {
+        self.backoff.multiplier = multiplier;
+        self
+    }
+
+    ///the longest delay between two retries of a transaction, defaults to 30 seconds
+    pub fn max_retry_delay(mut self, delay: Duration) -> Self {
+        self.backoff.max_delay = delay;
+        self
+    }
+
+    ///path to a pem encoded CA certificate, servers connected with `bolt+s://` or `neo4j+s://`
+    ///must present a certificate signed by it. the system root certificates are trusted if not
+    ///configured. `+ssc` uris accept any certificate and ignore it.
+    #[cfg(feature = "tls")]
+    pub fn ca_cert(mut self, path: impl AsRef<std::path::Path>) -> Self {
+        self.ca_cert = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    ///the bolt versions proposed to the server in order of preference, at most four versions can
+    ///be proposed. defaults to 4.1 and 4.0
+    pub fn versions(mut self, versions: &[Version]) -> Self {
+        self.versions = versions.to_vec();
+        self
+    }
+
     pub fn build(self) -> Result<Config> {
-        if self.uri.is_none()
-            || self.user.is_none()
-            || self.password.is_none()
-            || self.fetch_size.is_none()
-            || self.max_connections.is_none()
-            || self.db.is_none()
+        if self.versions.is_empty()
+            || self.versions.len() > 4
+            || self.backoff.multiplier.is_nan()
+            || self.backoff.multiplier < 1.0
         {
-            Err(Error::InvalidConfig)
-        } else {
-            //The config attributes are validated before unwrapping
-            Ok(Config {
-                uri: self.uri.unwrap(),
-                user: self.user.unwrap(),
-                password: self.password.unwrap(),
-                fetch_size: self.fetch_size.unwrap(),
-                max_connections: self.max_connections.unwrap(),
-                db: self.db.unwrap(),
-            })
+            return Err(Error::InvalidConfig);
+        }
+        let auth = match (self.auth, self.user, self.password) {
+            (Some(auth), _, _) => auth,
+            (None, Some(user), Some(password)) => Auth::basic(&user, &password),
+            _ => return Err(Error::InvalidConfig),
+        };
+        if let Some(uri) = &self.uri {
+            parse_uri(uri)?;
+        }
+        match (self.uri, self.fetch_size, self.max_connections, self.db) {
+            (Some(uri), Some(fetch_size), Some(max_connections), Some(db)) => Ok(Config {
+                uri,
+                auth,
+                user_agent: self.user_agent,
+                fetch_size,
+                max_connections,
+                db,
+                versions: self.versions,
+                checkout_timeout: self.checkout_timeout,
+                connect_timeout: self.connect_timeout,
+                read_timeout: self.read_timeout,
+                max_message_bytes: self.max_message_bytes,
+                keepalive_interval: self.keepalive_interval,
+                integer_encoding: self.integer_encoding,
+                backoff: self.backoff,
+                #[cfg(feature = "tls")]
+                ca_cert: self.ca_cert,
+            }),
+            _ => Err(Error::InvalidConfig),
         }
     }
 }
@@ -92,9 +238,21 @@ pub fn config() -> ConfigBuilder {
         uri: None,
         user: None,
         password: None,
+        auth: None,
+        user_agent: DEFAULT_USER_AGENT.to_owned(),
         db: Some("".to_owned()),
         max_connections: Some(DEFAULT_MAX_CONNECTIONS),
         fetch_size: Some(DEFAULT_FETCH_SIZE),
+        versions: Version::supported_versions(),
+        checkout_timeout: None,
+        connect_timeout: None,
+        read_timeout: None,
+        max_message_bytes: None,
+        keepalive_interval: None,
+        integer_encoding: IntegerEncoding::Minimal,
+        backoff: Backoff::default(),
+        #[cfg(feature = "tls")]
+        ca_cert: None,
     }
 }
 
@@ -111,14 +269,38 @@ mod tests {
             .db("some_db")
             .fetch_size(10)
             .max_connections(5)
+            .checkout_timeout(Duration::from_secs(3))
+            .connect_timeout(Duration::from_secs(5))
+            .read_timeout(Duration::from_secs(30))
+            .max_message_bytes(1 << 20)
+            .keepalive_interval(Duration::from_secs(60))
+            .integer_encoding(IntegerEncoding::AlwaysI64)
+            .max_retries(2)
+            .retry_delay(Duration::from_millis(10))
+            .retry_multiplier(1.5)
+            .max_retry_delay(Duration::from_secs(1))
             .build()
             .unwrap();
         assert_eq!(config.uri, "127.0.0.1:7687");
-        assert_eq!(config.user, "some_user");
-        assert_eq!(config.password, "some_password");
+        assert_eq!(config.auth, Auth::basic("some_user", "some_password"));
         assert_eq!(config.db, "some_db");
         assert_eq!(config.fetch_size, 10);
         assert_eq!(config.max_connections, 5);
+        assert_eq!(config.checkout_timeout, Some(Duration::from_secs(3)));
+        assert_eq!(config.connect_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(config.read_timeout, Some(Duration::from_secs(30)));
+        assert_eq!(config.max_message_bytes, Some(1 << 20));
+        assert_eq!(config.keepalive_interval, Some(Duration::from_secs(60)));
+        assert_eq!(config.integer_encoding, IntegerEncoding::AlwaysI64);
+        assert_eq!(
+            config.backoff,
+            Backoff {
+                max_retries: 2,
+                initial_delay: Duration::from_millis(10),
+                multiplier: 1.5,
+                max_delay: Duration::from_secs(1),
+            }
+        );
     }
 
     #[tokio::test]
@@ -130,11 +312,39 @@ mod tests {
             .build()
             .unwrap();
         assert_eq!(config.uri, "127.0.0.1:7687");
-        assert_eq!(config.user, "some_user");
-        assert_eq!(config.password, "some_password");
+        assert_eq!(config.auth, Auth::basic("some_user", "some_password"));
+        assert_eq!(config.user_agent, "neo4rs");
         assert_eq!(config.db, "");
         assert_eq!(config.fetch_size, 200);
         assert_eq!(config.max_connections, 16);
+        assert_eq!(config.checkout_timeout, None);
+        assert_eq!(config.connect_timeout, None);
+        assert_eq!(config.read_timeout, None);
+        assert_eq!(config.max_message_bytes, None);
+        assert_eq!(config.keepalive_interval, None);
+        assert_eq!(config.integer_encoding, IntegerEncoding::Minimal);
+        assert_eq!(config.backoff, Backoff::default());
+        assert_eq!(config.versions, vec![Version::V4_1, Version::V4]);
+    }
+
+    #[test]
+    fn should_build_with_version_preference() {
+        let pinned = config()
+            .uri("127.0.0.1:7687")
+            .user("some_user")
+            .password("some_password")
+            .versions(&[Version::V4_4])
+            .build()
+            .unwrap();
+        assert_eq!(pinned.versions, vec![Version::V4_4]);
+
+        assert!(config()
+            .uri("127.0.0.1:7687")
+            .user("some_user")
+            .password("some_password")
+            .versions(&[])
+            .build()
+            .is_err());
     }
 
     #[tokio::test]
@@ -157,4 +367,25 @@ mod tests {
             .build()
             .is_err());
     }
+
+    #[test]
+    fn should_build_with_auth() {
+        let built = config()
+            .uri("127.0.0.1:7687")
+            .auth(Auth::none())
+            .user_agent("app/1.0")
+            .build()
+            .unwrap();
+        assert_eq!(built.auth, Auth::none());
+        assert_eq!(built.user_agent, "app/1.0");
+
+        let built = config()
+            .uri("127.0.0.1:7687")
+            .user("some_user")
+            .password("some_password")
+            .auth(Auth::none())
+            .build()
+            .unwrap();
+        assert_eq!(built.auth, Auth::none());
+    }
 }
@@ -1,90 +1,896 @@
+use crate::auth::Auth;
+use crate::chunk::{ChunkedReader, ChunkedWriter};
+use crate::config::Config;
 use crate::errors::{unexpected, Error, Result};
+use crate::handshake::Handshake;
 use crate::messages::*;
+use crate::types::{BoltMap, Encoding, IntegerEncoding};
+use crate::uri::{parse_uri, ConnectionUri};
 use crate::version::Version;
 use bytes::*;
-use std::mem;
-use tokio::io::BufStream;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use log::{debug, log_enabled, trace, warn, Level};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, BufStream, ReadBuf};
 use tokio::net::TcpStream;
 
-const MAX_CHUNK_SIZE: usize = 65_535 - mem::size_of::<u16>();
+/// The log target of query texts, which can be turned off to keep them out of the logs
+const QUERY_TARGET: &str = "neo4rs::query";
 
 #[derive(Debug)]
 pub struct Connection {
     version: Version,
-    stream: BufStream<TcpStream>,
+    stream: BufStream<Stream>,
+    read_timeout: Option<Duration>,
+    max_message_bytes: Option<usize>,
+    integer_encoding: IntegerEncoding,
+    server_agent: Option<String>,
+    connection_id: Option<String>,
+    hints: BoltMap,
+    auth: Auth,
+    /// Set once a read or write failed in the middle of a message, after which the stream is out
+    /// of step with the server, e.g. the reply to a timed out request arrives later
+    broken: bool,
+    /// When a request was last sent or a response last received
+    last_used: Instant,
+}
+
+/// The transport of a connection, either plain tcp or tcp wrapped into tls.
+#[derive(Debug)]
+enum Stream {
+    Plain(TcpStream),
+    #[cfg(feature = "tls")]
+    Tls(Box<tokio_rustls::client::TlsStream<TcpStream>>),
 }
 
 impl Connection {
-    pub async fn new(uri: &str, user: &str, password: &str) -> Result<Connection> {
-        let mut stream = BufStream::new(TcpStream::connect(uri).await?);
-        stream.write_all(&[0x60, 0x60, 0xB0, 0x17]).await?;
-        stream.write_all(&Version::supported_versions()).await?;
-        stream.flush().await?;
-        let mut response = [0, 0, 0, 0];
-        stream.read_exact(&mut response).await?;
-        let version = Version::parse(response)?;
-        let mut connection = Connection { version, stream };
-        let hello = BoltRequest::hello("neo4rs", user.to_owned(), password.to_owned());
+    pub async fn new(config: &Config) -> Result<Connection> {
+        let uri = parse_uri(&config.uri)?;
+        let addr = uri.address();
+        let connect = async {
+            let tcp = TcpStream::connect(&addr).await?;
+            let stream = if uri.is_encrypted() {
+                Self::encrypt(tcp, &uri, config).await?
+            } else {
+                Stream::Plain(tcp)
+            };
+            let mut stream = BufStream::new(stream);
+            let version = Handshake::new(&config.versions)
+                .perform(&mut stream)
+                .await?;
+            Ok((version, stream))
+        };
+        let (version, stream) =
+            within(config.connect_timeout, connect)
+                .await
+                .map_err(|e| match e {
+                    Error::Timeout(m) => Error::Timeout(format!("connecting to {}: {}", addr, m)),
+                    e => e,
+                })?;
+        let mut connection = Connection {
+            version,
+            stream,
+            read_timeout: config.read_timeout,
+            max_message_bytes: config.max_message_bytes,
+            integer_encoding: config.integer_encoding,
+            server_agent: None,
+            connection_id: None,
+            hints: BoltMap::default(),
+            auth: config.auth.clone(),
+            broken: false,
+            last_used: Instant::now(),
+        };
+        let auth = Some(&config.auth).filter(|_| !version.has_logon());
+        let hello = BoltRequest::hello(
+            &config.user_agent,
+            auth,
+            version.has_bolt_agent().then(bolt_agent),
+        );
         match connection.send_recv(hello).await? {
-            BoltResponse::SuccessMessage(_msg) => Ok(connection),
-            BoltResponse::FailureMessage(msg) => {
-                Err(Error::AuthenticationError(msg.get("message").unwrap()))
+            BoltResponse::SuccessMessage(msg) => {
+                connection.server_agent = msg.get("server");
+                connection.connection_id = msg.get("connection_id");
+                connection.hints = msg.get("hints").unwrap_or_default();
+                if let (None, Some(timeout)) = (config.read_timeout, connection.recv_timeout_hint())
+                {
+                    debug!(
+                        "using the read timeout of {:?} hinted by the server",
+                        timeout
+                    );
+                    connection.read_timeout = Some(timeout);
+                }
+                debug!(
+                    "connected to {} as {} with hints {:?}",
+                    connection.server_agent().unwrap_or("unknown server"),
+                    connection.connection_id().unwrap_or("unknown connection"),
+                    connection.hints()
+                );
+                if version.has_logon() {
+                    connection.logon(&config.auth).await?;
+                }
+                Ok(connection)
             }
-
+            BoltResponse::FailureMessage(msg) => Err(authentication_failed(msg)),
             msg => Err(unexpected(msg, "HELLO")),
         }
     }
 
+    async fn logon(&mut self, auth: &Auth) -> Result<()> {
+        match self.send_recv(BoltRequest::logon(auth)).await? {
+            BoltResponse::SuccessMessage(_) => {
+                self.auth = auth.clone();
+                Ok(())
+            }
+            BoltResponse::FailureMessage(msg) => Err(authentication_failed(msg)),
+            msg => Err(unexpected(msg, "LOGON")),
+        }
+    }
+
+    /// Switches the user of the connection with LOGOFF and LOGON, which requires bolt 5.1 or
+    /// later. Nothing is sent if the connection is authenticated with `auth` already.
+    pub(crate) async fn reauthenticate(&mut self, auth: &Auth) -> Result<()> {
+        if *auth == self.auth {
+            return Ok(());
+        }
+        if !self.version.has_logon() {
+            return Err(Error::UnsupportedVersion(format!(
+                "re-authentication requires bolt 5.1 or later, the server speaks {:?}",
+                self.version
+            )));
+        }
+        match self.send_recv(BoltRequest::logoff()).await? {
+            BoltResponse::SuccessMessage(_) => self.logon(auth).await,
+            msg => Err(unexpected(msg, "LOGOFF")),
+        }
+    }
+
+    #[cfg(feature = "tls")]
+    async fn encrypt(tcp: TcpStream, uri: &ConnectionUri, config: &Config) -> Result<Stream> {
+        let tls = crate::tls::connect(
+            tcp,
+            uri.host(),
+            config.ca_cert.as_deref(),
+            uri.trusts_any_certificate(),
+        )
+        .await?;
+        Ok(Stream::Tls(Box::new(tls)))
+    }
+
+    #[cfg(not(feature = "tls"))]
+    async fn encrypt(_: TcpStream, _: &ConnectionUri, _: &Config) -> Result<Stream> {
+        Err(Error::TlsError(
+            "encrypted connections require the tls feature".to_owned(),
+        ))
+    }
+
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// How the requests of the connection are encoded
+    pub(crate) fn encoding(&self) -> Encoding {
+        Encoding {
+            version: self.version,
+            integers: self.integer_encoding,
+        }
+    }
+
+    /// The product and version of the server as sent in reply to HELLO, like `Neo4j/5.12.0`
+    pub fn server_agent(&self) -> Option<&str> {
+        self.server_agent.as_deref()
+    }
+
+    /// The id the server assigned to the connection, like `bolt-31`, which shows up in its logs
+    pub fn connection_id(&self) -> Option<&str> {
+        self.connection_id.as_deref()
+    }
+
+    /// The configuration hints of the server, empty if it sent none
+    pub fn hints(&self) -> &BoltMap {
+        &self.hints
+    }
+
+    /// The idle time after which the server may close the connection, from the
+    /// `connection.recv_timeout_seconds` hint. Hints which are not a positive integer are ignored.
+    /// It becomes the read timeout of connections which have none configured.
+    pub fn recv_timeout_hint(&self) -> Option<Duration> {
+        self.hints
+            .get::<i64>("connection.recv_timeout_seconds")
+            .filter(|&seconds| seconds > 0)
+            .map(|seconds| Duration::from_secs(seconds as u64))
+    }
+
+    /// Whether a read or write failed midway, a broken connection cannot be used anymore.
+    pub(crate) fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    /// How long no request was sent and no response received on the connection
+    pub(crate) fn idle_for(&self) -> Duration {
+        self.last_used.elapsed()
+    }
+
+    /// Brings a connection that saw a failure back into a state where it accepts requests,
+    /// requests which were sent before and are still pending are answered with IGNORED.
     pub async fn reset(&mut self) -> Result<()> {
-        match self.send_recv(BoltRequest::reset()).await? {
-            BoltResponse::SuccessMessage(_) => Ok(()),
-            msg => Err(unexpected(msg, "RESET")),
+        self.send(BoltRequest::reset()).await?;
+        loop {
+            match self.recv().await? {
+                BoltResponse::SuccessMessage(_) => return Ok(()),
+                BoltResponse::IgnoredMessage(_) => continue,
+                msg => return Err(unexpected(msg, "RESET")),
+            }
+        }
+    }
+
+    /// Turns a failure into its error and resets the connection, the server ignores every
+    /// request after a failure until it is reset.
+    pub(crate) async fn failed(&mut self, failure: Failure) -> Error {
+        if let Err(e) = self.reset().await {
+            warn!("failed to reset connection after failure: {:?}", e);
+        }
+        failure.into_error()
+    }
+
+    /// Tells the server that the connection is closed with GOODBYE, which the server does not
+    /// answer, and shuts the connection down. Dropping a connection closes it without GOODBYE.
+    pub async fn close(mut self) -> Result<()> {
+        self.send(BoltRequest::goodbye()).await?;
+        self.stream.shutdown().await?;
+        Ok(())
+    }
+
+    /// Sends requests without waiting for their responses, with at most `depth` requests in
+    /// flight at a time.
+    pub(crate) fn pipeline(&mut self, depth: usize) -> Pipeline<'_> {
+        Pipeline {
+            connection: self,
+            depth: depth.max(1),
+            in_flight: 0,
+            replies: VecDeque::new(),
         }
     }
 
     pub async fn send_recv(&mut self, message: BoltRequest) -> Result<BoltResponse> {
+        #[cfg(feature = "tracing")]
+        if let BoltRequest::RunMessage(run) = &message {
+            use tracing::Instrument;
+            let span = self.run_span(run);
+            return self.exchange(message).instrument(span).await;
+        }
+        self.exchange(message).await
+    }
+
+    async fn exchange(&mut self, message: BoltRequest) -> Result<BoltResponse> {
         self.send(message).await?;
         self.recv().await
     }
 
+    /// The span of a RUN and its reply, the query is only recorded while the `neo4rs::query`
+    /// target is enabled
+    #[cfg(feature = "tracing")]
+    fn run_span(&self, run: &Run) -> tracing::Span {
+        let span = tracing::debug_span!(
+            "run",
+            bolt = ?self.version,
+            query = tracing::field::Empty
+        );
+        if tracing::enabled!(target: QUERY_TARGET, tracing::Level::DEBUG) {
+            span.record("query", run.query());
+        }
+        span
+    }
+
     pub async fn send(&mut self, message: BoltRequest) -> Result<()> {
-        let end_marker: [u8; 2] = [0, 0];
-        let bytes: Bytes = message.into_bytes(self.version)?;
-        for c in bytes.chunks(MAX_CHUNK_SIZE) {
-            self.stream.write_u16(c.len() as u16).await?;
-            self.stream.write_all(c).await?;
-        }
-        self.stream.write_all(&end_marker).await?;
-        self.stream.flush().await?;
-        Ok(())
+        let name = message.name();
+        match &message {
+            BoltRequest::RunMessage(run) if log_enabled!(target: QUERY_TARGET, Level::Debug) => {
+                debug!(target: QUERY_TARGET, "C: RUN {:?} with bolt {:?}", run.query(), self.version)
+            }
+            _ => debug!("C: {} with bolt {:?}", name, self.version),
+        }
+        let bytes: Bytes = message.into_bytes(self.encoding())?;
+        if name != "HELLO" && name != "LOGON" {
+            trace!("C: {:02X?}", &bytes[..]);
+        }
+        let written = ChunkedWriter::new(&mut self.stream)
+            .write_message(&bytes)
+            .await;
+        self.broken |= written.is_err();
+        self.last_used = Instant::now();
+        written
     }
 
     pub async fn recv(&mut self) -> Result<BoltResponse> {
-        let mut bytes = BytesMut::new();
-        let mut chunk_size = 0;
-        while chunk_size == 0 {
-            chunk_size = self.read_u16().await?;
+        let mut reader = ChunkedReader::new(&mut self.stream).with_limit(self.max_message_bytes);
+        let read = within(self.read_timeout, reader.read_message()).await;
+        self.broken |= read.is_err();
+        self.last_used = Instant::now();
+        let bytes = read?;
+        trace!("S: {:02X?}", &bytes[..]);
+        let response = BoltResponse::parse(self.version, bytes)?;
+        debug!("S: {}", response.name());
+        Ok(response)
+    }
+}
+
+/// Requests written ahead of their responses, which saves a round trip for every request but the
+/// last. Sending a request while `depth` requests are in flight first reads the reply to the
+/// oldest one, so the responses the server writes meanwhile never pile up unread.
+pub(crate) struct Pipeline<'a> {
+    connection: &'a mut Connection,
+    depth: usize,
+    in_flight: usize,
+    replies: VecDeque<Vec<BoltResponse>>,
+}
+
+impl Pipeline<'_> {
+    pub(crate) async fn send(&mut self, request: BoltRequest) -> Result<()> {
+        if self.in_flight == self.depth {
+            let reply = self.read_reply().await?;
+            self.replies.push_back(reply);
+        }
+        self.connection.send(request).await?;
+        self.in_flight += 1;
+        Ok(())
+    }
+
+    /// The reply to the oldest request that was not returned yet, or `None` once all requests are
+    /// answered. A reply holds the records of the request followed by the SUCCESS, FAILURE or
+    /// IGNORED that ends it.
+    pub(crate) async fn recv(&mut self) -> Result<Option<Vec<BoltResponse>>> {
+        if let Some(reply) = self.replies.pop_front() {
+            return Ok(Some(reply));
+        }
+        if self.in_flight == 0 {
+            return Ok(None);
+        }
+        self.read_reply().await.map(Some)
+    }
+
+    async fn read_reply(&mut self) -> Result<Vec<BoltResponse>> {
+        let mut reply = Vec::new();
+        loop {
+            let response = self.connection.recv().await?;
+            let done = !matches!(response, BoltResponse::RecordMessage(_));
+            reply.push(response);
+            if done {
+                self.in_flight -= 1;
+                return Ok(reply);
+            }
+        }
+    }
+
+    /// Reads the replies to all requests in flight. The server ignores the requests after a
+    /// failure, in that case the connection is reset and the error of the failure returned.
+    pub(crate) async fn finish(mut self) -> Result<()> {
+        let mut failure = None;
+        while let Some(mut reply) = self.recv().await? {
+            match reply.pop() {
+                Some(BoltResponse::SuccessMessage(_)) => {}
+                Some(BoltResponse::IgnoredMessage(_)) if failure.is_some() => {}
+                Some(BoltResponse::FailureMessage(f)) if failure.is_none() => failure = Some(f),
+                msg => return Err(unexpected(msg, "pipelined request")),
+            }
+        }
+        match failure {
+            Some(failure) => Err(self.connection.failed(failure).await),
+            None => Ok(()),
+        }
+    }
+}
+
+fn authentication_failed(failure: Failure) -> Error {
+    Error::AuthenticationError(
+        failure
+            .get("message")
+            .unwrap_or_else(|| "authentication failed".to_owned()),
+    )
+}
+
+/// Awaits `future`, failing with [`Error::Timeout`] once `timeout` passed
+async fn within<T>(
+    timeout: Option<Duration>,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, future)
+            .await
+            .map_err(|_| Error::Timeout(format!("after {:?}", timeout)))?,
+        None => future.await,
+    }
+}
+
+impl AsyncRead for Stream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Plain(s) => Pin::new(s).poll_read(cx, buf),
+            #[cfg(feature = "tls")]
+            Stream::Tls(s) => Pin::new(s).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Stream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Stream::Plain(s) => Pin::new(s).poll_write(cx, buf),
+            #[cfg(feature = "tls")]
+            Stream::Tls(s) => Pin::new(s).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Plain(s) => Pin::new(s).poll_flush(cx),
+            #[cfg(feature = "tls")]
+            Stream::Tls(s) => Pin::new(s).poll_flush(cx),
         }
+    }
 
-        while chunk_size > 0 {
-            let chunk = self.read(chunk_size).await?;
-            bytes.put_slice(&chunk);
-            chunk_size = self.read_u16().await?;
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Plain(s) => Pin::new(s).poll_shutdown(cx),
+            #[cfg(feature = "tls")]
+            Stream::Tls(s) => Pin::new(s).poll_shutdown(cx),
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::config;
+    use crate::routing::AccessMode;
+    use crate::testkit::{MockServer, Response};
+    use crate::types::{BoltMap, BoltType};
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    fn test_config(addr: &str, password: &str) -> Config {
+        config()
+            .uri(addr)
+            .user("neo4j")
+            .password(password)
+            .build()
+            .unwrap()
+    }
+
+    /// Accepts a single connection, answers the handshake with bolt 4.1 and replies to HELLO
+    /// with the given message.
+    async fn serve_hello(response: &'static [u8]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut handshake = [0; 20];
+            socket.read_exact(&mut handshake).await.unwrap();
+            assert_eq!(&handshake[..4], &[0x60, 0x60, 0xB0, 0x17]);
+            socket.write_all(&[0, 0, 1, 4]).await.unwrap();
+            let hello = ChunkedReader::new(&mut socket)
+                .read_message()
+                .await
+                .unwrap();
+            assert_eq!(&hello[..2], &[0xB1, 0x01]);
+            ChunkedWriter::new(&mut socket)
+                .write_message(response)
+                .await
+                .unwrap();
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn should_connect_with_hello() {
+        let addr = serve_hello(&[0xB1, 0x70, 0xA0]).await;
+
+        let connection = Connection::new(&test_config(&addr, "neo")).await.unwrap();
 
-        Ok(BoltResponse::parse(self.version, bytes.freeze())?)
+        assert_eq!(connection.version, Version::V4_1);
+        assert_eq!(connection.server_agent(), None);
+        assert_eq!(connection.hints(), &BoltMap::default());
     }
 
-    async fn read(&mut self, size: u16) -> Result<Vec<u8>> {
-        let mut buf = vec![0; size as usize];
-        self.stream.read_exact(&mut buf).await?;
-        Ok(buf)
+    /// A SUCCESS to HELLO with server agent, connection id and the recv timeout hint
+    fn hello_success(recv_timeout_seconds: i64) -> &'static [u8] {
+        let hints = vec![(
+            "connection.recv_timeout_seconds".into(),
+            recv_timeout_seconds.into(),
+        )];
+        let metadata: BoltMap = vec![
+            ("server".into(), "Neo4j/5.12.0".into()),
+            ("connection_id".into(), "bolt-31".into()),
+            ("hints".into(), BoltType::Map(hints.into_iter().collect())),
+        ]
+        .into_iter()
+        .collect();
+        let mut response = vec![0xB1, 0x70];
+        response.extend(metadata.into_bytes(Version::V4_1).unwrap());
+        Box::leak(response.into_boxed_slice())
+    }
+
+    #[tokio::test]
+    async fn should_store_hello_metadata() {
+        let addr = serve_hello(hello_success(120)).await;
+
+        let connection = Connection::new(&test_config(&addr, "neo")).await.unwrap();
+
+        assert_eq!(connection.server_agent(), Some("Neo4j/5.12.0"));
+        assert_eq!(connection.connection_id(), Some("bolt-31"));
+        assert_eq!(
+            connection
+                .hints()
+                .get::<i64>("connection.recv_timeout_seconds"),
+            Some(120)
+        );
+    }
+
+    #[tokio::test]
+    async fn should_apply_recv_timeout_hint_unless_configured() {
+        let addr = serve_hello(hello_success(120)).await;
+        let connection = Connection::new(&test_config(&addr, "neo")).await.unwrap();
+        assert_eq!(connection.read_timeout, Some(Duration::from_secs(120)));
+
+        let addr = serve_hello(hello_success(0)).await;
+        let connection = Connection::new(&test_config(&addr, "neo")).await.unwrap();
+        assert_eq!(connection.recv_timeout_hint(), None);
+        assert_eq!(connection.read_timeout, None);
+
+        let addr = serve_hello(hello_success(120)).await;
+        let config = config()
+            .uri(&addr)
+            .user("neo4j")
+            .password("neo")
+            .read_timeout(Duration::from_secs(5))
+            .build()
+            .unwrap();
+        let connection = Connection::new(&config).await.unwrap();
+        assert_eq!(connection.read_timeout, Some(Duration::from_secs(5)));
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_rejected_hello() {
+        let addr = serve_hello(&[
+            0xB1, 0x7F, 0xA1, 0x87, b'm', b'e', b's', b's', b'a', b'g', b'e', 0x84, b'n', b'o',
+            b'p', b'e',
+        ])
+        .await;
+
+        match Connection::new(&test_config(&addr, "wrong")).await {
+            Err(Error::AuthenticationError(message)) => assert_eq!(message, "nope"),
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_fail_on_rejected_hello_without_message() {
+        let addr = serve_hello(&[0xB1, 0x7F, 0xA0]).await;
+
+        match Connection::new(&test_config(&addr, "wrong")).await {
+            Err(Error::AuthenticationError(message)) => {
+                assert_eq!(message, "authentication failed")
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_connect_with_bolt_scheme() {
+        let addr = serve_hello(&[0xB1, 0x70, 0xA0]).await;
+
+        let uri = format!("bolt://{}/", addr);
+        let connection = Connection::new(&test_config(&uri, "neo")).await.unwrap();
+
+        assert_eq!(connection.version, Version::V4_1);
+    }
+
+    #[tokio::test]
+    async fn should_recover_from_failure_with_reset() {
+        let mut server = MockServer::start(Version::V4_1).await.unwrap();
+        server.respond(vec![Response::success(vec![])]);
+        server.respond(vec![Response::failure("", "fail")]);
+        server.respond(vec![Response::Ignored]);
+        let mut connection = Connection::new(&test_config(&server.uri(), "neo"))
+            .await
+            .unwrap();
+
+        connection
+            .send(BoltRequest::run(
+                "",
+                "RETURN 1/0",
+                BoltMap::default(),
+                AccessMode::Write,
+            ))
+            .await
+            .unwrap();
+        connection.send(BoltRequest::pull(10, -1)).await.unwrap();
+        assert!(matches!(
+            connection.recv().await.unwrap(),
+            BoltResponse::FailureMessage(_)
+        ));
+        connection.reset().await.unwrap();
+        let run = BoltRequest::run("", "RETURN 1", BoltMap::default(), AccessMode::Write);
+
+        assert!(matches!(
+            connection.send_recv(run).await.unwrap(),
+            BoltResponse::SuccessMessage(_)
+        ));
+        let signatures: Vec<u8> = std::iter::from_fn(|| server.try_next_request())
+            .map(|request| request[1])
+            .collect();
+        assert_eq!(signatures, vec![0x01, 0x10, 0x3F, 0x0F, 0x10]);
+    }
+
+    #[tokio::test]
+    async fn should_time_out_connecting_to_silent_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (_socket, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        });
+        let config = config()
+            .uri(&addr)
+            .user("neo4j")
+            .password("neo")
+            .connect_timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+
+        match Connection::new(&config).await {
+            Err(Error::Timeout(message)) => assert!(message.contains(&addr)),
+            result => panic!("unexpected result {:?}", result),
+        }
     }
 
-    async fn read_u16(&mut self) -> Result<u16> {
-        let mut data = [0, 0];
-        self.stream.read_exact(&mut data).await?;
-        Ok(u16::from_be_bytes(data))
+    /// Records the fields of the spans created while it is the default dispatcher
+    #[cfg(feature = "tracing")]
+    #[derive(Default)]
+    struct SpanFields(std::sync::Mutex<Vec<String>>);
+
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for &SpanFields {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            let field = format!("{}={:?}", field.name(), value);
+            self.0.lock().unwrap().push(field);
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for SpanFields {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            span.record(&mut &*self);
+            tracing::span::Id::from_u64(1)
+        }
+        fn record(&self, _: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+            values.record(&mut &*self);
+        }
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+        fn event(&self, _: &tracing::Event<'_>) {}
+        fn enter(&self, _: &tracing::span::Id) {}
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn should_trace_run_with_version_and_query() {
+        let server = MockServer::start(Version::V4_1).await.unwrap();
+        let mut connection = Connection::new(&test_config(&server.uri(), "neo"))
+            .await
+            .unwrap();
+        let subscriber = std::sync::Arc::new(SpanFields::default());
+        let run = BoltRequest::run("", "RETURN 1", BoltMap::default(), AccessMode::Write);
+
+        let _default = tracing::subscriber::set_default(subscriber.clone());
+        connection.send_recv(run).await.unwrap();
+
+        let fields = subscriber.0.lock().unwrap();
+        assert_eq!(*fields, vec!["bolt=V4_1", "query=\"RETURN 1\""]);
+    }
+
+    #[tokio::test]
+    async fn should_time_out_waiting_for_response() {
+        let server = MockServer::start(Version::V4_1).await.unwrap();
+        server.respond(vec![Response::success(vec![])]);
+        server.respond(vec![]);
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .read_timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let mut connection = Connection::new(&config).await.unwrap();
+
+        let result = connection.send_recv(BoltRequest::reset()).await;
+
+        assert!(matches!(result, Err(Error::Timeout(_))));
+        assert!(connection.is_broken());
+    }
+
+    #[tokio::test]
+    async fn should_say_goodbye_on_close() {
+        let mut server = MockServer::start(Version::V4_1).await.unwrap();
+        let connection = Connection::new(&test_config(&server.uri(), "neo"))
+            .await
+            .unwrap();
+
+        connection.close().await.unwrap();
+
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB1, 0x01]);
+        assert_eq!(server.next_request().await.unwrap()[..], [0xB0, 0x02]);
+    }
+
+    #[tokio::test]
+    async fn should_read_pipelined_replies_in_order() {
+        let server = MockServer::start(Version::V4_1).await.unwrap();
+        server.respond(vec![Response::success(vec![])]);
+        server.respond(vec![Response::success(vec![])]);
+        server.respond(vec![
+            Response::record(vec![1.into()]),
+            Response::record(vec![1.into()]),
+            Response::success(vec![]),
+        ]);
+        let mut connection = Connection::new(&test_config(&server.uri(), "neo"))
+            .await
+            .unwrap();
+        let mut pipeline = connection.pipeline(1);
+
+        pipeline
+            .send(BoltRequest::run(
+                "",
+                "RETURN 1",
+                BoltMap::default(),
+                AccessMode::Read,
+            ))
+            .await
+            .unwrap();
+        pipeline.send(BoltRequest::pull(2, -1)).await.unwrap();
+        pipeline.send(BoltRequest::discard()).await.unwrap();
+
+        let mut replies = Vec::new();
+        while let Some(reply) = pipeline.recv().await.unwrap() {
+            replies.push(reply.len());
+        }
+        assert_eq!(replies, vec![1, 3, 1]);
+    }
+
+    #[tokio::test]
+    async fn should_logon_after_hello_on_bolt_5_1() {
+        let mut server = MockServer::start(Version::V5_1).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .versions(&[Version::V5_1])
+            .build()
+            .unwrap();
+        let mut connection = Connection::new(&config).await.unwrap();
+        let run = BoltRequest::run("", "RETURN 1", BoltMap::default(), AccessMode::Read);
+        connection.send_recv(run).await.unwrap();
+
+        let hello = server.next_request().await.unwrap();
+        let logon = server.next_request().await.unwrap();
+        let run = server.next_request().await.unwrap();
+        assert_eq!((hello[1], logon[1], run[1]), (0x01, 0x6A, 0x10));
+        let extra = BoltMap::parse(Version::V5_1, &mut hello.slice(2..)).unwrap();
+        assert_eq!(extra.get::<String>("user_agent"), Some(config.user_agent));
+        assert_eq!(extra.get::<String>("credentials"), None);
+        assert_eq!(extra.get::<BoltMap>("bolt_agent"), None);
+        let auth = BoltMap::parse(Version::V5_1, &mut logon.slice(2..)).unwrap();
+        assert_eq!(auth, Auth::basic("neo4j", "neo").to_map());
+    }
+
+    #[tokio::test]
+    async fn should_reauthenticate_with_logoff_and_logon() {
+        let mut server = MockServer::start(Version::V5_1).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .versions(&[Version::V5_1])
+            .build()
+            .unwrap();
+        let mut connection = Connection::new(&config).await.unwrap();
+        let alice = Auth::basic("alice", "secret");
+
+        connection.reauthenticate(&config.auth).await.unwrap();
+        connection.reauthenticate(&alice).await.unwrap();
+        connection.reauthenticate(&alice).await.unwrap();
+
+        let mut signatures = Vec::new();
+        for _ in 0..4 {
+            signatures.push(server.next_request().await.unwrap());
+        }
+        let signatures: Vec<u8> = signatures.iter().map(|request| request[1]).collect();
+        assert_eq!(signatures, vec![0x01, 0x6A, 0x6B, 0x6A]);
+        assert_eq!(connection.auth, alice);
+    }
+
+    #[tokio::test]
+    async fn should_reject_reauthentication_before_bolt_5_1() {
+        let addr = serve_hello(&[0xB1, 0x70, 0xA0]).await;
+        let mut connection = Connection::new(&test_config(&addr, "neo")).await.unwrap();
+
+        connection
+            .reauthenticate(&Auth::basic("neo4j", "neo"))
+            .await
+            .unwrap();
+        assert!(matches!(
+            connection.reauthenticate(&Auth::none()).await,
+            Err(Error::UnsupportedVersion(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_send_bolt_agent_on_bolt_5_3() {
+        let mut server = MockServer::start(Version::V5_3).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .user_agent("billing-service/2.1")
+            .versions(&[Version::V5_3])
+            .build()
+            .unwrap();
+        Connection::new(&config).await.unwrap();
+
+        let hello = server.next_request().await.unwrap();
+        let extra = BoltMap::parse(Version::V5_3, &mut hello.slice(2..)).unwrap();
+        assert_eq!(
+            extra.get::<String>("user_agent").as_deref(),
+            Some("billing-service/2.1")
+        );
+        let bolt_agent: BoltMap = extra.get("bolt_agent").unwrap();
+        assert_eq!(
+            bolt_agent.get::<String>("product"),
+            Some(format!("neo4rs/{}", env!("CARGO_PKG_VERSION")))
+        );
+        assert_eq!(
+            bolt_agent.get::<String>("language").as_deref(),
+            Some("Rust")
+        );
+        assert!(bolt_agent
+            .get::<String>("platform")
+            .unwrap()
+            .starts_with(std::env::consts::OS));
+    }
+
+    #[tokio::test]
+    async fn should_send_integers_as_i64_if_configured() {
+        let mut server = MockServer::start(Version::V4_4).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .integer_encoding(IntegerEncoding::AlwaysI64)
+            .versions(&[Version::V4_4])
+            .build()
+            .unwrap();
+        let mut connection = Connection::new(&config).await.unwrap();
+        server.next_request().await.unwrap();
+
+        connection.send(BoltRequest::pull(5, -1)).await.unwrap();
+
+        let pull = server.next_request().await.unwrap();
+        let n = [0x81, b'n', 0xCB, 0, 0, 0, 0, 0, 0, 0, 0x05];
+        assert!(pull.windows(n.len()).any(|window| window == n));
+        let extra = BoltMap::parse(Version::V4_4, &mut pull.slice(2..)).unwrap();
+        assert_eq!(extra.get::<i64>("n"), Some(5));
     }
 }
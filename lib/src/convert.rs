@@ -9,14 +9,17 @@ impl TryFrom<BoltType> for f64 {
     fn try_from(input: BoltType) -> Result<f64> {
         match input {
             BoltType::Float(t) => Ok(t.value),
-            _ => Err(Error::ConverstionError),
+            _ => Err(Error::UnexpectedType {
+                expected: BoltKind::Float,
+                found: input.kind(),
+            }),
         }
     }
 }
 
-impl Into<BoltType> for f64 {
-    fn into(self) -> BoltType {
-        BoltType::Float(BoltFloat::new(self))
+impl From<f64> for BoltType {
+    fn from(value: f64) -> BoltType {
+        BoltType::Float(BoltFloat::new(value))
     }
 }
 
@@ -26,18 +29,40 @@ impl TryFrom<BoltType> for i64 {
     fn try_from(input: BoltType) -> Result<i64> {
         match input {
             BoltType::Integer(t) => Ok(t.value),
-            _ => Err(Error::ConverstionError),
+            _ => Err(Error::UnexpectedType {
+                expected: BoltKind::Integer,
+                found: input.kind(),
+            }),
         }
     }
 }
 
+macro_rules! narrow_integer {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<BoltType> for $t {
+                type Error = Error;
+
+                fn try_from(input: BoltType) -> Result<$t> {
+                    BoltInteger::new(i64::try_from(input)?).narrow()
+                }
+            }
+        )*
+    };
+}
+
+narrow_integer!(i8, i16, i32, u16, u32, u64, usize);
+
 impl TryFrom<BoltType> for bool {
     type Error = Error;
 
     fn try_from(input: BoltType) -> Result<bool> {
         match input {
             BoltType::Boolean(t) => Ok(t.value),
-            _ => Err(Error::ConverstionError),
+            _ => Err(Error::UnexpectedType {
+                expected: BoltKind::Boolean,
+                found: input.kind(),
+            }),
         }
     }
 }
@@ -58,12 +83,13 @@ impl TryFrom<BoltType> for std::time::Duration {
 
     fn try_from(input: BoltType) -> Result<std::time::Duration> {
         match input {
-            BoltType::Duration(d) => Ok(d.into()),
+            BoltType::Duration(d) => d.try_into(),
             _ => Err(Error::ConverstionError),
         }
     }
 }
 
+#[cfg(feature = "chrono")]
 impl TryFrom<BoltType> for chrono::NaiveDate {
     type Error = Error;
 
@@ -75,6 +101,7 @@ impl TryFrom<BoltType> for chrono::NaiveDate {
     }
 }
 
+#[cfg(feature = "chrono")]
 impl TryFrom<BoltType> for chrono::DateTime<chrono::FixedOffset> {
     type Error = Error;
 
@@ -86,6 +113,7 @@ impl TryFrom<BoltType> for chrono::DateTime<chrono::FixedOffset> {
     }
 }
 
+#[cfg(feature = "chrono")]
 impl TryFrom<BoltType> for chrono::NaiveDateTime {
     type Error = Error;
 
@@ -97,25 +125,27 @@ impl TryFrom<BoltType> for chrono::NaiveDateTime {
     }
 }
 
+#[cfg(feature = "chrono")]
 impl TryFrom<BoltType> for (chrono::NaiveTime, Option<chrono::FixedOffset>) {
     type Error = Error;
 
     fn try_from(input: BoltType) -> Result<(chrono::NaiveTime, Option<chrono::FixedOffset>)> {
         match input {
             BoltType::Time(bolt_time) => {
-                let (time, offset) = bolt_time.into();
+                let (time, offset) = bolt_time.try_into()?;
                 if offset.local_minus_utc() == 0 {
                     Ok((time, None))
                 } else {
                     Ok((time, Some(offset)))
                 }
             }
-            BoltType::LocalTime(d) => Ok((d.into(), None)),
+            BoltType::LocalTime(d) => Ok((d.try_into()?, None)),
             _ => Err(Error::ConverstionError),
         }
     }
 }
 
+#[cfg(feature = "chrono")]
 impl TryFrom<BoltType> for (chrono::NaiveDateTime, String) {
     type Error = Error;
 
@@ -127,13 +157,28 @@ impl TryFrom<BoltType> for (chrono::NaiveDateTime, String) {
     }
 }
 
+#[cfg(feature = "chrono-tz")]
+impl TryFrom<BoltType> for chrono::DateTime<chrono_tz::Tz> {
+    type Error = Error;
+
+    fn try_from(input: BoltType) -> Result<chrono::DateTime<chrono_tz::Tz>> {
+        match input {
+            BoltType::DateTimeZoneId(date_time_zone_id) => date_time_zone_id.try_into(),
+            _ => Err(Error::ConverstionError),
+        }
+    }
+}
+
 impl TryFrom<BoltType> for Vec<u8> {
     type Error = Error;
 
     fn try_from(input: BoltType) -> Result<Vec<u8>> {
         match input {
             BoltType::Bytes(b) => Ok(b.value.to_vec()),
-            _ => Err(Error::ConverstionError),
+            _ => Err(Error::UnexpectedType {
+                expected: BoltKind::Bytes,
+                found: input.kind(),
+            }),
         }
     }
 }
@@ -198,7 +243,23 @@ impl TryFrom<BoltType> for BoltList {
     fn try_from(input: BoltType) -> Result<BoltList> {
         match input {
             BoltType::List(l) => Ok(l),
-            _ => Err(Error::ConverstionError),
+            _ => Err(Error::UnexpectedType {
+                expected: BoltKind::List,
+                found: input.kind(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<BoltType> for BoltMap {
+    type Error = Error;
+    fn try_from(input: BoltType) -> Result<BoltMap> {
+        match input {
+            BoltType::Map(m) => Ok(m),
+            _ => Err(Error::UnexpectedType {
+                expected: BoltKind::Map,
+                found: input.kind(),
+            }),
         }
     }
 }
@@ -208,7 +269,10 @@ impl TryFrom<BoltType> for BoltString {
     fn try_from(input: BoltType) -> Result<BoltString> {
         match input {
             BoltType::String(s) => Ok(s),
-            _ => Err(Error::ConverstionError),
+            _ => Err(Error::UnexpectedType {
+                expected: BoltKind::String,
+                found: input.kind(),
+            }),
         }
     }
 }
@@ -218,7 +282,10 @@ impl TryFrom<BoltType> for String {
     fn try_from(input: BoltType) -> Result<String> {
         match input {
             BoltType::String(t) => Ok(t.value),
-            _ => Err(Error::ConverstionError),
+            _ => Err(Error::UnexpectedType {
+                expected: BoltKind::String,
+                found: input.kind(),
+            }),
         }
     }
 }
@@ -229,63 +296,94 @@ impl Into<BoltType> for std::time::Duration {
     }
 }
 
+#[cfg(feature = "chrono")]
 impl Into<BoltType> for chrono::NaiveDate {
     fn into(self) -> BoltType {
         BoltType::Date(self.into())
     }
 }
 
+#[cfg(feature = "chrono")]
 impl Into<BoltType> for chrono::NaiveTime {
     fn into(self) -> BoltType {
         BoltType::LocalTime(self.into())
     }
 }
 
+#[cfg(feature = "chrono")]
 impl Into<BoltType> for chrono::NaiveDateTime {
     fn into(self) -> BoltType {
         BoltType::LocalDateTime(self.into())
     }
 }
 
+#[cfg(feature = "chrono")]
 impl Into<BoltType> for chrono::DateTime<chrono::FixedOffset> {
     fn into(self) -> BoltType {
         BoltType::DateTime(self.into())
     }
 }
 
+#[cfg(feature = "chrono")]
 impl Into<BoltType> for (chrono::NaiveTime, chrono::FixedOffset) {
     fn into(self) -> BoltType {
         BoltType::Time(self.into())
     }
 }
 
+#[cfg(feature = "chrono")]
 impl Into<BoltType> for (chrono::NaiveDateTime, &str) {
     fn into(self) -> BoltType {
         BoltType::DateTimeZoneId(self.into())
     }
 }
 
+#[cfg(feature = "chrono-tz")]
+impl From<chrono::DateTime<chrono_tz::Tz>> for BoltType {
+    fn from(datetime: chrono::DateTime<chrono_tz::Tz>) -> BoltType {
+        BoltType::DateTimeZoneId(datetime.into())
+    }
+}
+
 impl Into<BoltType> for Vec<u8> {
     fn into(self) -> BoltType {
         BoltType::Bytes(BoltBytes::new(self.into()))
     }
 }
 
-impl Into<BoltType> for i64 {
-    fn into(self) -> BoltType {
-        BoltType::Integer(BoltInteger::new(self))
+impl From<i64> for BoltType {
+    fn from(value: i64) -> BoltType {
+        BoltType::Integer(BoltInteger::new(value))
     }
 }
 
-impl Into<BoltType> for String {
-    fn into(self) -> BoltType {
-        BoltType::String(self.into())
+impl From<i32> for BoltType {
+    fn from(value: i32) -> BoltType {
+        BoltType::Integer(value.into())
     }
 }
 
-impl Into<BoltType> for &str {
-    fn into(self) -> BoltType {
-        BoltType::String(self.into())
+impl From<bool> for BoltType {
+    fn from(value: bool) -> BoltType {
+        BoltType::Boolean(BoltBoolean::new(value))
+    }
+}
+
+impl From<String> for BoltType {
+    fn from(value: String) -> BoltType {
+        BoltType::String(value.into())
+    }
+}
+
+impl From<&str> for BoltType {
+    fn from(value: &str) -> BoltType {
+        BoltType::String(value.into())
+    }
+}
+
+impl<A: Into<BoltType>> From<Option<A>> for BoltType {
+    fn from(value: Option<A>) -> BoltType {
+        value.map_or(BoltType::null(), Into::into)
     }
 }
 
@@ -300,21 +398,114 @@ impl<A: TryFrom<BoltType>> TryFrom<BoltType> for Vec<A> {
                 .iter()
                 .flat_map(|x| A::try_from(x.clone()))
                 .collect()),
-            _ => Err(Error::ConverstionError),
+            _ => Err(Error::UnexpectedType {
+                expected: BoltKind::List,
+                found: input.kind(),
+            }),
         }
     }
 }
 
-impl<A: Into<BoltType> + Clone> Into<BoltType> for Vec<A> {
-    fn into(self) -> BoltType {
+impl<A: Into<BoltType>> From<Vec<A>> for BoltType {
+    fn from(value: Vec<A>) -> BoltType {
         BoltType::List(BoltList {
-            value: self.iter().map(|v| v.clone().into()).collect(),
+            value: value.into_iter().map(Into::into).collect(),
         })
     }
 }
 
-impl Into<BoltType> for BoltMap {
-    fn into(self) -> BoltType {
-        BoltType::Map(self)
+impl From<BoltMap> for BoltType {
+    fn from(value: BoltMap) -> BoltType {
+        BoltType::Map(value)
+    }
+}
+
+macro_rules! nullable {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<BoltType> for Option<$t> {
+                type Error = Error;
+
+                fn try_from(input: BoltType) -> Result<Option<$t>> {
+                    match input {
+                        BoltType::Null(_) => Ok(None),
+                        value => value.try_into().map(Some),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+nullable!(i64, i32, f64, bool, String, BoltList, BoltMap);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_into_rust_types() {
+        assert_eq!(i64::try_from(BoltType::from(42)).unwrap(), 42);
+        assert_eq!(i32::try_from(BoltType::from(-7)).unwrap(), -7);
+        assert_eq!(u16::try_from(BoltType::from(65_535)).unwrap(), 65_535);
+        assert_eq!(f64::try_from(BoltType::from(1.5)).unwrap(), 1.5);
+        assert!(bool::try_from(BoltType::from(true)).unwrap());
+        assert_eq!(String::try_from(BoltType::from("Mark")).unwrap(), "Mark");
+    }
+
+    #[test]
+    fn should_reject_mismatched_types() {
+        assert!(matches!(
+            i64::try_from(BoltType::from("42")),
+            Err(Error::UnexpectedType {
+                expected: BoltKind::Integer,
+                found: BoltKind::String
+            })
+        ));
+        assert!(matches!(
+            i32::try_from(BoltType::from(1.0)),
+            Err(Error::UnexpectedType {
+                expected: BoltKind::Integer,
+                found: BoltKind::Float
+            })
+        ));
+        assert!(matches!(
+            String::try_from(BoltType::from(true)),
+            Err(Error::UnexpectedType { .. })
+        ));
+    }
+
+    #[test]
+    fn should_reject_integers_that_do_not_fit() {
+        let big = BoltType::from(i64::from(i32::MAX) + 1);
+        assert!(matches!(
+            i32::try_from(big),
+            Err(Error::IntegerOverflow {
+                value: 2_147_483_648,
+                target: "i32"
+            })
+        ));
+        assert_eq!(
+            i32::try_from(BoltType::from(i64::from(i32::MIN))).unwrap(),
+            i32::MIN
+        );
+        assert!(matches!(
+            u64::try_from(BoltType::from(-1)),
+            Err(Error::IntegerOverflow { .. })
+        ));
+        assert_eq!(
+            i8::try_from(BoltType::from(300)).unwrap_err().to_string(),
+            "integer 300 does not fit into i8"
+        );
+        assert_eq!(i8::try_from(BoltType::from(100)).unwrap(), 100);
+    }
+
+    #[test]
+    fn should_convert_null_into_none() {
+        let none: Option<i64> = BoltType::Null(BoltNull).try_into().unwrap();
+        let some: Option<String> = BoltType::from("Mark").try_into().unwrap();
+        assert_eq!(none, None);
+        assert_eq!(some, Some("Mark".to_owned()));
+        assert!(Option::<bool>::try_from(BoltType::from(1)).is_err());
     }
 }
@@ -0,0 +1,326 @@
+use crate::errors::{Error, Result};
+use crate::types::*;
+use serde::de::{
+    DeserializeOwned, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess,
+    Visitor,
+};
+use serde::forward_to_deserialize_any;
+use std::fmt::Display;
+
+/// The error reported to serde while deserializing, it is converted into
+/// [`Error::DeserializationError`] before it is returned to the caller.
+#[derive(Debug)]
+pub struct DeError(String);
+
+impl Display for DeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for DeError {}
+
+impl serde::de::Error for DeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        DeError(msg.to_string())
+    }
+}
+
+impl From<DeError> for Error {
+    fn from(e: DeError) -> Self {
+        Error::DeserializationError(e.0)
+    }
+}
+
+impl BoltType {
+    /// Deserializes the value into any type implementing [`serde::Deserialize`].
+    ///
+    /// Maps, nodes and relations deserialize into structs using their properties, lists into
+    /// sequences and [`BoltNull`] into `None`.
+    pub fn to<T: DeserializeOwned>(self) -> Result<T> {
+        Ok(T::deserialize(self)?)
+    }
+}
+
+impl<'de> Deserializer<'de> for BoltType {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, DeError> {
+        match self {
+            BoltType::Null(_) => visitor.visit_unit(),
+            BoltType::Boolean(b) => visitor.visit_bool(b.value),
+            BoltType::Integer(i) => visitor.visit_i64(i.value),
+            BoltType::Float(f) => visitor.visit_f64(f.value),
+            BoltType::String(s) => visitor.visit_string(s.value),
+            BoltType::Bytes(b) => visitor.visit_byte_buf(b.value.to_vec()),
+            BoltType::List(l) => visitor.visit_seq(BoltSeqAccess::new(l)),
+            BoltType::Map(m) => visitor.visit_map(BoltMapAccess::new(m)),
+            BoltType::Node(n) => visitor.visit_map(BoltMapAccess::new(n.properties)),
+            BoltType::Relation(r) => visitor.visit_map(BoltMapAccess::new(r.properties)),
+            BoltType::UnboundedRelation(r) => visitor.visit_map(BoltMapAccess::new(r.properties)),
+            other => Err(DeError(format!("can not deserialize {:?}", other))),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, DeError> {
+        match self {
+            BoltType::Null(_) => visitor.visit_none(),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> std::result::Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> std::result::Result<V::Value, DeError> {
+        match self {
+            BoltType::String(s) => visitor.visit_enum(s.value.into_deserializer()),
+            other => Err(DeError(format!("can not deserialize {:?} as enum", other))),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// Deserializes the columns of a row, into tuples and sequences by position and into anything
+/// else by the column names.
+pub(crate) struct RowDeserializer {
+    columns: BoltMap,
+}
+
+impl RowDeserializer {
+    pub(crate) fn new(columns: BoltMap) -> Self {
+        RowDeserializer { columns }
+    }
+
+    fn values(self) -> BoltList {
+        self.columns
+            .value
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+impl<'de> Deserializer<'de> for RowDeserializer {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, DeError> {
+        visitor.visit_map(BoltMapAccess::new(self.columns))
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, DeError> {
+        visitor.visit_seq(BoltSeqAccess::new(self.values()))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> std::result::Result<V::Value, DeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> std::result::Result<V::Value, DeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct map struct enum
+        identifier ignored_any
+    }
+}
+
+struct BoltSeqAccess {
+    values: std::vec::IntoIter<BoltType>,
+}
+
+impl BoltSeqAccess {
+    fn new(list: BoltList) -> Self {
+        BoltSeqAccess {
+            values: list.value.into_iter(),
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for BoltSeqAccess {
+    type Error = DeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> std::result::Result<Option<T::Value>, DeError> {
+        match self.values.next() {
+            Some(value) => seed.deserialize(value).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+struct BoltMapAccess {
+    entries: indexmap::map::IntoIter<BoltString, BoltType>,
+    value: Option<BoltType>,
+}
+
+impl BoltMapAccess {
+    fn new(map: BoltMap) -> Self {
+        BoltMapAccess {
+            entries: map.value.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for BoltMapAccess {
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> std::result::Result<Option<K::Value>, DeError> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(BoltType::String(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> std::result::Result<V::Value, DeError> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(value),
+            None => Err(DeError("value requested before key".to_owned())),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Person {
+        name: String,
+        age: i32,
+        nickname: Option<String>,
+        email: Option<String>,
+        scores: Vec<i64>,
+        address: Address,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Address {
+        city: String,
+    }
+
+    fn person_properties() -> BoltMap {
+        let address: BoltMap = vec![("city".into(), "Berlin".into())].into_iter().collect();
+        vec![
+            ("name".into(), "Mark".into()),
+            ("age".into(), 42.into()),
+            ("nickname".into(), BoltType::Null(BoltNull)),
+            ("scores".into(), vec![1_i64, 2].into()),
+            ("address".into(), address.into()),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn should_deserialize_map_into_struct() {
+        let person: Person = BoltType::Map(person_properties()).to().unwrap();
+
+        assert_eq!(
+            person,
+            Person {
+                name: "Mark".to_owned(),
+                age: 42,
+                nickname: None,
+                email: None,
+                scores: vec![1, 2],
+                address: Address {
+                    city: "Berlin".to_owned()
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn should_deserialize_node_properties_into_struct() {
+        let node = BoltNode::new(1.into(), vec!["Person".into()].into(), person_properties());
+
+        let person: Person = BoltType::Node(node).to().unwrap();
+
+        assert_eq!(person.name, "Mark");
+        assert_eq!(person.address.city, "Berlin");
+    }
+
+    #[test]
+    fn should_deserialize_list_into_vec() {
+        let list = BoltType::List(vec!["a".into(), "b".into()].into());
+        let values: Vec<String> = list.to().unwrap();
+        assert_eq!(values, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn should_fail_on_integer_overflow() {
+        let result = BoltType::Integer(BoltInteger::new(300)).to::<u8>();
+        assert!(matches!(result, Err(Error::DeserializationError(_))));
+        assert_eq!(
+            BoltType::Integer(BoltInteger::new(200)).to::<u8>().unwrap(),
+            200
+        );
+    }
+
+    #[test]
+    fn should_fail_on_type_mismatch() {
+        let result = BoltType::String("a".into()).to::<i64>();
+        assert!(matches!(result, Err(Error::DeserializationError(_))));
+    }
+}
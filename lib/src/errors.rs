@@ -1,28 +1,134 @@
+use crate::types::BoltKind;
+use std::fmt;
+use std::sync::Arc;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
-#[derive(Debug)]
+/// The errors of the driver, which are cheap to clone to keep or hand on the last error of a retry.
+#[derive(Debug, Clone)]
 pub enum Error {
-    IOError { detail: String },
+    IOError {
+        detail: String,
+        source: Arc<std::io::Error>,
+    },
     ConnectionError,
+    PoolTimeout,
+    Timeout(String),
     StringTooLong,
     MapTooBig,
     BytesTooBig,
     ListTooLong,
+    StructureTooBig,
+    MessageTooLarge {
+        limit: usize,
+    },
     InvalidConfig,
+    InvalidUri(String),
+    InvalidParameter {
+        name: String,
+        reason: String,
+    },
     UnsupportedVersion(String),
     UnexpectedMessage(String),
+    UnexpectedRowCount(String),
     UnknownType(String),
     UnknownMessage(String),
     ConverstionError,
+    IntegerOverflow {
+        value: i64,
+        target: &'static str,
+    },
     AuthenticationError(String),
+    TlsError(String),
+    ServiceUnavailable(String),
+    Neo4j {
+        code: String,
+        message: String,
+    },
     InvalidTypeMarker(String),
+    InvalidStructureSignature {
+        found: u8,
+    },
+    UnexpectedType {
+        expected: BoltKind,
+        found: BoltKind,
+    },
     DeserializationError(String),
+    SerializationError(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IOError { detail, .. } => write!(f, "io error: {}", detail),
+            Error::ConnectionError => write!(f, "connection error"),
+            Error::PoolTimeout => write!(f, "timed out waiting for a connection from the pool"),
+            Error::Timeout(m) => write!(f, "timed out {}", m),
+            Error::StringTooLong => write!(f, "string too long"),
+            Error::MapTooBig => write!(f, "map too big"),
+            Error::BytesTooBig => write!(f, "bytes too big"),
+            Error::ListTooLong => write!(f, "list too long"),
+            Error::StructureTooBig => write!(f, "structure has too many fields"),
+            Error::MessageTooLarge { limit } => {
+                write!(f, "message exceeds the limit of {} bytes", limit)
+            }
+            Error::InvalidConfig => write!(f, "invalid config"),
+            Error::InvalidUri(u) => write!(f, "invalid uri {}", u),
+            Error::InvalidParameter { name, reason } => {
+                write!(f, "invalid parameter {}: {}", name, reason)
+            }
+            Error::UnsupportedVersion(v) => write!(f, "unsupported version: {}", v),
+            Error::UnexpectedMessage(m) => write!(f, "{}", m),
+            Error::UnexpectedRowCount(m) => write!(f, "{}", m),
+            Error::UnknownType(t) => write!(f, "unknown type: {}", t),
+            Error::UnknownMessage(m) => write!(f, "unknown message: {}", m),
+            Error::ConverstionError => write!(f, "conversion error"),
+            Error::IntegerOverflow { value, target } => {
+                write!(f, "integer {} does not fit into {}", value, target)
+            }
+            Error::AuthenticationError(m) => write!(f, "authentication error: {}", m),
+            Error::TlsError(m) => write!(f, "tls error: {}", m),
+            Error::ServiceUnavailable(m) => write!(f, "service unavailable: {}", m),
+            Error::Neo4j { code, message } => write!(f, "{}: {}", code, message),
+            Error::InvalidTypeMarker(m) => write!(f, "invalid type marker: {}", m),
+            Error::InvalidStructureSignature { found } => {
+                write!(f, "invalid structure signature {:#04X}", found)
+            }
+            Error::UnexpectedType { expected, found } => {
+                write!(f, "expected {:?} but found {:?}", expected, found)
+            }
+            Error::DeserializationError(m) => write!(f, "deserialization error: {}", m),
+            Error::SerializationError(m) => write!(f, "serialization error: {}", m),
+        }
+    }
 }
 
 impl std::convert::From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
         Error::IOError {
             detail: e.to_string(),
+            source: Arc::new(e),
+        }
+    }
+}
+
+impl std::convert::From<std::str::Utf8Error> for Error {
+    fn from(e: std::str::Utf8Error) -> Self {
+        Error::DeserializationError(format!("invalid utf-8: {}", e))
+    }
+}
+
+impl std::convert::From<std::string::FromUtf8Error> for Error {
+    fn from(e: std::string::FromUtf8Error) -> Self {
+        e.utf8_error().into()
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::IOError { source, .. } => Some(source.as_ref()),
+            _ => None,
         }
     }
 }
@@ -31,7 +137,18 @@ impl std::convert::From<deadpool::managed::PoolError<Error>> for Error {
     fn from(e: deadpool::managed::PoolError<Error>) -> Self {
         match e {
             deadpool::managed::PoolError::Backend(e) => e,
-            _ => Error::ConnectionError,
+            deadpool::managed::PoolError::Timeout(_) => Error::PoolTimeout,
+        }
+    }
+}
+
+impl Error {
+    /// The classification of an error reported by the server, the second part of its code like
+    /// `ClientError` or `TransientError`.
+    pub fn classification(&self) -> Option<&str> {
+        match self {
+            Error::Neo4j { code, .. } => code.split('.').nth(1),
+            _ => None,
         }
     }
 }
@@ -42,3 +159,77 @@ pub fn unexpected<T: std::fmt::Debug>(response: T, request: &str) -> Error {
         request, response
     ))
 }
+
+/// The error for a marker that does not belong to the `expected` kind, reserved markers are
+/// reported as [`Error::InvalidTypeMarker`].
+pub(crate) fn unexpected_marker(expected: BoltKind, marker: u8) -> Error {
+    match crate::types::marker_kind(marker) {
+        Some(found) => Error::UnexpectedType { expected, found },
+        None => Error::InvalidTypeMarker(format!("invalid {:?} marker {:#04X}", expected, marker)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_display_unexpected_type() {
+        let error = Error::UnexpectedType {
+            expected: BoltKind::String,
+            found: BoltKind::Integer,
+        };
+        assert_eq!(error.to_string(), "expected String but found Integer");
+    }
+
+    #[test]
+    fn should_classify_server_errors() {
+        let error = Error::Neo4j {
+            code: "Neo.ClientError.Statement.SyntaxError".to_owned(),
+            message: "Invalid input".to_owned(),
+        };
+        assert_eq!(error.classification(), Some("ClientError"));
+        assert_eq!(
+            error.to_string(),
+            "Neo.ClientError.Statement.SyntaxError: Invalid input"
+        );
+        assert_eq!(Error::ConnectionError.classification(), None);
+    }
+
+    #[test]
+    fn should_classify_unexpected_marker() {
+        assert!(matches!(
+            unexpected_marker(BoltKind::String, 0x01),
+            Error::UnexpectedType {
+                expected: BoltKind::String,
+                found: BoltKind::Integer
+            }
+        ));
+        assert!(matches!(
+            unexpected_marker(BoltKind::String, 0xE0),
+            Error::InvalidTypeMarker(_)
+        ));
+    }
+
+    #[test]
+    fn should_keep_io_error_as_source_of_clones() {
+        let error: Error = std::io::Error::new(std::io::ErrorKind::BrokenPipe, "closed").into();
+        let last = error.clone();
+
+        assert_eq!(last.to_string(), "io error: closed");
+        let source = std::error::Error::source(&last)
+            .and_then(|e| e.downcast_ref::<std::io::Error>())
+            .unwrap();
+        assert_eq!(source.kind(), std::io::ErrorKind::BrokenPipe);
+        assert!(std::error::Error::source(&Error::ConnectionError).is_none());
+    }
+
+    #[test]
+    fn should_convert_utf8_errors() {
+        let error: Error = String::from_utf8(vec![0xC3, 0x28]).unwrap_err().into();
+        assert_eq!(
+            error.to_string(),
+            "deserialization error: invalid utf-8: invalid utf-8 sequence of 1 bytes from index 0"
+        );
+    }
+}
@@ -1,16 +1,25 @@
 use crate::config::{config, Config};
 use crate::errors::*;
-use crate::pool::{create_pool, ConnectionPool};
+use crate::pool::{create_pool, ConnectionPool, KeepAlive, ManagedConnection};
 use crate::query::Query;
+use crate::retry::with_retry;
+use crate::routing::AccessMode;
+use crate::row::Row;
+use crate::session::Session;
 use crate::stream::RowStream;
-use crate::txn::Txn;
+use crate::summary::Counters;
+use crate::txn::{Txn, TxnOptions};
+use crate::types::BoltMap;
+use futures::future::BoxFuture;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-/// A neo4j database abstraction
+/// A neo4j database abstraction, cloning is cheap as all clones share the same connection pool
+#[derive(Clone)]
 pub struct Graph {
     config: Config,
     pool: ConnectionPool,
+    _keepalive: Option<Arc<KeepAlive>>,
 }
 
 /// Returns a [`Query`] which provides methods like [`Query::param`] to add parameters to the query
@@ -23,7 +32,12 @@ impl Graph {
     /// [`config`]
     pub async fn connect(config: Config) -> Result<Self> {
         let pool = create_pool(&config).await;
-        Ok(Graph { config, pool })
+        let keepalive = KeepAlive::spawn(&config, &pool).map(Arc::new);
+        Ok(Graph {
+            config,
+            pool,
+            _keepalive: keepalive,
+        })
     }
 
     /// Connects to the database with default configurations
@@ -35,8 +49,54 @@ impl Graph {
     /// Starts a new transaction, all queries that needs to be run/executed within the transaction
     /// should be executed using either [`Txn::run`] or [`Txn::execute`]
     pub async fn start_txn(&self) -> Result<Txn> {
+        self.start_txn_with(TxnOptions::default()).await
+    }
+
+    /// Starts a new transaction with `options`, see [`Graph::start_txn`]
+    pub async fn start_txn_with(&self, options: TxnOptions) -> Result<Txn> {
         let connection = self.pool.get().await?;
-        Txn::new(self.config.clone(), connection).await
+        Txn::new(self.config.clone(), connection, options).await
+    }
+
+    /// Creates a [`Session`] whose transactions see the writes of the transactions committed in
+    /// the session before
+    pub fn session(&self) -> Session {
+        Session::new(self.clone())
+    }
+
+    /// Runs `work` in a transaction and commits it, the transaction is retried with exponential
+    /// backoff if it fails with a retriable error such as a deadlock, see
+    /// [`ConfigBuilder::max_retries`]. `work` may thus be called several times.
+    ///
+    /// ```no_run
+    /// # async fn example(graph: neo4rs::Graph) -> neo4rs::Result<()> {
+    /// graph
+    ///     .execute_write(|txn| {
+    ///         Box::pin(async move { txn.run("CREATE (p:Person {name: 'Mark'})").await })
+    ///     })
+    ///     .await
+    /// # }
+    /// ```
+    pub async fn execute_write<T, F>(&self, work: F) -> Result<T>
+    where
+        F: for<'a> FnMut(&'a Txn) -> BoxFuture<'a, Result<T>>,
+    {
+        with_retry(&self.config.backoff, || self.start_txn(), work).await
+    }
+
+    /// Runs `work` in a transaction which begins in read mode and is retried like the ones of
+    /// [`Graph::execute_write`]. The single server of a graph runs it like any other transaction,
+    /// a [`crate::RoutingDriver`] picks a reader for it instead.
+    pub async fn execute_read<T, F>(&self, work: F) -> Result<T>
+    where
+        F: for<'a> FnMut(&'a Txn) -> BoxFuture<'a, Result<T>>,
+    {
+        with_retry(
+            &self.config.backoff,
+            || self.start_txn_with(TxnOptions::default().with_mode(AccessMode::Read)),
+            work,
+        )
+        .await
     }
 
     /// Runs a query using a connection from the connection pool, it doesn't return any
@@ -45,14 +105,103 @@ impl Graph {
     /// Use [`Graph::run`] for cases where you just want a write operation
     ///
     /// use [`Graph::execute`] when you are interested in the result stream
-    pub async fn run(&self, q: Query) -> Result<()> {
+    pub async fn run(&self, q: impl Into<Query>) -> Result<()> {
         let connection = Arc::new(Mutex::new(self.pool.get().await?));
-        q.run(&self.config, connection).await
+        q.into().run(&self.config, connection).await
     }
 
     /// Executes a query and returns a [`RowStream`]
-    pub async fn execute(&self, q: Query) -> Result<RowStream> {
+    pub async fn execute(&self, q: impl Into<Query>) -> Result<RowStream> {
+        let connection = Arc::new(Mutex::new(self.pool.get().await?));
+        q.into().execute(&self.config, connection).await
+    }
+
+    /// Executes a query and returns its first row, or `None` if the result is empty. Only the
+    /// first row is pulled from the server, the rest of the result is discarded.
+    pub async fn execute_one(&self, q: impl Into<Query>) -> Result<Option<Row>> {
+        let connection = Arc::new(Mutex::new(self.pool.get().await?));
+        q.into().fetch_one(&self.config, connection).await
+    }
+
+    /// Executes a query which is expected to return a single row, fails with
+    /// [`Error::UnexpectedRowCount`] if the result is empty or has more than one row.
+    pub async fn execute_exactly_one(&self, q: impl Into<Query>) -> Result<Row> {
         let connection = Arc::new(Mutex::new(self.pool.get().await?));
-        q.execute(&self.config, connection).await
+        q.into().fetch_exactly_one(&self.config, connection).await
+    }
+
+    /// Inserts `rows` in batches within a single transaction, which is committed once all
+    /// batches succeeded, see [`Txn::bulk_unwind`]
+    pub async fn bulk_unwind(
+        &self,
+        query: &str,
+        rows: impl IntoIterator<Item = BoltMap>,
+        batch_size: usize,
+    ) -> Result<Counters> {
+        let txn = self.start_txn().await?;
+        let counters = txn.bulk_unwind(query, rows, batch_size).await?;
+        txn.commit().await?;
+        Ok(counters)
+    }
+
+    /// Closes the idle connections of the pool with GOODBYE, so that the server frees their
+    /// resources right away. Connections which are still in use are dropped without GOODBYE.
+    pub async fn close(self) -> Result<()> {
+        while self.pool.status().available > 0 {
+            let connection = self.pool.try_get().await?;
+            ManagedConnection::take(connection).close().await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::MockServer;
+    use crate::version::Version;
+
+    #[tokio::test]
+    async fn should_say_goodbye_on_idle_connections() {
+        let mut server = MockServer::start(Version::V4_1).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+        graph.run("RETURN 1").await.unwrap();
+
+        graph.close().await.unwrap();
+
+        let mut signatures = Vec::new();
+        for _ in 0..5 {
+            signatures.push(server.next_request().await.unwrap()[1]);
+        }
+        assert_eq!(signatures, vec![0x01, 0x10, 0x2F, 0x0F, 0x02]);
+    }
+
+    #[tokio::test]
+    async fn should_begin_read_transaction_in_read_mode() {
+        let mut server = MockServer::start(Version::V4_1).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+
+        graph
+            .execute_read(|txn| Box::pin(async move { txn.run("MATCH (n) RETURN n").await }))
+            .await
+            .unwrap();
+
+        let _hello = server.next_request().await.unwrap();
+        assert_eq!(
+            server.next_request().await.unwrap()[..],
+            [0xB1, 0x11, 0xA1, 0x84, b'm', b'o', b'd', b'e', 0x81, b'r']
+        );
     }
 }
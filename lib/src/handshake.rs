@@ -0,0 +1,126 @@
+use crate::errors::{Error, Result};
+use crate::version::Version;
+use bytes::*;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+pub const MAGIC: [u8; 4] = [0x60, 0x60, 0xB0, 0x17];
+const MAX_PROPOSALS: usize = 4;
+
+/// Negotiates the bolt version with the server.
+///
+/// The client sends the magic preamble followed by exactly four version proposals in order of
+/// preference, unused slots are zero. The server answers with the version it picked or zero if it
+/// supports none of them.
+pub struct Handshake<'a> {
+    versions: &'a [Version],
+}
+
+impl<'a> Handshake<'a> {
+    pub fn new(versions: &'a [Version]) -> Self {
+        Handshake { versions }
+    }
+
+    pub fn to_bytes(&self) -> Result<Bytes> {
+        if self.versions.is_empty() || self.versions.len() > MAX_PROPOSALS {
+            return Err(Error::InvalidConfig);
+        }
+        let mut bytes = BytesMut::with_capacity(MAGIC.len() + MAX_PROPOSALS * 4);
+        bytes.put_slice(&MAGIC);
+        for version in self.versions {
+            bytes.put_u32(version.as_u32());
+        }
+        bytes.put_bytes(0, (MAX_PROPOSALS - self.versions.len()) * 4);
+        Ok(bytes.freeze())
+    }
+
+    pub async fn perform<S: AsyncRead + AsyncWrite + Unpin>(
+        &self,
+        stream: &mut S,
+    ) -> Result<Version> {
+        stream.write_all(&self.to_bytes()?).await?;
+        stream.flush().await?;
+        let mut response = [0, 0, 0, 0];
+        stream.read_exact(&mut response).await?;
+        let version = Version::parse(response)?;
+        if self.versions.contains(&version) {
+            Ok(version)
+        } else {
+            Err(Error::UnsupportedVersion(format!(
+                "server picked version {:?} which was not proposed",
+                version
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_serialize_proposals() {
+        let bytes = Handshake::new(&[Version::V4_4, Version::V4_1])
+            .to_bytes()
+            .unwrap();
+
+        assert_eq!(
+            &bytes[..],
+            &[0x60, 0x60, 0xB0, 0x17, 0, 0, 4, 4, 0, 0, 1, 4, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_preference_list() {
+        assert!(matches!(
+            Handshake::new(&[]).to_bytes(),
+            Err(Error::InvalidConfig)
+        ));
+        let versions = [
+            Version::V4_4,
+            Version::V4_1,
+            Version::V4,
+            Version::V4,
+            Version::V4,
+        ];
+        assert!(matches!(
+            Handshake::new(&versions).to_bytes(),
+            Err(Error::InvalidConfig)
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_negotiate_version() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        server.write_all(&[0, 0, 4, 4]).await.unwrap();
+
+        let version = Handshake::new(&[Version::V4_4, Version::V4_1])
+            .perform(&mut client)
+            .await
+            .unwrap();
+
+        assert_eq!(version, Version::V4_4);
+        let mut sent = [0; 20];
+        server.read_exact(&mut sent).await.unwrap();
+        assert_eq!(&sent[..8], &[0x60, 0x60, 0xB0, 0x17, 0, 0, 4, 4]);
+    }
+
+    #[tokio::test]
+    async fn should_fail_when_server_rejects_all_versions() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        server.write_all(&[0, 0, 0, 0]).await.unwrap();
+
+        let result = Handshake::new(&[Version::V4_1]).perform(&mut client).await;
+
+        assert!(matches!(result, Err(Error::UnsupportedVersion(_))));
+    }
+
+    #[tokio::test]
+    async fn should_fail_when_server_picks_unproposed_version() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        server.write_all(&[0, 0, 4, 4]).await.unwrap();
+
+        let result = Handshake::new(&[Version::V4_1]).perform(&mut client).await;
+
+        assert!(matches!(result, Err(Error::UnsupportedVersion(_))));
+    }
+}
@@ -0,0 +1,328 @@
+use crate::errors::{Error, Result};
+use crate::row::Row;
+use crate::types::*;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use serde_json::{json, Map, Number, Value};
+use std::convert::{TryFrom, TryInto};
+
+impl BoltType {
+    /// Converts the value into json for inspection, the mapping is lossy but stable:
+    ///
+    /// * nodes become `{"id", "labels", "properties"}`, relationships
+    ///   `{"id", "type", "start", "end", "properties"}` and paths `{"nodes", "relationships"}`
+    /// * temporal values become ISO-8601 strings, with the zone id appended in brackets
+    /// * points become `{"srid", "x", "y"}` with `"z"` for 3D points
+    /// * bytes become a list of numbers, NaN and infinite floats become `null`
+    ///
+    /// Integers are emitted as json numbers even beyond 2^53, which javascript can not represent
+    /// exactly and rounds when parsing.
+    pub fn to_json(&self) -> Value {
+        match self {
+            BoltType::Null(_) => Value::Null,
+            BoltType::Boolean(b) => Value::Bool(b.value),
+            BoltType::Integer(i) => Value::Number(i.value.into()),
+            BoltType::Float(f) => float(f),
+            BoltType::String(s) => Value::String(s.value.clone()),
+            BoltType::Bytes(b) => b.value.iter().map(|&byte| Value::from(byte)).collect(),
+            BoltType::List(list) => list.value.iter().map(BoltType::to_json).collect(),
+            BoltType::Map(map) => Value::Object(object(map)),
+            BoltType::Node(node) => node_json(node),
+            BoltType::Relation(rel) => json!({
+                "id": rel.id.value,
+                "type": rel.typ.value,
+                "start": rel.start_node_id.value,
+                "end": rel.end_node_id.value,
+                "properties": object(&rel.properties),
+            }),
+            BoltType::UnboundedRelation(rel) => json!({
+                "id": rel.id.value,
+                "type": rel.typ.value,
+                "properties": object(&rel.properties),
+            }),
+            BoltType::Path(path) => path_json(path),
+            BoltType::Point2D(p) => json!({
+                "srid": p.sr_id.value,
+                "x": float(&p.x),
+                "y": float(&p.y),
+            }),
+            BoltType::Point3D(p) => json!({
+                "srid": p.sr_id.value,
+                "x": float(&p.x),
+                "y": float(&p.y),
+                "z": float(&p.z),
+            }),
+            BoltType::Duration(d) => Value::String(duration(d)),
+            BoltType::Date(d) => iso(d.clone().try_into().map(|d: NaiveDate| d.to_string())),
+            BoltType::Time(t) => iso(t
+                .clone()
+                .try_into()
+                .map(|(time, offset): (NaiveTime, FixedOffset)| format!("{}{}", time, offset))),
+            BoltType::LocalTime(t) => iso(t.clone().try_into().map(|t: NaiveTime| t.to_string())),
+            BoltType::DateTime(d) => iso(d
+                .clone()
+                .try_into()
+                .map(|d: DateTime<FixedOffset>| d.to_rfc3339())),
+            BoltType::LocalDateTime(d) => iso(d
+                .clone()
+                .try_into()
+                .map(|d: NaiveDateTime| d.format("%Y-%m-%dT%H:%M:%S%.f").to_string())),
+            BoltType::DateTimeZoneId(d) => {
+                iso(d
+                    .clone()
+                    .try_into()
+                    .map(|(datetime, zone): (NaiveDateTime, String)| {
+                        format!("{}[{}]", datetime.format("%Y-%m-%dT%H:%M:%S%.f"), zone)
+                    }))
+            }
+            BoltType::Structure(s) => json!({
+                "signature": s.signature,
+                "fields": s.fields.value.iter().map(BoltType::to_json).collect::<Vec<_>>(),
+            }),
+        }
+    }
+}
+
+impl Row {
+    /// Converts the row into a json object with a key for every column, see
+    /// [`BoltType::to_json`] for how the values are mapped.
+    pub fn to_json(&self) -> Value {
+        let columns = self.keys().into_iter().enumerate().map(|(index, key)| {
+            let value = self
+                .get_by_index::<BoltType>(index)
+                .map_or(Value::Null, |value| value.to_json());
+            (key.to_owned(), value)
+        });
+        Value::Object(columns.collect())
+    }
+}
+
+/// Converts json into a bolt value, for example parameters received as json. Objects become maps
+/// and arrays lists. Numbers become integers if they are whole numbers in the range of an `i64`,
+/// including floats like `3.0`, and floats otherwise. Integers above `i64::MAX` can not be
+/// sent and fail with [`Error::SerializationError`].
+impl TryFrom<Value> for BoltType {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<BoltType> {
+        let value = match value {
+            Value::Null => BoltType::null(),
+            Value::Bool(b) => b.into(),
+            Value::Number(n) => number(&n)?,
+            Value::String(s) => s.into(),
+            Value::Array(values) => BoltType::List(
+                values
+                    .into_iter()
+                    .map(BoltType::try_from)
+                    .collect::<Result<Vec<_>>>()?
+                    .into(),
+            ),
+            Value::Object(entries) => BoltType::Map(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| Ok((key.into(), BoltType::try_from(value)?)))
+                    .collect::<Result<BoltMap>>()?,
+            ),
+        };
+        Ok(value)
+    }
+}
+
+fn number(n: &Number) -> Result<BoltType> {
+    if let Some(i) = n.as_i64() {
+        return Ok(i.into());
+    }
+    if n.is_u64() {
+        return Err(Error::SerializationError(format!(
+            "{} does not fit into a 64 bit integer",
+            n
+        )));
+    }
+    let f = n.as_f64().unwrap_or(f64::NAN);
+    // i64::MAX is not a float, the float it rounds to is 2^63 which is out of range
+    if f.fract() == 0.0 && f >= i64::MIN as f64 && f < i64::MAX as f64 {
+        Ok((f as i64).into())
+    } else {
+        Ok(f.into())
+    }
+}
+
+fn float(f: &BoltFloat) -> Value {
+    Number::from_f64(f.value).map_or(Value::Null, Value::Number)
+}
+
+fn object(map: &BoltMap) -> Map<String, Value> {
+    map.value
+        .iter()
+        .map(|(key, value)| (key.value.clone(), value.to_json()))
+        .collect()
+}
+
+fn node_json(node: &BoltNode) -> Value {
+    json!({
+        "id": node.id.value,
+        "labels": node.labels.value.iter().map(BoltType::to_json).collect::<Vec<_>>(),
+        "properties": object(&node.properties),
+    })
+}
+
+/// The nodes of the path in the order they are traversed, relationships carry their actual
+/// direction. Paths which reference nodes or relationships they do not contain are emitted as
+/// they were received.
+fn path_json(path: &BoltPath) -> Value {
+    let segments = match path.segments() {
+        Ok(segments) => segments,
+        Err(_) => {
+            return json!({
+                "nodes": BoltType::List(path.nodes.clone()).to_json(),
+                "relationships": BoltType::List(path.rels.clone()).to_json(),
+            })
+        }
+    };
+    let mut nodes: Vec<Value> = path
+        .nodes
+        .value
+        .first()
+        .map(BoltType::to_json)
+        .into_iter()
+        .collect();
+    let mut relationships = Vec::new();
+    for (_, rel, end) in segments {
+        relationships.push(BoltType::Relation(rel).to_json());
+        nodes.push(node_json(&end));
+    }
+    json!({ "nodes": nodes, "relationships": relationships })
+}
+
+/// The ISO-8601 representation of a temporal value, values chrono can not represent are `null`
+fn iso(formatted: crate::errors::Result<String>) -> Value {
+    formatted.map_or(Value::Null, Value::String)
+}
+
+/// The duration in ISO-8601, e.g. `P14M3DT12.5S`
+fn duration(d: &BoltDuration) -> String {
+    let nanos = i128::from(d.seconds()) * 1_000_000_000 + i128::from(d.nanoseconds());
+    let sign = if nanos < 0 { "-" } else { "" };
+    let (seconds, fraction) = (nanos.abs() / 1_000_000_000, nanos.abs() % 1_000_000_000);
+    let seconds = if fraction == 0 {
+        format!("{}{}", sign, seconds)
+    } else {
+        let fraction = format!("{:09}", fraction);
+        format!("{}{}.{}", sign, seconds, fraction.trim_end_matches('0'))
+    };
+    format!("P{}M{}DT{}S", d.months(), d.days(), seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_node_row_to_json() {
+        let node = BoltNode::new(
+            42.into(),
+            vec!["Person".into()].into(),
+            vec![
+                ("name".into(), "Mark".into()),
+                ("height".into(), 1.82.into()),
+                ("tags".into(), BoltType::List(vec!["admin".into()].into())),
+                ("big".into(), i64::MAX.into()),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let row = Row::new(
+            vec!["n".into(), "born".into()].into(),
+            vec![BoltType::Node(node), BoltType::Date(BoltDate::new(0))].into(),
+        );
+
+        assert_eq!(
+            row.to_json(),
+            json!({
+                "n": {
+                    "id": 42,
+                    "labels": ["Person"],
+                    "properties": {
+                        "name": "Mark",
+                        "height": 1.82,
+                        "tags": ["admin"],
+                        "big": 9_223_372_036_854_775_807_i64,
+                    },
+                },
+                "born": "1970-01-01",
+            })
+        );
+    }
+
+    #[test]
+    fn should_format_durations_in_iso_8601() {
+        let duration = |seconds: i64, nanos: i64| {
+            BoltType::Duration(BoltDuration::new(
+                14.into(),
+                3.into(),
+                seconds.into(),
+                nanos.into(),
+            ))
+            .to_json()
+        };
+
+        assert_eq!(duration(12, 500_000_000), json!("P14M3DT12.5S"));
+        assert_eq!(duration(-2, 500_000_000), json!("P14M3DT-1.5S"));
+        assert_eq!(duration(0, 0), json!("P14M3DT0S"));
+        assert_eq!(BoltType::from(f64::NAN).to_json(), Value::Null);
+    }
+
+    #[test]
+    fn should_convert_nested_json_into_bolt() {
+        let value = json!({
+            "name": "Mark",
+            "age": 42,
+            "height": 1.82,
+            "score": 3.0,
+            "nickname": null,
+            "active": true,
+            "tags": ["admin", 7],
+            "address": {"city": "Dresden", "zip": 1067},
+        });
+
+        let bolt = BoltType::try_from(value).unwrap();
+
+        let address: BoltMap = vec![
+            ("city".into(), "Dresden".into()),
+            ("zip".into(), 1067.into()),
+        ]
+        .into_iter()
+        .collect();
+        let expected: BoltMap = vec![
+            ("name".into(), "Mark".into()),
+            ("age".into(), 42.into()),
+            ("height".into(), 1.82.into()),
+            ("score".into(), 3.into()),
+            ("nickname".into(), BoltType::null()),
+            ("active".into(), true.into()),
+            (
+                "tags".into(),
+                BoltType::List(vec!["admin".into(), 7.into()].into()),
+            ),
+            ("address".into(), address.into()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(bolt, BoltType::Map(expected));
+        assert_eq!(BoltType::try_from(json!(1e300)).unwrap(), 1e300.into());
+        assert_eq!(BoltType::try_from(json!(-2.5)).unwrap(), (-2.5).into());
+    }
+
+    #[test]
+    fn should_reject_json_integers_beyond_i64() {
+        let too_big = json!(u64::MAX);
+
+        assert!(matches!(
+            BoltType::try_from(too_big),
+            Err(Error::SerializationError(_))
+        ));
+        assert_eq!(
+            BoltType::try_from(json!(i64::MIN)).unwrap(),
+            i64::MIN.into()
+        );
+    }
+}
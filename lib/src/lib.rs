@@ -748,27 +748,71 @@
 //! }
 //! ```
 //!
+//! ## Logging
 //!
+//! The messages exchanged with the server are logged with the [`log`](https://docs.rs/log)
+//! crate, `RUST_LOG=neo4rs=debug` shows the name of every message sent (`C:`) and received
+//! (`S:`) and `RUST_LOG=neo4rs=trace` their raw bytes as well. Parameters are only part of the
+//! raw bytes and HELLO, which carries the credentials, is never logged raw. The query of RUN is
+//! logged under the target `neo4rs::query`, `RUST_LOG=neo4rs=debug,neo4rs::query=off` redacts it.
+//!
+//! With the `tracing` feature the RUN of a query and its reply run in a `run` span of the
+//! [`tracing`](https://docs.rs/tracing) crate at debug level, which records the negotiated bolt
+//! version and, unless the `neo4rs::query` target is disabled, the query. The pipelined batches
+//! of [`Txn::bulk_unwind`] have no span. The feature is off by default and the span costs nothing
+//! without it.
+//!
+//!
+mod auth;
+mod chunk;
 mod config;
 mod connection;
 mod convert;
+#[cfg(feature = "serde")]
+mod de;
 mod errors;
 mod graph;
+mod handshake;
+#[cfg(feature = "json")]
+mod json;
 mod messages;
 mod pool;
 mod query;
+mod retry;
+mod routing;
 mod row;
+#[cfg(feature = "serde")]
+mod ser;
+mod session;
 mod stream;
+mod summary;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testkit;
+#[cfg(feature = "tls")]
+mod tls;
 mod txn;
+mod uri;
 mod version;
 
 pub mod types;
 
+pub use crate::auth::Auth;
 pub use crate::config::{config, Config, ConfigBuilder};
+#[cfg(feature = "serde")]
+pub use crate::de::DeError;
 pub use crate::errors::*;
 pub use crate::graph::{query, Graph};
 pub use crate::query::Query;
+pub use crate::routing::{AccessMode, RoutingDriver};
 pub use crate::row::{Node, Path, Point2D, Point3D, Relation, Row, UnboundedRelation};
+#[cfg(feature = "serde")]
+pub use crate::ser::{BoltSerializer, SerError};
+pub use crate::session::Session;
 pub use crate::stream::RowStream;
-pub use crate::txn::Txn;
+pub use crate::summary::{
+    Counters, InputPosition, Notification, PlanNode, QueryType, ResultSummary,
+};
+pub use crate::txn::{Txn, TxnOptions};
+pub use crate::types::IntegerEncoding;
+pub use crate::uri::{parse_uri, ConnectionUri};
 pub use crate::version::Version;
@@ -4,40 +4,53 @@ mod commit;
 mod discard;
 mod failure;
 mod hello;
+mod ignored;
+mod logoff;
+mod logon;
 mod pull;
 mod record;
 mod reset;
 mod rollback;
+mod route;
 mod run;
 mod success;
+use crate::auth::Auth;
 use crate::errors::*;
+use crate::routing::AccessMode;
 use crate::types::*;
 use crate::version::Version;
-use begin::Begin;
+pub(crate) use begin::Begin;
+use bye::Bye;
 use bytes::*;
 use commit::Commit;
 use discard::Discard;
-use failure::Failure;
+pub(crate) use failure::Failure;
+pub(crate) use hello::bolt_agent;
 use hello::Hello;
+use ignored::Ignored;
+use logoff::Logoff;
+use logon::Logon;
 use pull::Pull;
 use record::Record;
 use reset::Reset;
 use rollback::Rollback;
-use run::Run;
-use std::cell::RefCell;
-use std::rc::Rc;
-use success::Success;
+use route::Route;
+pub(crate) use run::Run;
+pub(crate) use success::Success;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum BoltResponse {
     SuccessMessage(Success),
     FailureMessage(Failure),
     RecordMessage(Record),
+    IgnoredMessage(Ignored),
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum BoltRequest {
     HelloMessage(Hello),
+    LogonMessage(Logon),
+    LogoffMessage(Logoff),
     RunMessage(Run),
     PullMessage(Pull),
     DiscardMessage(Discard),
@@ -45,32 +58,65 @@ pub enum BoltRequest {
     CommitMessage(Commit),
     RollbackMessage(Rollback),
     ResetMessage(Reset),
+    RouteMessage(Route),
+    ByeMessage(Bye),
 }
 
 impl BoltRequest {
-    pub fn hello(agent: &str, principal: String, credentials: String) -> BoltRequest {
+    /// The HELLO with the auth token, which is sent with LOGON instead since bolt 5.1, and the
+    /// `bolt_agent` of bolt 5.3
+    pub fn hello(agent: &str, auth: Option<&Auth>, bolt_agent: Option<BoltMap>) -> BoltRequest {
         let mut data = BoltMap::default();
         data.put("user_agent".into(), agent.into());
-        data.put("scheme".into(), "basic".into());
-        data.put("principal".into(), principal.into());
-        data.put("credentials".into(), credentials.into());
+        if let Some(bolt_agent) = bolt_agent {
+            data.put("bolt_agent".into(), bolt_agent.into());
+        }
+        for (key, value) in auth.map(Auth::to_map).unwrap_or_default().value {
+            data.put(key, value);
+        }
         BoltRequest::HelloMessage(Hello::new(data))
     }
 
-    pub fn run(db: &str, query: &str, params: BoltMap) -> BoltRequest {
-        BoltRequest::RunMessage(Run::new(db.into(), query.into(), params))
+    pub fn logon(auth: &Auth) -> BoltRequest {
+        BoltRequest::LogonMessage(Logon::new(auth.to_map()))
+    }
+
+    pub fn logoff() -> BoltRequest {
+        BoltRequest::LogoffMessage(Logoff)
+    }
+
+    pub fn run(db: &str, query: &str, params: BoltMap, mode: AccessMode) -> BoltRequest {
+        BoltRequest::RunMessage(Run::new(db.into(), query.into(), params, mode))
+    }
+
+    /// The RUN of a query with prepared parameters, which are sent along with `params`
+    pub fn run_prepared(
+        db: &str,
+        query: &str,
+        prepared: PreparedParams,
+        params: BoltMap,
+        mode: AccessMode,
+    ) -> BoltRequest {
+        BoltRequest::RunMessage(
+            Run::new(db.into(), query.into(), params, mode).with_prepared(prepared),
+        )
     }
 
-    pub fn pull(n: usize, qid: i64) -> BoltRequest {
-        BoltRequest::PullMessage(Pull::new(n as i64, qid))
+    /// Pulls the next `n` records of the query `qid`, all of them if `n` is -1
+    pub fn pull(n: i64, qid: i64) -> BoltRequest {
+        BoltRequest::PullMessage(Pull::new(n, qid))
     }
 
     pub fn discard() -> BoltRequest {
         BoltRequest::DiscardMessage(Discard::default())
     }
 
-    pub fn begin() -> BoltRequest {
-        BoltRequest::BeginMessage(Begin::new(BoltMap::default()))
+    pub fn discard_all(qid: i64) -> BoltRequest {
+        BoltRequest::DiscardMessage(Discard::new(-1, qid))
+    }
+
+    pub fn begin(extra: BoltMap) -> BoltRequest {
+        BoltRequest::BeginMessage(Begin::new(extra))
     }
 
     pub fn commit() -> BoltRequest {
@@ -84,36 +130,124 @@ impl BoltRequest {
     pub fn reset() -> BoltRequest {
         BoltRequest::ResetMessage(Reset::new())
     }
+
+    pub fn route(routing: BoltMap, db: &str) -> BoltRequest {
+        BoltRequest::RouteMessage(Route::new(routing, db))
+    }
+
+    pub fn goodbye() -> BoltRequest {
+        BoltRequest::ByeMessage(Bye)
+    }
 }
 
 impl BoltRequest {
-    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+    /// The name of the message in the bolt specification, for logging
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            BoltRequest::HelloMessage(_) => "HELLO",
+            BoltRequest::LogonMessage(_) => "LOGON",
+            BoltRequest::LogoffMessage(_) => "LOGOFF",
+            BoltRequest::RunMessage(_) => "RUN",
+            BoltRequest::PullMessage(_) => "PULL",
+            BoltRequest::DiscardMessage(_) => "DISCARD",
+            BoltRequest::BeginMessage(_) => "BEGIN",
+            BoltRequest::CommitMessage(_) => "COMMIT",
+            BoltRequest::RollbackMessage(_) => "ROLLBACK",
+            BoltRequest::ResetMessage(_) => "RESET",
+            BoltRequest::RouteMessage(_) => "ROUTE",
+            BoltRequest::ByeMessage(_) => "GOODBYE",
+        }
+    }
+
+    pub fn into_bytes(self, encoding: impl Into<Encoding>) -> Result<Bytes> {
+        let encoding = encoding.into();
         let bytes: Bytes = match self {
-            BoltRequest::HelloMessage(hello) => hello.into_bytes(version)?,
-            BoltRequest::RunMessage(run) => run.into_bytes(version)?,
-            BoltRequest::PullMessage(pull) => pull.into_bytes(version)?,
-            BoltRequest::DiscardMessage(discard) => discard.into_bytes(version)?,
-            BoltRequest::BeginMessage(begin) => begin.into_bytes(version)?,
-            BoltRequest::CommitMessage(commit) => commit.into_bytes(version)?,
-            BoltRequest::RollbackMessage(rollback) => rollback.into_bytes(version)?,
-            BoltRequest::ResetMessage(reset) => reset.into_bytes(version)?,
+            BoltRequest::HelloMessage(hello) => hello.into_bytes(encoding)?,
+            BoltRequest::LogonMessage(logon) => logon.into_bytes(encoding)?,
+            BoltRequest::LogoffMessage(logoff) => logoff.into_bytes(encoding)?,
+            BoltRequest::RunMessage(run) => run.into_bytes(encoding)?,
+            BoltRequest::PullMessage(pull) => pull.into_bytes(encoding)?,
+            BoltRequest::DiscardMessage(discard) => discard.into_bytes(encoding)?,
+            BoltRequest::BeginMessage(begin) => begin.into_bytes(encoding)?,
+            BoltRequest::CommitMessage(commit) => commit.into_bytes(encoding)?,
+            BoltRequest::RollbackMessage(rollback) => rollback.into_bytes(encoding)?,
+            BoltRequest::ResetMessage(reset) => reset.into_bytes(encoding)?,
+            BoltRequest::RouteMessage(route) => route.into_bytes(encoding)?,
+            BoltRequest::ByeMessage(bye) => bye.into_bytes(encoding)?,
         };
         Ok(bytes)
     }
+
+    /// Decodes a request as a server reads it, see [`crate::testkit::decode_message`]
+    #[cfg(any(test, feature = "test-util"))]
+    pub(crate) fn parse(version: Version, mut request: Bytes) -> Result<BoltRequest> {
+        match request {
+            ref mut input if Hello::can_parse(version, input) => {
+                Ok(BoltRequest::HelloMessage(Hello::parse(version, input)?))
+            }
+            ref mut input if Logon::can_parse(version, input) => {
+                Ok(BoltRequest::LogonMessage(Logon::parse(version, input)?))
+            }
+            ref mut input if Logoff::can_parse(version, input) => {
+                Ok(BoltRequest::LogoffMessage(Logoff::parse(version, input)?))
+            }
+            ref mut input if Run::can_parse(version, input) => {
+                Ok(BoltRequest::RunMessage(Run::parse(version, input)?))
+            }
+            ref mut input if Pull::can_parse(version, input) => {
+                Ok(BoltRequest::PullMessage(Pull::parse(version, input)?))
+            }
+            ref mut input if Discard::can_parse(version, input) => {
+                Ok(BoltRequest::DiscardMessage(Discard::parse(version, input)?))
+            }
+            ref mut input if Begin::can_parse(version, input) => {
+                Ok(BoltRequest::BeginMessage(Begin::parse(version, input)?))
+            }
+            ref mut input if Commit::can_parse(version, input) => {
+                Ok(BoltRequest::CommitMessage(Commit::parse(version, input)?))
+            }
+            ref mut input if Rollback::can_parse(version, input) => Ok(
+                BoltRequest::RollbackMessage(Rollback::parse(version, input)?),
+            ),
+            ref mut input if Reset::can_parse(version, input) => {
+                Ok(BoltRequest::ResetMessage(Reset::parse(version, input)?))
+            }
+            ref mut input if Route::can_parse(version, input) => {
+                Ok(BoltRequest::RouteMessage(Route::parse(version, input)?))
+            }
+            ref mut input if Bye::can_parse(version, input) => {
+                Ok(BoltRequest::ByeMessage(Bye::parse(version, input)?))
+            }
+            msg => Err(Error::UnknownMessage(format!("unknown message {:?}", msg))),
+        }
+    }
 }
 
 impl BoltResponse {
-    pub fn parse(version: Version, response: Bytes) -> Result<BoltResponse> {
-        match Rc::new(RefCell::new(response)) {
-            input if Success::can_parse(version, input.clone()) => Ok(
+    /// The name of the message in the bolt specification, for logging
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            BoltResponse::SuccessMessage(_) => "SUCCESS",
+            BoltResponse::FailureMessage(_) => "FAILURE",
+            BoltResponse::RecordMessage(_) => "RECORD",
+            BoltResponse::IgnoredMessage(_) => "IGNORED",
+        }
+    }
+
+    pub fn parse(version: Version, mut response: Bytes) -> Result<BoltResponse> {
+        match response {
+            ref mut input if Success::can_parse(version, input) => Ok(
                 BoltResponse::SuccessMessage(Success::parse(version, input)?),
             ),
-            input if Failure::can_parse(version, input.clone()) => Ok(
+            ref mut input if Failure::can_parse(version, input) => Ok(
                 BoltResponse::FailureMessage(Failure::parse(version, input)?),
             ),
-            input if Record::can_parse(version, input.clone()) => {
+            ref mut input if Record::can_parse(version, input) => {
                 Ok(BoltResponse::RecordMessage(Record::parse(version, input)?))
             }
+            ref mut input if Ignored::can_parse(version, input) => Ok(
+                BoltResponse::IgnoredMessage(Ignored::parse(version, input)?),
+            ),
             msg => Err(Error::UnknownMessage(format!("unknown message {:?}", msg))),
         }
     }
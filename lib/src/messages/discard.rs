@@ -27,8 +27,6 @@ mod tests {
     use super::*;
     use crate::version::Version;
     use bytes::*;
-    use std::cell::RefCell;
-    use std::rc::Rc;
 
     #[test]
     fn should_serialize_discard_message() {
@@ -36,14 +34,11 @@ mod tests {
         let bytes: Bytes = discard.into_bytes(Version::V4_1).unwrap();
         let (marker_signature, extra) = bytes.split_at(2);
         assert_eq!(marker_signature, &[0xB1, 0x2F]);
-        let extra: BoltMap = BoltMap::parse(
-            Version::V4_1,
-            Rc::new(RefCell::new(Bytes::copy_from_slice(extra))),
-        )
-        .unwrap();
+        let extra: BoltMap =
+            BoltMap::parse(Version::V4_1, &mut Bytes::copy_from_slice(extra)).unwrap();
 
-        assert_eq!(extra.get::<i64>("n").unwrap(), 42.into());
-        assert_eq!(extra.get::<i64>("qid").unwrap(), 1.into());
+        assert_eq!(extra.get::<i64>("n").unwrap(), 42);
+        assert_eq!(extra.get::<i64>("qid").unwrap(), 1);
     }
 
     #[test]
@@ -52,13 +47,10 @@ mod tests {
         let bytes: Bytes = discard.into_bytes(Version::V4_1).unwrap();
         let (marker_signature, extra) = bytes.split_at(2);
         assert_eq!(marker_signature, &[0xB1, 0x2F]);
-        let extra: BoltMap = BoltMap::parse(
-            Version::V4_1,
-            Rc::new(RefCell::new(Bytes::copy_from_slice(extra))),
-        )
-        .unwrap();
+        let extra: BoltMap =
+            BoltMap::parse(Version::V4_1, &mut Bytes::copy_from_slice(extra)).unwrap();
 
-        assert_eq!(extra.get::<i64>("n").unwrap(), 255.into());
-        assert_eq!(extra.get::<i64>("qid").unwrap(), 255.into());
+        assert_eq!(extra.get::<i64>("n").unwrap(), -1);
+        assert_eq!(extra.get::<i64>("qid").unwrap(), -1);
     }
 }
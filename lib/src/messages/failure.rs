@@ -1,16 +1,43 @@
+use crate::errors::Error;
 use crate::types::*;
 use neo4rs_macros::BoltStruct;
 
 #[derive(Debug, PartialEq, Clone, BoltStruct)]
 #[signature(0xB1, 0x7F)]
 pub struct Failure {
-    metadata: BoltMap,
+    pub metadata: BoltMap,
 }
 
 impl Failure {
     pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
         self.metadata.get(key)
     }
+
+    /// Whether the server refused the request because it can not serve it in its current cluster
+    /// role, a routing driver retries such requests on another member.
+    pub fn is_unavailable(&self) -> bool {
+        matches!(
+            self.get::<String>("code").as_deref(),
+            Some("Neo.ClientError.Cluster.NotALeader")
+                | Some("Neo.ClientError.General.ForbiddenOnReadOnlyDatabase")
+                | Some("Neo.TransientError.General.DatabaseUnavailable")
+        )
+    }
+
+    /// The error reported by the server, failures of unavailable servers become
+    /// [`Error::ServiceUnavailable`] so they can be retried elsewhere.
+    pub(crate) fn into_error(self) -> Error {
+        if self.is_unavailable() {
+            return Error::ServiceUnavailable(
+                self.get::<String>("message")
+                    .unwrap_or_else(|| "server unavailable".to_owned()),
+            );
+        }
+        Error::Neo4j {
+            code: self.get("code").unwrap_or_default(),
+            message: self.get("message").unwrap_or_default(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -18,12 +45,10 @@ mod tests {
     use super::*;
     use crate::version::Version;
     use bytes::*;
-    use std::cell::RefCell;
-    use std::rc::Rc;
 
     #[test]
     fn should_deserialize_success() {
-        let data = Bytes::from_static(&[
+        let mut data = Bytes::from_static(&[
             0xB1, 0x7F, 0xA2, 0x84, 0x63, 0x6F, 0x64, 0x65, 0xD0, 0x25, 0x4E, 0x65, 0x6F, 0x2E,
             0x43, 0x6C, 0x69, 0x65, 0x6E, 0x74, 0x45, 0x72, 0x72, 0x6F, 0x72, 0x2E, 0x53, 0x65,
             0x63, 0x75, 0x72, 0x69, 0x74, 0x79, 0x2E, 0x55, 0x6E, 0x61, 0x75, 0x74, 0x68, 0x6F,
@@ -35,7 +60,7 @@ mod tests {
             0x65, 0x2E,
         ]);
 
-        let failure: Failure = Failure::parse(Version::V4_1, Rc::new(RefCell::new(data))).unwrap();
+        let failure: Failure = Failure::parse(Version::V4_1, &mut data).unwrap();
 
         assert_eq!(
             failure.get::<String>("code").unwrap(),
@@ -45,5 +70,9 @@ mod tests {
             failure.get::<String>("message").unwrap(),
             "The client is unauthorized due to authentication failure."
         );
+        assert!(matches!(
+            failure.into_error(),
+            Error::Neo4j { code, .. } if code == "Neo.ClientError.Security.Unauthorized"
+        ));
     }
 }
@@ -13,9 +13,24 @@ impl Hello {
     }
 }
 
+/// Describes the driver to the server next to the configurable user agent, like
+/// `{product: "neo4rs/0.5.9", platform: "linux x86_64", language: "Rust"}`
+pub(crate) fn bolt_agent() -> BoltMap {
+    let product = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+    let platform = format!("{} {}", std::env::consts::OS, std::env::consts::ARCH);
+    vec![
+        ("product".into(), product.into()),
+        ("platform".into(), platform.into()),
+        ("language".into(), "Rust".into()),
+    ]
+    .into_iter()
+    .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::auth::Auth;
     use crate::version::Version;
     use bytes::*;
 
@@ -51,4 +66,55 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn should_serialize_hello_with_auth_token() {
+        let hello =
+            crate::messages::BoltRequest::hello("neo4rs", Some(&Auth::basic("neo4j", "neo")), None);
+
+        let bytes: Bytes = hello.into_bytes(Version::V4_1).unwrap();
+
+        let expected: Vec<u8> = [
+            &[0xB1, 0x01, map::TINY | 4][..],
+            &[string::TINY | 10],
+            b"user_agent",
+            &[string::TINY | 6],
+            b"neo4rs",
+            &[string::TINY | 6],
+            b"scheme",
+            &[string::TINY | 5],
+            b"basic",
+            &[string::TINY | 9],
+            b"principal",
+            &[string::TINY | 5],
+            b"neo4j",
+            &[string::TINY | 11],
+            b"credentials",
+            &[string::TINY | 3],
+            b"neo",
+        ]
+        .concat();
+        assert_eq!(bytes, Bytes::from(expected));
+    }
+
+    #[test]
+    fn should_serialize_hello_without_auth() {
+        let hello = crate::messages::BoltRequest::hello("app", Some(&Auth::none()), None);
+
+        let bytes: Bytes = hello.into_bytes(Version::V4_1).unwrap();
+
+        let expected: Vec<u8> = [
+            &[0xB1, 0x01, map::TINY | 2][..],
+            &[string::TINY | 10],
+            b"user_agent",
+            &[string::TINY | 3],
+            b"app",
+            &[string::TINY | 6],
+            b"scheme",
+            &[string::TINY | 4],
+            b"none",
+        ]
+        .concat();
+        assert_eq!(bytes, Bytes::from(expected));
+    }
 }
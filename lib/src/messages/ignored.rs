@@ -0,0 +1,23 @@
+use neo4rs_macros::BoltStruct;
+
+#[derive(Debug, PartialEq, Eq, Clone, BoltStruct)]
+#[signature(0xB0, 0x7E)]
+pub struct Ignored;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+    use bytes::*;
+
+    #[test]
+    fn should_deserialize_ignored() {
+        let mut data = Bytes::from_static(&[0xB0, 0x7E]);
+
+        assert!(Ignored::can_parse(Version::V4_1, &data));
+        let ignored = Ignored::parse(Version::V4_1, &mut data).unwrap();
+
+        assert_eq!(ignored, Ignored);
+        assert!(data.is_empty());
+    }
+}
@@ -0,0 +1,20 @@
+use neo4rs_macros::BoltStruct;
+
+#[derive(Debug, PartialEq, Eq, Clone, BoltStruct)]
+#[signature(0xB0, 0x6B)]
+/// Drops the authentication of a connection, which is authenticated again with LOGON
+pub struct Logoff;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+    use bytes::*;
+
+    #[test]
+    fn should_serialize_logoff() {
+        let bytes: Bytes = Logoff.into_bytes(Version::V5_1).unwrap();
+
+        assert_eq!(bytes, Bytes::from_static(&[0xB0, 0x6B]));
+    }
+}
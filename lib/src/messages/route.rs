@@ -0,0 +1,83 @@
+use crate::types::*;
+use neo4rs_macros::BoltStruct;
+
+#[derive(Debug, PartialEq, Clone, BoltStruct)]
+#[signature(0xB3, 0x66)]
+pub struct Route {
+    routing: BoltMap,
+    bookmarks: BoltList,
+    extra: BoltMap,
+}
+
+impl Route {
+    /// Requests the routing table of `db`, the home database is used if `db` is empty.
+    pub fn new(routing: BoltMap, db: &str) -> Route {
+        let mut extra = BoltMap::default();
+        if !db.is_empty() {
+            extra.put("db".into(), db.into());
+        }
+        Route {
+            routing,
+            bookmarks: BoltList::new(),
+            extra,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+    use bytes::*;
+
+    #[test]
+    fn should_serialize_route() {
+        let route = Route::new(
+            vec![("address".into(), "a:1".into())].into_iter().collect(),
+            "db",
+        );
+
+        let bytes: Bytes = route.into_bytes(Version::V4_4).unwrap();
+
+        assert_eq!(
+            bytes,
+            Bytes::from_static(&[
+                0xB3,
+                0x66,
+                map::TINY | 1,
+                string::TINY | 7,
+                b'a',
+                b'd',
+                b'd',
+                b'r',
+                b'e',
+                b's',
+                b's',
+                string::TINY | 3,
+                b'a',
+                b':',
+                b'1',
+                list::TINY,
+                map::TINY | 1,
+                string::TINY | 2,
+                b'd',
+                b'b',
+                string::TINY | 2,
+                b'd',
+                b'b',
+            ])
+        );
+    }
+
+    #[test]
+    fn should_omit_default_db() {
+        let route = Route::new(BoltMap::default(), "");
+
+        let bytes: Bytes = route.into_bytes(Version::V4_4).unwrap();
+
+        assert_eq!(
+            bytes,
+            Bytes::from_static(&[0xB3, 0x66, map::TINY, list::TINY, map::TINY])
+        );
+    }
+}
@@ -1,24 +1,82 @@
+use crate::errors::Result;
+use crate::routing::AccessMode;
 use crate::types::*;
+use crate::version::Version;
 use neo4rs_macros::BoltStruct;
 
 #[derive(Debug, PartialEq, Clone, BoltStruct)]
-#[signature(0xB1, 0x10)]
+#[signature(0xB3, 0x10)]
 pub struct Run {
     query: BoltString,
-    parameters: BoltMap,
+    parameters: Parameters,
     extra: BoltMap,
 }
 
+/// The parameters of a RUN, the prepared parameters of the query are sent as part of the same map
+#[derive(Debug, PartialEq, Clone)]
+pub struct Parameters {
+    prepared: Option<PreparedParams>,
+    params: BoltMap,
+}
+
+impl Parameters {
+    // only called by the derived `Run::parse`, which the driver itself never needs
+    #[allow(dead_code)]
+    pub fn parse(version: Version, input: &mut Bytes) -> Result<Parameters> {
+        Ok(Parameters {
+            prepared: None,
+            params: BoltMap::parse(version, input)?,
+        })
+    }
+}
+
+impl BoltEncode for Parameters {
+    fn encode(&self, encoding: Encoding, dst: &mut BytesMut) -> Result<()> {
+        match &self.prepared {
+            Some(prepared) => prepared.encode_with(&self.params, encoding, dst),
+            None => self.params.encode(encoding, dst),
+        }
+    }
+
+    fn encoded_len(&self, encoding: Encoding) -> usize {
+        match &self.prepared {
+            Some(prepared) => prepared.encoded_len_with(&self.params, encoding),
+            None => self.params.encoded_len(encoding),
+        }
+    }
+}
+
 impl Run {
-    pub fn new(db: BoltString, query: BoltString, parameters: BoltMap) -> Run {
+    /// Runs `query` against `db`, the home database of the user is used if `db` is empty.
+    pub fn new(db: BoltString, query: BoltString, parameters: BoltMap, mode: AccessMode) -> Run {
+        let mut extra = BoltMap::default();
+        if !db.value.is_empty() {
+            extra.put("db".into(), BoltType::String(db));
+        }
+        mode.put_into(&mut extra);
+        Run::with_extra(query, parameters, extra)
+    }
+
+    /// Runs `query` with the given extra map as it is, see [`Run::new`] for the usual fields
+    pub fn with_extra(query: BoltString, parameters: BoltMap, extra: BoltMap) -> Run {
         Run {
             query,
-            parameters,
-            extra: vec![("db".into(), BoltType::String(db))]
-                .into_iter()
-                .collect(),
+            parameters: Parameters {
+                prepared: None,
+                params: parameters,
+            },
+            extra,
         }
     }
+
+    pub(crate) fn with_prepared(mut self, prepared: PreparedParams) -> Run {
+        self.parameters.prepared = Some(prepared);
+        self
+    }
+
+    pub(crate) fn query(&self) -> &str {
+        &self.query.value
+    }
 }
 
 #[cfg(test)]
@@ -32,6 +90,7 @@ mod tests {
             "test".into(),
             "query".into(),
             vec![("k".into(), "v".into())].into_iter().collect(),
+            AccessMode::Write,
         );
 
         let bytes: Bytes = run.into_bytes(Version::V4_1).unwrap();
@@ -39,7 +98,7 @@ mod tests {
         assert_eq!(
             bytes,
             Bytes::from_static(&[
-                0xB1,
+                0xB3,
                 0x10,
                 string::TINY | 5,
                 b'q',
@@ -67,14 +126,19 @@ mod tests {
 
     #[test]
     fn should_serialize_run_with_no_params() {
-        let run = Run::new("".into(), "query".into(), BoltMap::default());
+        let run = Run::new(
+            "".into(),
+            "query".into(),
+            BoltMap::default(),
+            AccessMode::Write,
+        );
 
         let bytes: Bytes = run.into_bytes(Version::V4_1).unwrap();
 
         assert_eq!(
             bytes,
             Bytes::from_static(&[
-                0xB1,
+                0xB3,
                 0x10,
                 string::TINY | 5,
                 b'q',
@@ -82,12 +146,8 @@ mod tests {
                 b'e',
                 b'r',
                 b'y',
-                map::TINY | 0,
-                map::TINY | 1,
-                string::TINY | 2,
-                b'd',
-                b'b',
-                string::TINY | 0,
+                map::TINY,
+                map::TINY,
             ])
         );
     }
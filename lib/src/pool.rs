@@ -2,23 +2,27 @@ use crate::config::Config;
 use crate::connection::Connection;
 use crate::errors::Error;
 use async_trait::async_trait;
+use deadpool::managed::{PoolConfig, RecycleError, Timeouts};
 use log::info;
+use std::convert::TryFrom;
+use std::time::Duration;
 
 pub type ConnectionPool = deadpool::managed::Pool<Connection, Error>;
 pub type ManagedConnection = deadpool::managed::Object<Connection, Error>;
 
+tokio::task_local! {
+    /// The keepalive interval while [`ping_idle`] checks connections out
+    static PING_INTERVAL: Duration;
+}
+
 pub struct ConnectionManager {
-    uri: String,
-    user: String,
-    password: String,
+    config: Config,
 }
 
 impl ConnectionManager {
-    pub fn new(uri: &str, user: &str, password: &str) -> ConnectionManager {
+    pub fn new(config: &Config) -> ConnectionManager {
         ConnectionManager {
-            uri: uri.to_owned(),
-            user: user.to_owned(),
-            password: password.to_owned(),
+            config: config.clone(),
         }
     }
 }
@@ -27,19 +31,260 @@ impl ConnectionManager {
 impl deadpool::managed::Manager<Connection, Error> for ConnectionManager {
     async fn create(&self) -> std::result::Result<Connection, Error> {
         info!("creating new connection...");
-        Connection::new(&self.uri, &self.user, &self.password).await
+        Connection::new(&self.config).await
     }
 
     async fn recycle(&self, conn: &mut Connection) -> deadpool::managed::RecycleResult<Error> {
-        Ok(conn.reset().await?)
+        if conn.is_broken() {
+            return Err(RecycleError::Message("connection is broken".to_owned()));
+        }
+        // a connection used within the keepalive interval needs no ping, its next user resets it
+        if PING_INTERVAL
+            .try_with(|interval| conn.idle_for() < *interval)
+            .unwrap_or(false)
+        {
+            return Ok(());
+        }
+        conn.reset().await?;
+        // a transaction with its own auth leaves the connection authenticated as another user
+        Ok(conn.reauthenticate(&self.config.auth).await?)
     }
 }
 
 pub async fn create_pool(config: &Config) -> ConnectionPool {
-    let mgr = ConnectionManager::new(&config.uri, &config.user, &config.password);
+    let mgr = ConnectionManager::new(config);
     info!(
         "creating connection pool with max size {}",
         config.max_connections
     );
-    ConnectionPool::new(mgr, config.max_connections)
+    ConnectionPool::from_config(
+        mgr,
+        PoolConfig {
+            max_size: config.max_connections,
+            timeouts: Timeouts {
+                wait: config.checkout_timeout,
+                create: None,
+                recycle: None,
+            },
+        },
+    )
+}
+
+/// Resets the connections of a pool which were idle for longer than `keepalive_interval` of the
+/// config, so that load balancers do not drop them. The pings stop once the handle is dropped.
+pub(crate) struct KeepAlive {
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl KeepAlive {
+    /// Starts pinging the idle connections of `pool`, if the config enables it
+    pub(crate) fn spawn(config: &Config, pool: &ConnectionPool) -> Option<KeepAlive> {
+        let interval = config.keepalive_interval?;
+        let pool = pool.clone();
+        let task = tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            ticks.tick().await;
+            loop {
+                ticks.tick().await;
+                ping_idle(&pool, interval).await;
+            }
+        });
+        Some(KeepAlive { task })
+    }
+}
+
+impl Drop for KeepAlive {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Checks out every idle connection without waiting, the pool resets those which were idle for
+/// longer than `interval` and replaces those which fail the reset.
+async fn ping_idle(pool: &ConnectionPool, interval: Duration) {
+    let idle = usize::try_from(pool.status().available).unwrap_or(0);
+    let mut pinged = Vec::with_capacity(idle);
+    for _ in 0..idle {
+        match PING_INTERVAL.scope(interval, pool.try_get()).await {
+            Ok(connection) => pinged.push(connection),
+            Err(_) => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::config;
+    use crate::messages::BoltRequest;
+    use crate::testkit::{MockServer, Response};
+    use crate::version::Version;
+    use bytes::Bytes;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn should_share_connections_between_more_borrowers_than_pool_size() {
+        let server = MockServer::start(Version::V4_1).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .max_connections(2)
+            .build()
+            .unwrap();
+        let pool = create_pool(&config).await;
+
+        let borrowers: Vec<_> = (0..6)
+            .map(|_| {
+                let pool = pool.clone();
+                tokio::spawn(async move {
+                    let connection = pool.get().await.map_err(Error::from)?;
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    drop(connection);
+                    Ok::<_, Error>(())
+                })
+            })
+            .collect();
+        for borrower in borrowers {
+            borrower.await.unwrap().unwrap();
+        }
+
+        let status = pool.status();
+        assert_eq!(status.max_size, 2);
+        assert!(status.size <= 2);
+    }
+
+    #[tokio::test]
+    async fn should_time_out_waiting_for_a_connection() {
+        let server = MockServer::start(Version::V4_1).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .max_connections(1)
+            .checkout_timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let pool = create_pool(&config).await;
+
+        let _held = pool.get().await.map_err(Error::from).unwrap();
+        let result = pool.get().await.map_err(Error::from);
+
+        assert!(matches!(result, Err(Error::PoolTimeout)));
+    }
+
+    #[tokio::test]
+    async fn should_replace_connection_after_read_timeout() {
+        let mut server = MockServer::start(Version::V4_1).await.unwrap();
+        server.respond(vec![Response::success(vec![])]);
+        server.respond(vec![]);
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .max_connections(1)
+            .read_timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let pool = create_pool(&config).await;
+
+        let mut connection = pool.get().await.map_err(Error::from).unwrap();
+        let result = connection.send_recv(BoltRequest::reset()).await;
+        assert!(matches!(result, Err(Error::Timeout(_))));
+        assert!(connection.is_broken());
+        drop(connection);
+        let connection = pool.get().await.map_err(Error::from).unwrap();
+
+        assert!(!connection.is_broken());
+        let sent: Vec<u8> = std::iter::from_fn(|| server.try_next_request())
+            .map(|request| request[1])
+            .collect();
+        // the late reply to the timed out RESET must not be read by a RESET of the pool
+        assert_eq!(sent, vec![0x01, 0x0F, 0x01]);
+    }
+
+    #[test]
+    fn should_be_shareable_between_threads() {
+        fn assert_send_sync<T: Send + Sync + Clone>() {}
+        assert_send_sync::<ConnectionPool>();
+        assert_send_sync::<crate::Graph>();
+    }
+
+    async fn keepalive_pool(
+        script: Vec<Vec<Response>>,
+    ) -> (ConnectionPool, Option<KeepAlive>, MockServer) {
+        let server = MockServer::start(Version::V4_1).await.unwrap();
+        for responses in script {
+            server.respond(responses);
+        }
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .keepalive_interval(Duration::from_millis(30))
+            .build()
+            .unwrap();
+        let pool = create_pool(&config).await;
+        let keepalive = KeepAlive::spawn(&config, &pool);
+        drop(pool.get().await.map_err(Error::from).unwrap());
+        (pool, keepalive, server)
+    }
+
+    #[tokio::test]
+    async fn should_ping_idle_connections() {
+        let (_pool, _keepalive, mut server) = keepalive_pool(vec![]).await;
+
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB1, 0x01]);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let sent: Vec<Bytes> = std::iter::from_fn(|| server.try_next_request()).collect();
+        assert!(!sent.is_empty());
+        assert!(sent.iter().all(|request| request[..] == [0xB0, 0x0F]));
+    }
+
+    #[tokio::test]
+    async fn should_replace_connections_failing_the_ping() {
+        let (_pool, _keepalive, mut server) = keepalive_pool(vec![
+            vec![Response::success(vec![])],
+            vec![Response::failure("", "fail")],
+        ])
+        .await;
+
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB1, 0x01]);
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB0, 0x0F]);
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB1, 0x01]);
+    }
+
+    #[tokio::test]
+    async fn should_not_ping_recently_used_connections() {
+        let mut server = MockServer::start(Version::V4_1).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .build()
+            .unwrap();
+        let pool = create_pool(&config).await;
+        drop(pool.get().await.map_err(Error::from).unwrap());
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB1, 0x01]);
+
+        ping_idle(&pool, Duration::from_secs(60)).await;
+        assert!(server.try_next_request().is_none());
+
+        ping_idle(&pool, Duration::ZERO).await;
+        assert_eq!(server.try_next_request().unwrap()[..], [0xB0, 0x0F]);
+    }
+
+    #[tokio::test]
+    async fn should_not_ping_without_interval() {
+        let server = MockServer::start(Version::V4_1).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .build()
+            .unwrap();
+
+        assert!(KeepAlive::spawn(&config, &create_pool(&config).await).is_none());
+    }
 }
@@ -2,8 +2,11 @@ use crate::config::Config;
 use crate::errors::*;
 use crate::messages::*;
 use crate::pool::*;
+use crate::routing::AccessMode;
+use crate::row::Row;
 use crate::stream::*;
 use crate::types::*;
+use std::convert::TryFrom;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -12,6 +15,11 @@ use tokio::sync::Mutex;
 pub struct Query {
     query: String,
     params: BoltMap,
+    prepared: Option<PreparedParams>,
+    db: Option<String>,
+    mode: AccessMode,
+    fetch_size: Option<i64>,
+    in_txn: bool,
 }
 
 impl Query {
@@ -19,28 +27,145 @@ impl Query {
         Query {
             query,
             params: BoltMap::default(),
+            prepared: None,
+            db: None,
+            mode: AccessMode::default(),
+            fetch_size: None,
+            in_txn: false,
         }
     }
 
+    /// Runs the query against `db` instead of the database of the config, the home database of
+    /// the user is used if neither is set. Has no effect on queries of a [`crate::Txn`], whose
+    /// database is chosen when the transaction begins.
+    pub fn db(mut self, db: &str) -> Self {
+        self.db = Some(db.to_owned());
+        self
+    }
+
+    /// Tells a server which routes on its own whether the query only reads, see
+    /// [`AccessMode`]. A [`crate::RoutingDriver`] sets the mode it was called with.
+    pub fn mode(mut self, mode: AccessMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Pulls the rows of the query in batches of `fetch_size` instead of the fetch size of the
+    /// config, -1 pulls all rows at once. Sizes below 1 are treated like -1.
+    pub fn fetch_size(mut self, fetch_size: i64) -> Self {
+        self.fetch_size = Some(if fetch_size > 0 { fetch_size } else { -1 });
+        self
+    }
+
+    /// Drops the database and mode of the query, as both are chosen by BEGIN in a transaction
+    pub(crate) fn in_txn(mut self) -> Self {
+        self.db = None;
+        self.mode = AccessMode::default();
+        self.in_txn = true;
+        self
+    }
+
+    /// Binds `value` to the parameter `key`, plain rust values are converted into their bolt
+    /// type and `None` is sent as null.
+    ///
+    /// ```
+    /// use neo4rs::query;
+    ///
+    /// let q = query("CREATE (p:Person $props)")
+    ///     .param("name", "Mark")
+    ///     .param("age", 30)
+    ///     .param("id", 8_590_215_325_i64)
+    ///     .param("height", 1.82)
+    ///     .param("active", true)
+    ///     .param("tags", vec!["admin", "staff"])
+    ///     .param("nickname", None::<String>);
+    /// ```
     pub fn param<T: std::convert::Into<BoltType>>(mut self, key: &str, value: T) -> Self {
         self.params.put(key.into(), value.into());
         self
     }
 
+    /// Binds a serializable value to the parameter `key`. Values bolt can not represent, like maps
+    /// with keys that are not strings or integers above `i64::MAX`, fail with
+    /// [`Error::InvalidParameter`] right away instead of when the query is sent.
+    #[cfg(feature = "serde")]
+    pub fn try_param<T: serde::Serialize>(mut self, key: &str, value: T) -> Result<Self> {
+        let value = BoltType::from_serialize(&value).map_err(|e| Error::InvalidParameter {
+            name: key.to_owned(),
+            reason: e.to_string(),
+        })?;
+        self.params.put(key.into(), value);
+        Ok(self)
+    }
+
+    /// Adds every field of a serializable struct or map as a parameter.
+    ///
+    /// Fails if the value does not serialize into a map or contains values bolt can not
+    /// represent.
+    #[cfg(feature = "serde")]
+    pub fn params<T: serde::Serialize>(mut self, value: T) -> Result<Self> {
+        match BoltType::from_serialize(&value)? {
+            BoltType::Map(map) => {
+                for (key, value) in map.value {
+                    self.params.put(key, value);
+                }
+                Ok(self)
+            }
+            other => Err(Error::SerializationError(format!(
+                "parameters must serialize into a map, got {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// Sends `params` along with the parameters bound to the query, without encoding them again.
+    /// A parameter which is bound to the query as well takes the value bound to the query.
+    ///
+    /// ```
+    /// use neo4rs::{query, types::PreparedParams};
+    ///
+    /// let config = vec![("source".into(), "import".into())]
+    ///     .into_iter()
+    ///     .collect();
+    /// let prepared = PreparedParams::new(config).unwrap();
+    /// for id in 0..3 {
+    ///     let q = query("CREATE (n:Item {id: $id, source: $source})")
+    ///         .prepared_params(&prepared)
+    ///         .param("id", id);
+    /// }
+    /// ```
+    pub fn prepared_params(mut self, params: &PreparedParams) -> Self {
+        self.prepared = Some(params.clone());
+        self
+    }
+
+    /// The RUN of the query, against the database of the config unless another one was chosen
+    pub(crate) fn into_request(self, config: &Config) -> BoltRequest {
+        let db = self.db.as_deref().unwrap_or(&config.db);
+        match self.prepared {
+            Some(prepared) => {
+                BoltRequest::run_prepared(db, &self.query, prepared, self.params, self.mode)
+            }
+            None => BoltRequest::run(db, &self.query, self.params, self.mode),
+        }
+    }
+
     pub(crate) async fn run(
         self,
         config: &Config,
         connection: Arc<Mutex<ManagedConnection>>,
     ) -> Result<()> {
-        let run = BoltRequest::run(&config.db, &self.query, self.params.clone());
+        let run = self.into_request(config);
         let mut connection = connection.lock().await;
         match connection.send_recv(run).await? {
             BoltResponse::SuccessMessage(_) => {
                 match connection.send_recv(BoltRequest::discard()).await? {
                     BoltResponse::SuccessMessage(_) => Ok(()),
+                    BoltResponse::FailureMessage(failure) => Err(connection.failed(failure).await),
                     msg => Err(unexpected(msg, "DISCARD")),
                 }
             }
+            BoltResponse::FailureMessage(failure) => Err(connection.failed(failure).await),
             msg => Err(unexpected(msg, "RUN")),
         }
     }
@@ -50,19 +175,221 @@ impl Query {
         config: &Config,
         connection: Arc<Mutex<ManagedConnection>>,
     ) -> Result<RowStream> {
-        let run = BoltRequest::run(&config.db, &self.query, self.params);
-        match connection.lock().await.send_recv(run).await {
-            Ok(BoltResponse::SuccessMessage(success)) => {
+        let fetch_size = self
+            .fetch_size
+            .unwrap_or_else(|| i64::try_from(config.fetch_size).unwrap_or(-1));
+        self.execute_with(config, connection, fetch_size).await
+    }
+
+    /// The first row of the query, only a single row is pulled and the rest is discarded
+    pub(crate) async fn fetch_one(
+        self,
+        config: &Config,
+        connection: Arc<Mutex<ManagedConnection>>,
+    ) -> Result<Option<Row>> {
+        Ok(self.fetch(config, connection, 1).await?.pop())
+    }
+
+    /// The only row of the query, failing with [`Error::UnexpectedRowCount`] if there is none or
+    /// more than one. Pulls at most two rows to tell.
+    pub(crate) async fn fetch_exactly_one(
+        self,
+        config: &Config,
+        connection: Arc<Mutex<ManagedConnection>>,
+    ) -> Result<Row> {
+        let mut rows = self.fetch(config, connection, 2).await?;
+        match rows.len() {
+            1 => Ok(rows.remove(0)),
+            0 => Err(Error::UnexpectedRowCount(
+                "expected exactly one row but the result is empty".to_owned(),
+            )),
+            _ => Err(Error::UnexpectedRowCount(
+                "expected exactly one row but the result has more".to_owned(),
+            )),
+        }
+    }
+
+    /// Pulls up to `limit` rows with a single PULL and discards the rest of the result
+    async fn fetch(
+        self,
+        config: &Config,
+        connection: Arc<Mutex<ManagedConnection>>,
+        limit: usize,
+    ) -> Result<Vec<Row>> {
+        let mut stream = self.execute_with(config, connection, limit as i64).await?;
+        let mut rows = Vec::with_capacity(limit);
+        while rows.len() < limit {
+            match stream.next().await? {
+                Some(row) => rows.push(row),
+                None => break,
+            }
+        }
+        stream.discard().await?;
+        Ok(rows)
+    }
+
+    async fn execute_with(
+        self,
+        config: &Config,
+        connection: Arc<Mutex<ManagedConnection>>,
+        fetch_size: i64,
+    ) -> Result<RowStream> {
+        let in_txn = self.in_txn;
+        let run = self.into_request(config);
+        let mut guard = connection.lock().await;
+        match guard.send_recv(run).await? {
+            BoltResponse::SuccessMessage(success) => {
                 let fields: BoltList = success.get("fields").unwrap_or_else(BoltList::new);
                 let qid: i64 = success.get("qid").unwrap_or(-1);
-                Ok(RowStream::new(
+                let stream = RowStream::new(
                     qid,
                     fields,
-                    config.fetch_size,
+                    fetch_size,
+                    success.get("t_first"),
                     connection.clone(),
-                ))
+                );
+                Ok(if in_txn { stream.in_txn() } else { stream })
             }
+            BoltResponse::FailureMessage(failure) => Err(guard.failed(failure).await),
             msg => Err(unexpected(msg, "RUN")),
         }
     }
 }
+
+impl From<&str> for Query {
+    fn from(query: &str) -> Self {
+        Query::new(query.to_owned())
+    }
+}
+
+impl From<String> for Query {
+    fn from(query: String) -> Self {
+        Query::new(query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::{MockServer, Response};
+    use crate::version::Version;
+
+    #[test]
+    fn should_bind_params() {
+        let query = Query::from("MATCH (n) WHERE n.name = $name AND n.age > $age RETURN n")
+            .param("name", "Alice")
+            .param("age", 42_i64)
+            .param("name", "Bob");
+
+        assert_eq!(
+            query.query,
+            "MATCH (n) WHERE n.name = $name AND n.age > $age RETURN n"
+        );
+        assert_eq!(query.params.get::<String>("name"), Some("Bob".to_owned()));
+        assert_eq!(query.params.get::<i64>("age"), Some(42));
+        assert_eq!(query.params.len(), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_validate_serializable_params() {
+        let by_id: std::collections::HashMap<i32, String> =
+            vec![(1, "a".to_owned())].into_iter().collect();
+        match Query::from("RETURN $m").try_param("m", by_id) {
+            Err(Error::InvalidParameter { name, .. }) => assert_eq!(name, "m"),
+            _ => panic!("integer keys should be rejected"),
+        }
+
+        let by_name: std::collections::HashMap<String, i32> =
+            vec![("a".to_owned(), 1)].into_iter().collect();
+        let query = Query::from("RETURN $m").try_param("m", by_name).unwrap();
+        let m: BoltMap = query.params.get("m").unwrap();
+        assert_eq!(m.get::<i64>("a"), Some(1));
+    }
+
+    #[test]
+    fn should_convert_from_string() {
+        let query: Query = "RETURN 1".to_owned().into();
+        assert_eq!(query.query, "RETURN 1");
+        assert!(query.params.is_empty());
+    }
+
+    #[tokio::test]
+    async fn should_send_database_of_query() {
+        let mut server = MockServer::start(Version::V4_1).await.unwrap();
+        let config = crate::config::config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .db("neo4j")
+            .build()
+            .unwrap();
+        let connection = create_pool(&config).await.get().await.unwrap();
+        let connection = Arc::new(Mutex::new(connection));
+
+        Query::from("RETURN 1")
+            .db("analytics")
+            .run(&config, connection.clone())
+            .await
+            .unwrap();
+        Query::from("RETURN 1")
+            .run(&config, connection)
+            .await
+            .unwrap();
+
+        let _hello = server.next_request().await.unwrap();
+        let run = server.next_request().await.unwrap();
+        assert!(run.ends_with(&[
+            0xA1, 0x82, b'd', b'b', 0x89, b'a', b'n', b'a', b'l', b'y', b't', b'i', b'c', b's'
+        ]));
+        let _discard = server.next_request().await.unwrap();
+        let run = server.next_request().await.unwrap();
+        assert!(run.ends_with(&[0xA1, 0x82, b'd', b'b', 0x85, b'n', b'e', b'o', b'4', b'j']));
+    }
+
+    #[tokio::test]
+    async fn should_time_out_waiting_for_run() {
+        let server = MockServer::start(Version::V4_1).await.unwrap();
+        server.respond(vec![Response::success(vec![])]);
+        server.respond(vec![]);
+        let config = crate::config::config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .read_timeout(std::time::Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let graph = crate::Graph::connect(config).await.unwrap();
+
+        let result = graph.execute(Query::from("RETURN 1")).await;
+
+        assert!(matches!(result, Err(Error::Timeout(_))));
+    }
+
+    #[test]
+    fn should_send_prepared_params_with_query_params() {
+        let config = crate::config::config()
+            .uri("localhost:7687")
+            .user("neo4j")
+            .password("neo")
+            .build()
+            .unwrap();
+        let base = vec![("source".into(), "import".into())]
+            .into_iter()
+            .collect();
+        let prepared = PreparedParams::new(base).unwrap();
+
+        let request = Query::from("RETURN $id, $source")
+            .prepared_params(&prepared)
+            .param("id", 7)
+            .into_request(&config);
+
+        let mut params = prepared.base().clone();
+        params.put("id".into(), 7.into());
+        let expected = BoltRequest::run("", "RETURN $id, $source", params, AccessMode::Write);
+        assert_eq!(
+            request.into_bytes(Version::V4_1).unwrap(),
+            expected.into_bytes(Version::V4_1).unwrap()
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use crate::errors::*;
+use crate::txn::Txn;
+use futures::future::BoxFuture;
+use log::warn;
+use std::future::Future;
+use std::time::Duration;
+
+/// How often and how long to wait before a transaction which failed with a retriable error is
+/// retried, the delay is multiplied after every attempt up to `max_delay`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Backoff {
+    pub(crate) max_retries: usize,
+    pub(crate) initial_delay: Duration,
+    pub(crate) multiplier: f64,
+    pub(crate) max_delay: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff {
+            max_retries: 5,
+            initial_delay: Duration::from_secs(1),
+            multiplier: 2.0,
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Backoff {
+    /// The delay before the retry following the `attempt`th failure, counted from 0
+    pub(crate) fn delay(&self, attempt: usize) -> Duration {
+        let factor = self.multiplier.powi(attempt.min(i32::MAX as usize) as i32);
+        let delay = self.initial_delay.as_secs_f64() * factor;
+        if delay.is_finite() && delay < self.max_delay.as_secs_f64() {
+            Duration::from_secs_f64(delay)
+        } else {
+            self.max_delay
+        }
+    }
+}
+
+impl Error {
+    /// Whether the operation may succeed when it is tried again, like transient server errors
+    /// such as deadlocks or a cluster which is electing a new leader.
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Error::ServiceUnavailable(_) => true,
+            Error::Neo4j { code, .. } => {
+                self.classification() == Some("TransientError")
+                    && code != "Neo.TransientError.Transaction.Terminated"
+                    && code != "Neo.TransientError.Transaction.LockClientStopped"
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Runs `work` in transactions created by `start` and commits them, the whole transaction is
+/// retried as long as it fails with a retriable error and `backoff` permits another attempt.
+pub(crate) async fn with_retry<T, S, SF, F>(backoff: &Backoff, start: S, mut work: F) -> Result<T>
+where
+    S: Fn() -> SF,
+    SF: Future<Output = Result<Txn>>,
+    F: for<'a> FnMut(&'a Txn) -> BoxFuture<'a, Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        let error = match start().await {
+            Ok(txn) => match work(&txn).await {
+                Ok(value) => match txn.commit().await {
+                    Ok(_) => return Ok(value),
+                    Err(e) => e,
+                },
+                Err(e) => {
+                    if let Err(rollback) = txn.rollback().await {
+                        warn!("failed to roll back transaction: {:?}", rollback);
+                    }
+                    e
+                }
+            },
+            Err(e) => e,
+        };
+        if !error.is_retriable() || attempt >= backoff.max_retries {
+            return Err(error);
+        }
+        let delay = backoff.delay(attempt);
+        warn!("retrying transaction in {:?} after: {}", delay, error);
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::config;
+    use crate::graph::Graph;
+    use crate::testkit::{MockServer, Response};
+    use crate::version::Version;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn neo4j(code: &str) -> Error {
+        Error::Neo4j {
+            code: code.to_owned(),
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn should_grow_delay_up_to_max() {
+        let backoff = Backoff {
+            max_retries: 10,
+            initial_delay: Duration::from_millis(100),
+            multiplier: 2.0,
+            max_delay: Duration::from_millis(500),
+        };
+
+        assert_eq!(backoff.delay(0), Duration::from_millis(100));
+        assert_eq!(backoff.delay(1), Duration::from_millis(200));
+        assert_eq!(backoff.delay(2), Duration::from_millis(400));
+        assert_eq!(backoff.delay(3), Duration::from_millis(500));
+        assert_eq!(backoff.delay(usize::MAX), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn should_classify_retriable_errors() {
+        assert!(neo4j("Neo.TransientError.Transaction.DeadlockDetected").is_retriable());
+        assert!(Error::ServiceUnavailable("leader switch".to_owned()).is_retriable());
+        assert!(!neo4j("Neo.TransientError.Transaction.Terminated").is_retriable());
+        assert!(!neo4j("Neo.ClientError.Statement.SyntaxError").is_retriable());
+        assert!(!Error::ConnectionError.is_retriable());
+    }
+
+    #[tokio::test]
+    async fn should_retry_transaction_on_transient_error() {
+        // HELLO, BEGIN, RUN fails with a deadlock, RESET, ROLLBACK and everything after succeeds
+        let mut server = MockServer::start(Version::V4_1).await.unwrap();
+        server.respond(vec![Response::success(vec![])]);
+        server.respond(vec![Response::success(vec![])]);
+        server.respond(vec![Response::failure(
+            "Neo.TransientError.Transaction.DeadlockDetected",
+            "",
+        )]);
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .max_connections(1)
+            .retry_delay(Duration::from_millis(1))
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+        let attempts = AtomicUsize::new(0);
+
+        let value = graph
+            .execute_write(|txn| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async move {
+                    txn.run("CREATE (n)").await?;
+                    Ok(42)
+                })
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(value, 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        let signatures: Vec<u8> = std::iter::from_fn(|| server.try_next_request())
+            .map(|request| request[1])
+            .collect();
+        assert_eq!(
+            signatures,
+            vec![0x01, 0x11, 0x10, 0x0F, 0x13, 0x0F, 0x11, 0x10, 0x2F, 0x12]
+        );
+    }
+
+    #[tokio::test]
+    async fn should_give_up_after_max_retries() {
+        let server = MockServer::start(Version::V4_1).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .max_retries(2)
+            .retry_delay(Duration::from_millis(1))
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+        let attempts = AtomicUsize::new(0);
+
+        let result: Result<()> = graph
+            .execute_read(|_| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async { Err(neo4j("Neo.TransientError.General.OutOfMemoryError")) })
+            })
+            .await;
+
+        assert!(matches!(result, Err(Error::Neo4j { .. })));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn should_not_retry_client_errors() {
+        let server = MockServer::start(Version::V4_1).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .retry_delay(Duration::from_millis(1))
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+        let attempts = AtomicUsize::new(0);
+
+        let result: Result<()> = graph
+            .execute_write(|_| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async { Err(neo4j("Neo.ClientError.Statement.SyntaxError")) })
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,576 @@
+use crate::config::Config;
+use crate::connection::Connection;
+use crate::errors::*;
+use crate::messages::*;
+use crate::pool::{create_pool, ConnectionPool, KeepAlive, ManagedConnection};
+use crate::query::Query;
+use crate::retry::with_retry;
+use crate::stream::RowStream;
+use crate::txn::{Txn, TxnOptions};
+use crate::types::*;
+use crate::uri::parse_uri;
+use futures::future::BoxFuture;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+const ROUTING_PROCEDURE: &str = "CALL dbms.routing.getRoutingTable($context, $database)";
+const MAX_ATTEMPTS: usize = 3;
+
+/// Whether a query only reads or also writes, a [`RoutingDriver`] sends reads to the readers of a
+/// cluster and writes to its leader.
+///
+/// The mode is also sent with BEGIN and RUN, so that a server which routes on its own can pick a
+/// member of the cluster. Write is the default, as a write sent to a reader fails.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum AccessMode {
+    Read,
+    #[default]
+    Write,
+}
+
+impl AccessMode {
+    /// Adds the mode to the extra map of BEGIN or RUN, write is omitted as the server defaults
+    /// to it.
+    pub(crate) fn put_into(self, extra: &mut BoltMap) {
+        if self == AccessMode::Read {
+            extra.put("mode".into(), "r".into());
+        }
+    }
+}
+
+/// The members of a cluster as reported by the routing procedure, the table is valid for `ttl`
+/// seconds after it was fetched.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RoutingTable {
+    routers: Vec<String>,
+    readers: Vec<String>,
+    writers: Vec<String>,
+    expires_at: Instant,
+}
+
+impl RoutingTable {
+    fn parse(ttl: i64, servers: BoltList) -> Result<RoutingTable> {
+        let mut table = RoutingTable {
+            routers: Vec::new(),
+            readers: Vec::new(),
+            writers: Vec::new(),
+            expires_at: Instant::now() + Duration::from_secs(ttl.max(0) as u64),
+        };
+        for server in servers.value {
+            let server: BoltMap = server.try_into()?;
+            let addresses: Vec<String> = server.get("addresses").ok_or_else(|| {
+                Error::UnexpectedMessage("routing table server without addresses".to_owned())
+            })?;
+            match server.get::<String>("role").as_deref() {
+                Some("ROUTE") => table.routers.extend(addresses),
+                Some("READ") => table.readers.extend(addresses),
+                Some("WRITE") => table.writers.extend(addresses),
+                role => {
+                    return Err(Error::UnexpectedMessage(format!(
+                        "unknown routing table role {:?}",
+                        role
+                    )))
+                }
+            }
+        }
+        Ok(table)
+    }
+
+    fn is_expired(&self) -> bool {
+        Instant::now() >= self.expires_at
+    }
+
+    fn servers(&self, mode: AccessMode) -> &[String] {
+        match mode {
+            AccessMode::Read => &self.readers,
+            AccessMode::Write => &self.writers,
+        }
+    }
+
+    /// Drops an unreachable server from the readers and writers until the next refresh.
+    fn forget(&mut self, addr: &str) {
+        self.readers.retain(|a| a != addr);
+        self.writers.retain(|a| a != addr);
+    }
+}
+
+/// Fetches the routing table of the configured database through an established connection.
+async fn fetch_table(
+    connection: &mut Connection,
+    context: BoltMap,
+    db: &str,
+) -> Result<RoutingTable> {
+    if connection.version().has_route_message() {
+        return match connection
+            .send_recv(BoltRequest::route(context, db))
+            .await?
+        {
+            BoltResponse::SuccessMessage(success) => {
+                let rt: BoltMap = success.get("rt").ok_or_else(|| {
+                    Error::UnexpectedMessage("ROUTE response without routing table".to_owned())
+                })?;
+                RoutingTable::parse(
+                    rt.get("ttl").unwrap_or(0),
+                    rt.get("servers").unwrap_or_default(),
+                )
+            }
+            msg => Err(unexpected(msg, "ROUTE")),
+        };
+    }
+
+    let mut params = BoltMap::default();
+    params.put("context".into(), BoltType::Map(context));
+    let database = match db {
+        "" => BoltType::null(),
+        db => db.into(),
+    };
+    params.put("database".into(), database);
+    let run = BoltRequest::run("system", ROUTING_PROCEDURE, params, AccessMode::Write);
+    match connection.send_recv(run).await? {
+        BoltResponse::SuccessMessage(_) => {}
+        msg => return Err(unexpected(msg, "RUN")),
+    }
+    let record = match connection.send_recv(BoltRequest::pull(1, -1)).await? {
+        BoltResponse::RecordMessage(record) => record,
+        msg => return Err(unexpected(msg, "PULL")),
+    };
+    match connection.recv().await? {
+        BoltResponse::SuccessMessage(_) => {}
+        msg => return Err(unexpected(msg, "PULL")),
+    }
+    let mut values = record.data.value.into_iter();
+    match (values.next(), values.next()) {
+        (Some(BoltType::Integer(ttl)), Some(BoltType::List(servers))) => {
+            RoutingTable::parse(ttl.value, servers)
+        }
+        row => Err(Error::UnexpectedMessage(format!(
+            "unexpected routing table record {:?}",
+            row
+        ))),
+    }
+}
+
+/// The connection pool of a cluster member, along with the pings keeping its idle connections
+/// alive.
+type ServerPool = (ConnectionPool, Option<KeepAlive>);
+
+/// A driver for a neo4j cluster, queries are routed to a reader or to the leader of the cluster
+/// depending on their [`AccessMode`].
+///
+/// The cluster members are discovered through the router configured as uri and kept in a routing
+/// table, which is fetched again once its ttl passed or a server became unavailable. A connection
+/// pool is kept for every cluster member, cloning is cheap as all clones share the same pools.
+#[derive(Clone)]
+pub struct RoutingDriver {
+    config: Config,
+    table: Arc<Mutex<RoutingTable>>,
+    pools: Arc<Mutex<HashMap<String, ServerPool>>>,
+    next: Arc<AtomicUsize>,
+}
+
+impl RoutingDriver {
+    /// Connects to the router configured as uri and fetches the initial routing table
+    pub async fn connect(config: Config) -> Result<Self> {
+        let table = Self::fetch(&config, &[]).await?;
+        Ok(RoutingDriver {
+            config,
+            table: Arc::new(Mutex::new(table)),
+            pools: Arc::new(Mutex::new(HashMap::new())),
+            next: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// Runs a query on a server picked by `mode` and discards the stream, see [`Graph::run`]
+    pub async fn run(&self, q: impl Into<Query>, mode: AccessMode) -> Result<()> {
+        let q = q.into();
+        self.with_connection(mode, |config, connection| {
+            q.clone()
+                .mode(mode)
+                .run(config, Arc::new(Mutex::new(connection)))
+        })
+        .await
+    }
+
+    /// Executes a query on a server picked by `mode` and returns a [`RowStream`]
+    pub async fn execute(&self, q: impl Into<Query>, mode: AccessMode) -> Result<RowStream> {
+        let q = q.into();
+        self.with_connection(mode, |config, connection| {
+            q.clone()
+                .mode(mode)
+                .execute(config, Arc::new(Mutex::new(connection)))
+        })
+        .await
+    }
+
+    /// Starts a new transaction on a server picked by `mode`
+    pub async fn start_txn(&self, mode: AccessMode) -> Result<Txn> {
+        self.start_txn_with(TxnOptions::default().with_mode(mode))
+            .await
+    }
+
+    /// Starts a new transaction with `options` on a server picked by their access mode
+    pub async fn start_txn_with(&self, options: TxnOptions) -> Result<Txn> {
+        self.with_connection(options.mode(), |config, connection| {
+            Txn::new(config.clone(), connection, options.clone())
+        })
+        .await
+    }
+
+    /// Runs `work` in a transaction on a reader, see [`Graph::execute_write`]
+    pub async fn execute_read<T, F>(&self, work: F) -> Result<T>
+    where
+        F: for<'a> FnMut(&'a Txn) -> BoxFuture<'a, Result<T>>,
+    {
+        with_retry(
+            &self.config.backoff,
+            || self.start_txn(AccessMode::Read),
+            work,
+        )
+        .await
+    }
+
+    /// Runs `work` in a transaction on the leader, the transaction is retried with exponential
+    /// backoff if it fails with a retriable error, see [`Graph::execute_write`]
+    pub async fn execute_write<T, F>(&self, work: F) -> Result<T>
+    where
+        F: for<'a> FnMut(&'a Txn) -> BoxFuture<'a, Result<T>>,
+    {
+        with_retry(
+            &self.config.backoff,
+            || self.start_txn(AccessMode::Write),
+            work,
+        )
+        .await
+    }
+
+    /// Calls `f` with a connection to a server for `mode`, if the server turns out to be
+    /// unavailable it is removed from the routing table and `f` is retried on another server.
+    async fn with_connection<'a, T, F, Fut>(&'a self, mode: AccessMode, f: F) -> Result<T>
+    where
+        F: Fn(&'a Config, ManagedConnection) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let (addr, pool) = self.pick(mode).await?;
+            let result = match pool.get().await.map_err(Error::from) {
+                Ok(connection) => f(&self.config, connection).await,
+                Err(e) => Err(e),
+            };
+            // a server closing the connection is as unavailable as one refusing it
+            let result = match result {
+                Err(Error::IOError { detail, .. }) => Err(Error::ServiceUnavailable(detail)),
+                Err(Error::ConnectionError) => {
+                    Err(Error::ServiceUnavailable("connection closed".to_owned()))
+                }
+                result => result,
+            };
+            match result {
+                Err(Error::ServiceUnavailable(detail)) if attempts < MAX_ATTEMPTS => {
+                    warn!(
+                        "{} is unavailable, refreshing routing table: {}",
+                        addr, detail
+                    );
+                    self.table.lock().await.forget(&addr);
+                    self.pools.lock().await.remove(&addr);
+                    self.refresh().await?;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Picks the next server for `mode` in a round robin fashion, the routing table is refreshed
+    /// if it expired or knows no server for `mode`.
+    async fn pick(&self, mode: AccessMode) -> Result<(String, ConnectionPool)> {
+        let addr = {
+            let stale = {
+                let table = self.table.lock().await;
+                table.is_expired() || table.servers(mode).is_empty()
+            };
+            if stale {
+                self.refresh().await?;
+            }
+            let table = self.table.lock().await;
+            let servers = table.servers(mode);
+            if servers.is_empty() {
+                return Err(Error::ServiceUnavailable(format!(
+                    "no server available for {:?}",
+                    mode
+                )));
+            }
+            servers[self.next.fetch_add(1, Ordering::Relaxed) % servers.len()].clone()
+        };
+        let mut pools = self.pools.lock().await;
+        let pool = match pools.get(&addr) {
+            Some((pool, _)) => pool.clone(),
+            None => {
+                let config = server_config(&self.config, &addr);
+                let pool = create_pool(&config).await;
+                let keepalive = KeepAlive::spawn(&config, &pool);
+                pools.insert(addr.clone(), (pool.clone(), keepalive));
+                pool
+            }
+        };
+        Ok((addr, pool))
+    }
+
+    async fn refresh(&self) -> Result<()> {
+        let routers = self.table.lock().await.routers.clone();
+        let table = Self::fetch(&self.config, &routers).await?;
+        *self.table.lock().await = table;
+        Ok(())
+    }
+
+    /// Fetches the routing table from the first reachable router, falling back to the
+    /// configured uri.
+    async fn fetch(config: &Config, routers: &[String]) -> Result<RoutingTable> {
+        let initial = parse_uri(&config.uri)?.address();
+        let mut context = BoltMap::default();
+        context.put("address".into(), initial.as_str().into());
+        let mut last_error = None;
+        for router in routers
+            .iter()
+            .map(|r| r.as_str())
+            .chain(Some(initial.as_str()))
+        {
+            info!("fetching routing table from {}", router);
+            let result = match Connection::new(&server_config(config, router)).await {
+                Ok(mut connection) => {
+                    fetch_table(&mut connection, context.clone(), &config.db).await
+                }
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(table) => return Ok(table),
+                Err(e) => {
+                    warn!("failed to fetch routing table from {}: {}", router, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(Error::ServiceUnavailable(format!(
+            "no router is reachable: {}",
+            last_error.map_or_else(String::new, |e| e.to_string())
+        )))
+    }
+}
+
+/// The config to connect to a single cluster member, which is encrypted if the configured uri is.
+fn server_config(config: &Config, addr: &str) -> Config {
+    let mut server = config.clone();
+    server.uri = match parse_uri(&config.uri) {
+        Ok(uri) => uri.direct(addr),
+        Err(_) => addr.to_owned(),
+    };
+    server
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::config;
+    use crate::testkit::{MockServer, Response};
+    use crate::version::Version;
+    use bytes::Bytes;
+
+    fn server(addresses: &[&str], role: &str) -> BoltType {
+        let addresses: Vec<BoltType> = addresses.iter().map(|a| (*a).into()).collect();
+        let mut server = BoltMap::default();
+        server.put("addresses".into(), BoltType::List(addresses.into()));
+        server.put("role".into(), role.into());
+        BoltType::Map(server)
+    }
+
+    /// Serves a routing procedure answering with the given readers and writers.
+    async fn serve_router(readers: &[&str], writers: &[&str]) -> MockServer {
+        let router = MockServer::start(Version::V4_1).await.unwrap();
+        route(&router, readers, writers);
+        router
+    }
+
+    /// Queues the answers to the HELLO, RUN and PULL of a connection fetching the routing table
+    fn route(router: &MockServer, readers: &[&str], writers: &[&str]) {
+        let servers: BoltList = vec![server(readers, "READ"), server(writers, "WRITE")].into();
+        router.respond(vec![Response::success(vec![])]);
+        router.respond(vec![Response::success(vec![])]);
+        router.respond(vec![
+            Response::record(vec![300.into(), BoltType::List(servers)]),
+            Response::success(vec![]),
+        ]);
+    }
+
+    async fn serve() -> MockServer {
+        MockServer::start(Version::V4_1).await.unwrap()
+    }
+
+    fn test_config(uri: &str) -> Config {
+        config()
+            .uri(uri)
+            .user("neo4j")
+            .password("neo")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_parse_routing_table() {
+        let servers: BoltList = vec![
+            server(&["a:7687"], "ROUTE"),
+            server(&["b:7687", "c:7687"], "READ"),
+            server(&["a:7687"], "WRITE"),
+        ]
+        .into();
+
+        let table = RoutingTable::parse(300, servers).unwrap();
+
+        assert_eq!(table.routers, vec!["a:7687"]);
+        assert_eq!(table.servers(AccessMode::Read), ["b:7687", "c:7687"]);
+        assert_eq!(table.servers(AccessMode::Write), ["a:7687"]);
+        assert!(!table.is_expired());
+        assert!(RoutingTable::parse(0, BoltList::new())
+            .unwrap()
+            .is_expired());
+    }
+
+    #[test]
+    fn should_reject_unknown_role() {
+        let servers: BoltList = vec![server(&["a:7687"], "LEADER")].into();
+
+        assert!(matches!(
+            RoutingTable::parse(300, servers),
+            Err(Error::UnexpectedMessage(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_route_by_access_mode() {
+        let mut reader = serve().await;
+        let mut writer = serve().await;
+        let mut router = serve_router(&[reader.addr()], &[writer.addr()]).await;
+
+        let driver = RoutingDriver::connect(test_config(&router.uri()))
+            .await
+            .unwrap();
+        assert_eq!(router.next_request().await.unwrap()[..2], [0xB1, 0x01]);
+        let procedure = router.next_request().await.unwrap();
+        assert!(procedure
+            .windows(ROUTING_PROCEDURE.len())
+            .any(|w| w == ROUTING_PROCEDURE.as_bytes()));
+
+        driver
+            .run("MATCH (n) RETURN n", AccessMode::Read)
+            .await
+            .unwrap();
+        assert_eq!(reader.next_request().await.unwrap()[..2], [0xB1, 0x01]);
+        assert_eq!(reader.next_request().await.unwrap()[..2], [0xB3, 0x10]);
+        assert!(writer.try_next_request().is_none());
+
+        driver.run("CREATE (n)", AccessMode::Write).await.unwrap();
+        assert_eq!(writer.next_request().await.unwrap()[..2], [0xB1, 0x01]);
+        assert_eq!(writer.next_request().await.unwrap()[..2], [0xB3, 0x10]);
+    }
+
+    #[tokio::test]
+    async fn should_send_read_mode_to_reader() {
+        let mut reader = serve().await;
+        let mut writer = serve().await;
+        let router = serve_router(&[reader.addr()], &[writer.addr()]).await;
+        let driver = RoutingDriver::connect(test_config(&router.uri()))
+            .await
+            .unwrap();
+
+        let _txn = driver.start_txn(AccessMode::Read).await.unwrap();
+        driver
+            .run("MATCH (n) RETURN n", AccessMode::Read)
+            .await
+            .unwrap();
+
+        let sent: Vec<Bytes> = std::iter::from_fn(|| reader.try_next_request()).collect();
+        let begin = sent.iter().find(|request| request[1] == 0x11).unwrap();
+        assert_eq!(
+            begin[..],
+            [0xB1, 0x11, 0xA1, 0x84, b'm', b'o', b'd', b'e', 0x81, b'r']
+        );
+        let run = sent.iter().find(|request| request[1] == 0x10).unwrap();
+        assert!(run.ends_with(&[0xA1, 0x84, b'm', b'o', b'd', b'e', 0x81, b'r']));
+        assert!(writer.try_next_request().is_none());
+    }
+
+    #[tokio::test]
+    async fn should_omit_write_mode() {
+        let reader = serve().await;
+        let mut writer = serve().await;
+        let router = serve_router(&[reader.addr()], &[writer.addr()]).await;
+        let driver = RoutingDriver::connect(test_config(&router.uri()))
+            .await
+            .unwrap();
+
+        let _txn = driver.start_txn(AccessMode::Write).await.unwrap();
+
+        assert_eq!(writer.next_request().await.unwrap()[..2], [0xB1, 0x01]);
+        assert_eq!(writer.next_request().await.unwrap()[..], [0xB1, 0x11, 0xA0]);
+    }
+
+    #[tokio::test]
+    async fn should_retry_on_another_server_when_unavailable() {
+        let unreachable = {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().to_string()
+        };
+        let mut reader = serve().await;
+        let router = serve_router(&[&unreachable, reader.addr()], &[reader.addr()]).await;
+        route(&router, &[reader.addr()], &[reader.addr()]);
+        let driver = RoutingDriver::connect(test_config(&router.uri()))
+            .await
+            .unwrap();
+
+        driver
+            .run("MATCH (n) RETURN n", AccessMode::Read)
+            .await
+            .unwrap();
+
+        assert_eq!(reader.next_request().await.unwrap()[..2], [0xB1, 0x01]);
+        assert_eq!(reader.next_request().await.unwrap()[..2], [0xB3, 0x10]);
+    }
+
+    #[tokio::test]
+    async fn should_retry_on_another_server_when_connection_closes() {
+        let closing = serve().await;
+        closing.respond(vec![Response::success(vec![])]);
+        closing.hang_up();
+        let mut reader = serve().await;
+        let router = serve_router(&[closing.addr(), reader.addr()], &[reader.addr()]).await;
+        route(&router, &[reader.addr()], &[reader.addr()]);
+        let driver = RoutingDriver::connect(test_config(&router.uri()))
+            .await
+            .unwrap();
+
+        driver
+            .run("MATCH (n) RETURN n", AccessMode::Read)
+            .await
+            .unwrap();
+
+        assert_eq!(reader.next_request().await.unwrap()[..2], [0xB1, 0x01]);
+        assert_eq!(reader.next_request().await.unwrap()[..2], [0xB3, 0x10]);
+    }
+
+    #[tokio::test]
+    async fn should_fail_without_reachable_router() {
+        let unreachable = {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().to_string()
+        };
+
+        let result = RoutingDriver::connect(test_config(&unreachable)).await;
+
+        assert!(matches!(result, Err(Error::ServiceUnavailable(_))));
+    }
+}
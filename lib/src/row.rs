@@ -1,12 +1,39 @@
 use crate::types::*;
-use std::convert::TryInto;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::Arc;
 
 /// Represents a row returned as a result of executing a query.
 ///
 /// A row is very similar to a `HashMap`, you can get the attributes using [`Row::get`] method.
-#[derive(Debug)]
+/// The column names are shared between all rows of a result, so cloning a row only copies its
+/// values.
+#[derive(Debug, Clone)]
 pub struct Row {
-    attributes: BoltMap,
+    columns: Arc<Columns>,
+    values: BoltList,
+}
+
+/// The column names of a result along with their positions.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Columns {
+    names: Vec<String>,
+    positions: HashMap<String, usize>,
+}
+
+impl Columns {
+    pub(crate) fn new(fields: BoltList) -> Self {
+        let names: Vec<String> = fields
+            .into_iter()
+            .map(|field| String::try_from(field).unwrap_or_default())
+            .collect();
+        let positions = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (name.clone(), index))
+            .collect();
+        Columns { names, positions }
+    }
 }
 
 /// Snapshot of a node within a graph database
@@ -62,6 +89,17 @@ impl Path {
         let rels = self.inner.rels();
         rels.into_iter().map(UnboundedRelation::new).collect()
     }
+
+    /// The steps along the path from its first node, each step is made of the node it starts at,
+    /// the relationship it follows and the node it ends at. A relationship followed against its
+    /// direction starts at the end of the step.
+    pub fn segments(&self) -> crate::errors::Result<Vec<(Node, Relation, Node)>> {
+        let segments = self.inner.segments()?;
+        Ok(segments
+            .into_iter()
+            .map(|(start, rel, end)| (Node::new(start), Relation::new(rel), Node::new(end)))
+            .collect())
+    }
 }
 
 impl Point2D {
@@ -69,6 +107,15 @@ impl Point2D {
         Point2D { inner }
     }
 
+    /// Whether the point is geographic, located by longitude and latitude in WGS-84
+    pub fn is_wgs84(&self) -> bool {
+        self.inner.is_wgs84()
+    }
+
+    pub fn is_cartesian(&self) -> bool {
+        self.inner.is_cartesian()
+    }
+
     /// Spatial refrerence system identifier, see <https://en.wikipedia.org/wiki/Spatial_reference_system#Identifier>
     pub fn sr_id(&self) -> i64 {
         self.inner.sr_id.value
@@ -88,6 +135,15 @@ impl Point3D {
         Point3D { inner }
     }
 
+    /// Whether the point is geographic, located by longitude and latitude in WGS-84
+    pub fn is_wgs84(&self) -> bool {
+        self.inner.is_wgs84()
+    }
+
+    pub fn is_cartesian(&self) -> bool {
+        self.inner.is_cartesian()
+    }
+
     /// Spatial refrerence system identifier, see <https://en.wikipedia.org/wiki/Spatial_reference_system#Identifier>
     pub fn sr_id(&self) -> i64 {
         self.inner.sr_id.value
@@ -108,17 +164,77 @@ impl Point3D {
 
 impl Row {
     pub fn new(fields: BoltList, data: BoltList) -> Self {
-        let mut attributes = BoltMap::with_capacity(fields.len());
-        for (field, value) in fields.into_iter().zip(data.into_iter()) {
-            if let Ok(key) = field.try_into() {
-                attributes.put(key, value);
-            }
+        Row::with_columns(Arc::new(Columns::new(fields)), data)
+    }
+
+    pub(crate) fn with_columns(columns: Arc<Columns>, values: BoltList) -> Self {
+        Row { columns, values }
+    }
+
+    /// Get the value of the column with the given name, `None` if there is no such column or its
+    /// value does not convert into `T`. A null converts into `None` of an `Option<T>`, so
+    /// `get::<Option<T>>` tells a missing column (`None`) from a null (`Some(None)`).
+    pub fn get<T: TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
+        let index = *self.columns.positions.get(key)?;
+        self.get_by_index(index)
+    }
+
+    /// Get the value of the column at the given position, starting at 0
+    pub fn get_by_index<T: TryFrom<BoltType>>(&self, index: usize) -> Option<T> {
+        let value = self.values.get(index)?;
+        T::try_from(value.clone()).ok()
+    }
+
+    /// The names of the columns in the order they were returned
+    pub fn keys(&self) -> Vec<&str> {
+        self.columns
+            .names
+            .iter()
+            .map(|name| name.as_str())
+            .collect()
+    }
+
+    /// Deserializes the value of the given column into any type implementing
+    /// [`serde::Deserialize`].
+    #[cfg(feature = "serde")]
+    pub fn get_as<T: serde::de::DeserializeOwned>(&self, key: &str) -> crate::errors::Result<T> {
+        let value = self
+            .columns
+            .positions
+            .get(key)
+            .and_then(|index| self.values.get(*index));
+        match value {
+            Some(value) => value.clone().to(),
+            None => Err(crate::errors::Error::DeserializationError(format!(
+                "no column named {}",
+                key
+            ))),
         }
-        Row { attributes }
     }
 
-    pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
-        self.attributes.get(key)
+    /// Deserializes the whole row, using the column names as field names. Tuples are
+    /// deserialized from the columns in the order they were returned.
+    #[cfg(feature = "serde")]
+    pub fn to<T: serde::de::DeserializeOwned>(&self) -> crate::errors::Result<T> {
+        let map: BoltMap = self
+            .columns
+            .names
+            .iter()
+            .zip(self.values.iter())
+            .map(|(name, value)| (name.as_str().into(), value.clone()))
+            .collect();
+        Ok(T::deserialize(crate::de::RowDeserializer::new(map))?)
+    }
+
+    /// Deserializes the value of a row with a single column, or the whole row, see [`Row::to`]
+    #[cfg(feature = "serde")]
+    pub(crate) fn to_value_or_row<T: serde::de::DeserializeOwned>(
+        &self,
+    ) -> crate::errors::Result<T> {
+        match self.values.value.as_slice() {
+            [value] => value.clone().to(),
+            _ => self.to(),
+        }
     }
 }
 
@@ -132,6 +248,11 @@ impl Node {
         self.inner.id.value
     }
 
+    /// Element id of the node, only sent by servers speaking bolt 5.0 or later
+    pub fn element_id(&self) -> Option<&str> {
+        self.inner.element_id.as_ref().map(|id| id.value.as_str())
+    }
+
     /// various labels attached to this node
     pub fn labels(&self) -> Vec<String> {
         self.inner.labels.iter().map(|l| l.to_string()).collect()
@@ -141,6 +262,13 @@ impl Node {
     pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
         self.inner.get(key)
     }
+
+    /// Deserializes the properties of the node into any type implementing
+    /// [`serde::Deserialize`].
+    #[cfg(feature = "serde")]
+    pub fn to<T: serde::de::DeserializeOwned>(&self) -> crate::errors::Result<T> {
+        BoltType::Map(self.inner.properties.clone()).to()
+    }
 }
 
 impl Relation {
@@ -152,6 +280,11 @@ impl Relation {
         self.inner.id.value
     }
 
+    /// Element id of the relation, only sent by servers speaking bolt 5.0 or later
+    pub fn element_id(&self) -> Option<&str> {
+        self.inner.element_id.as_ref().map(|id| id.value.as_str())
+    }
+
     pub fn start_node_id(&self) -> i64 {
         self.inner.start_node_id.value
     }
@@ -178,6 +311,11 @@ impl UnboundedRelation {
         self.inner.id.value
     }
 
+    /// Element id of the relation, only sent by servers speaking bolt 5.0 or later
+    pub fn element_id(&self) -> Option<&str> {
+        self.inner.element_id.as_ref().map(|id| id.value.as_str())
+    }
+
     pub fn typ(&self) -> String {
         self.inner.typ.value.clone()
     }
@@ -186,3 +324,52 @@ impl UnboundedRelation {
         self.inner.get(key)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+    use bytes::Bytes;
+
+    #[test]
+    fn should_read_record_by_name_and_index() {
+        let fields = BoltList::from(vec!["name".into(), "age".into()]);
+        let mut record = Bytes::from_static(&[0x92, 0x85, b'A', b'l', b'i', b'c', b'e', 0x2A]);
+        let data = BoltList::parse(Version::V4_1, &mut record).unwrap();
+
+        let row = Row::new(fields, data);
+
+        assert_eq!(row.get::<String>("name"), Some("Alice".to_owned()));
+        assert_eq!(row.get::<i64>("age"), Some(42));
+        assert_eq!(row.get_by_index::<String>(0), Some("Alice".to_owned()));
+        assert_eq!(row.get_by_index::<i64>(1), Some(42));
+        assert_eq!(row.get::<i64>("missing"), None);
+        assert_eq!(row.get_by_index::<i64>(2), None);
+        assert_eq!(row.get::<i64>("name"), None);
+        assert_eq!(row.keys(), vec!["name", "age"]);
+    }
+
+    #[test]
+    fn should_tell_missing_columns_from_nulls() {
+        let row = Row::new(
+            vec!["nickname".into()].into(),
+            vec![BoltType::null()].into(),
+        );
+
+        assert_eq!(row.get::<Option<String>>("nickname"), Some(None));
+        assert_eq!(row.get::<Option<String>>("missing"), None);
+        assert_eq!(row.get::<String>("nickname"), None);
+        assert!(row.get::<BoltType>("nickname").unwrap().is_null());
+    }
+
+    #[test]
+    fn should_share_columns_between_rows() {
+        let columns = Arc::new(Columns::new(vec!["n".into()].into()));
+        let first = Row::with_columns(columns.clone(), vec![1_i64.into()].into());
+        let second = first.clone();
+
+        assert!(Arc::ptr_eq(&first.columns, &second.columns));
+        assert!(Arc::ptr_eq(&first.columns, &columns));
+        assert_eq!(second.get::<i64>("n"), Some(1));
+    }
+}
@@ -0,0 +1,498 @@
+use crate::errors::{Error, Result};
+use crate::types::*;
+use serde::ser::{
+    Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant, Serializer,
+};
+use std::convert::TryInto;
+use std::fmt::Display;
+
+/// The error reported to serde while serializing, it is converted into
+/// [`Error::SerializationError`] before it is returned to the caller.
+#[derive(Debug)]
+pub struct SerError(String);
+
+impl Display for SerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for SerError {}
+
+impl serde::ser::Error for SerError {
+    fn custom<T: Display>(msg: T) -> Self {
+        SerError(msg.to_string())
+    }
+}
+
+impl From<SerError> for Error {
+    fn from(e: SerError) -> Self {
+        Error::SerializationError(e.0)
+    }
+}
+
+impl BoltType {
+    /// Converts any type implementing [`serde::Serialize`] into a bolt value.
+    ///
+    /// Structs and maps become a [`BoltMap`], sequences a [`BoltList`] and `None` a [`BoltNull`].
+    /// Values bolt can not represent, like `u128` or integers above `i64::MAX`, are rejected.
+    pub fn from_serialize<T: Serialize + ?Sized>(value: &T) -> Result<BoltType> {
+        Ok(value.serialize(BoltSerializer)?)
+    }
+}
+
+/// A [`serde::Serializer`] producing [`BoltType`] values.
+pub struct BoltSerializer;
+
+type SerResult = std::result::Result<BoltType, SerError>;
+
+fn integer<T: TryInto<i64> + Display + Copy>(v: T) -> SerResult {
+    v.try_into()
+        .map(|v| BoltType::Integer(BoltInteger::new(v)))
+        .map_err(|_| SerError(format!("integer {} does not fit into i64", v)))
+}
+
+fn variant(name: &'static str, value: BoltType) -> BoltType {
+    let mut map = BoltMap::with_capacity(1);
+    map.put(name.into(), value);
+    BoltType::Map(map)
+}
+
+impl Serializer for BoltSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+    type SerializeSeq = ListSerializer;
+    type SerializeTuple = ListSerializer;
+    type SerializeTupleStruct = ListSerializer;
+    type SerializeTupleVariant = ListSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = MapSerializer;
+
+    fn serialize_bool(self, v: bool) -> SerResult {
+        Ok(BoltType::Boolean(BoltBoolean::new(v)))
+    }
+
+    fn serialize_i8(self, v: i8) -> SerResult {
+        integer(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> SerResult {
+        integer(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> SerResult {
+        integer(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> SerResult {
+        integer(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> SerResult {
+        integer(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> SerResult {
+        integer(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> SerResult {
+        integer(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> SerResult {
+        integer(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> SerResult {
+        integer(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> SerResult {
+        Err(SerError(format!(
+            "u128 can not be represented in bolt, got {}",
+            v
+        )))
+    }
+
+    fn serialize_f32(self, v: f32) -> SerResult {
+        Ok(BoltType::Float(BoltFloat::new(v.into())))
+    }
+
+    fn serialize_f64(self, v: f64) -> SerResult {
+        Ok(BoltType::Float(BoltFloat::new(v)))
+    }
+
+    fn serialize_char(self, v: char) -> SerResult {
+        Ok(BoltType::String(v.to_string().into()))
+    }
+
+    fn serialize_str(self, v: &str) -> SerResult {
+        Ok(BoltType::String(v.into()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> SerResult {
+        Ok(BoltType::Bytes(BoltBytes::new(
+            bytes::Bytes::copy_from_slice(v),
+        )))
+    }
+
+    fn serialize_none(self) -> SerResult {
+        Ok(BoltType::null())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> SerResult {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> SerResult {
+        Ok(BoltType::null())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> SerResult {
+        Ok(BoltType::null())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> SerResult {
+        Ok(BoltType::String(variant.into()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> SerResult {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        name: &'static str,
+        value: &T,
+    ) -> SerResult {
+        Ok(variant(name, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> std::result::Result<ListSerializer, SerError> {
+        Ok(ListSerializer::new(len.unwrap_or(0), None))
+    }
+
+    fn serialize_tuple(self, len: usize) -> std::result::Result<ListSerializer, SerError> {
+        Ok(ListSerializer::new(len, None))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> std::result::Result<ListSerializer, SerError> {
+        Ok(ListSerializer::new(len, None))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        name: &'static str,
+        len: usize,
+    ) -> std::result::Result<ListSerializer, SerError> {
+        Ok(ListSerializer::new(len, Some(name)))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> std::result::Result<MapSerializer, SerError> {
+        Ok(MapSerializer::new(len.unwrap_or(0), None))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> std::result::Result<MapSerializer, SerError> {
+        Ok(MapSerializer::new(len, None))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        name: &'static str,
+        len: usize,
+    ) -> std::result::Result<MapSerializer, SerError> {
+        Ok(MapSerializer::new(len, Some(name)))
+    }
+}
+
+pub struct ListSerializer {
+    list: BoltList,
+    variant: Option<&'static str>,
+}
+
+impl ListSerializer {
+    fn new(len: usize, variant: Option<&'static str>) -> Self {
+        ListSerializer {
+            list: BoltList::with_capacity(len),
+            variant,
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> std::result::Result<(), SerError> {
+        self.list.push(value.serialize(BoltSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> SerResult {
+        let list = BoltType::List(self.list);
+        Ok(match self.variant {
+            Some(name) => variant(name, list),
+            None => list,
+        })
+    }
+}
+
+impl SerializeSeq for ListSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+
+    fn serialize_element<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> std::result::Result<(), SerError> {
+        self.push(value)
+    }
+
+    fn end(self) -> SerResult {
+        self.finish()
+    }
+}
+
+impl SerializeTuple for ListSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+
+    fn serialize_element<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> std::result::Result<(), SerError> {
+        self.push(value)
+    }
+
+    fn end(self) -> SerResult {
+        self.finish()
+    }
+}
+
+impl SerializeTupleStruct for ListSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> std::result::Result<(), SerError> {
+        self.push(value)
+    }
+
+    fn end(self) -> SerResult {
+        self.finish()
+    }
+}
+
+impl SerializeTupleVariant for ListSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> std::result::Result<(), SerError> {
+        self.push(value)
+    }
+
+    fn end(self) -> SerResult {
+        self.finish()
+    }
+}
+
+pub struct MapSerializer {
+    map: BoltMap,
+    key: Option<BoltString>,
+    variant: Option<&'static str>,
+}
+
+impl MapSerializer {
+    fn new(len: usize, variant: Option<&'static str>) -> Self {
+        MapSerializer {
+            map: BoltMap::with_capacity(len),
+            key: None,
+            variant,
+        }
+    }
+
+    fn finish(self) -> SerResult {
+        let map = BoltType::Map(self.map);
+        Ok(match self.variant {
+            Some(name) => variant(name, map),
+            None => map,
+        })
+    }
+}
+
+impl SerializeMap for MapSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+
+    fn serialize_key<T: Serialize + ?Sized>(
+        &mut self,
+        key: &T,
+    ) -> std::result::Result<(), SerError> {
+        match key.serialize(BoltSerializer)? {
+            BoltType::String(key) => {
+                self.key = Some(key);
+                Ok(())
+            }
+            other => Err(SerError(format!(
+                "map keys must be strings, got {:?}",
+                other
+            ))),
+        }
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> std::result::Result<(), SerError> {
+        match self.key.take() {
+            Some(key) => {
+                self.map.put(key, value.serialize(BoltSerializer)?);
+                Ok(())
+            }
+            None => Err(SerError("value serialized before key".to_owned())),
+        }
+    }
+
+    fn end(self) -> SerResult {
+        self.finish()
+    }
+}
+
+impl SerializeStruct for MapSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> std::result::Result<(), SerError> {
+        self.map.put(key.into(), value.serialize(BoltSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> SerResult {
+        self.finish()
+    }
+}
+
+impl SerializeStructVariant for MapSerializer {
+    type Ok = BoltType;
+    type Error = SerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> std::result::Result<(), SerError> {
+        self.map.put(key.into(), value.serialize(BoltSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> SerResult {
+        self.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Movie {
+        title: String,
+        released: u16,
+        tagline: Option<String>,
+        genres: Vec<&'static str>,
+        rating: Rating,
+        kind: Kind,
+    }
+
+    #[derive(Serialize)]
+    struct Rating {
+        stars: f64,
+    }
+
+    #[derive(Serialize)]
+    enum Kind {
+        Feature,
+    }
+
+    #[test]
+    fn should_serialize_struct_into_map() {
+        let movie = Movie {
+            title: "The Matrix".to_owned(),
+            released: 1999,
+            tagline: None,
+            genres: vec!["action", "sci-fi"],
+            rating: Rating { stars: 4.5 },
+            kind: Kind::Feature,
+        };
+
+        let value = BoltType::from_serialize(&movie).unwrap();
+
+        let rating: BoltMap = vec![("stars".into(), 4.5.into())].into_iter().collect();
+        let expected: BoltMap = vec![
+            ("title".into(), "The Matrix".into()),
+            ("released".into(), BoltType::Integer(BoltInteger::new(1999))),
+            ("tagline".into(), BoltType::Null(BoltNull)),
+            (
+                "genres".into(),
+                BoltType::List(vec!["action".into(), "sci-fi".into()].into()),
+            ),
+            ("rating".into(), BoltType::Map(rating)),
+            ("kind".into(), "Feature".into()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(value, BoltType::Map(expected));
+    }
+
+    #[test]
+    fn should_reject_u128() {
+        let result = BoltType::from_serialize(&1_u128);
+        assert!(matches!(result, Err(Error::SerializationError(_))));
+    }
+
+    #[test]
+    fn should_reject_u64_above_i64_max() {
+        assert!(BoltType::from_serialize(&u64::MAX).is_err());
+        assert_eq!(
+            BoltType::from_serialize(&(i64::MAX as u64)).unwrap(),
+            BoltType::Integer(BoltInteger::new(i64::MAX))
+        );
+    }
+
+    #[test]
+    fn should_reject_non_string_map_keys() {
+        let map: std::collections::BTreeMap<i32, i32> = vec![(1, 2)].into_iter().collect();
+        assert!(BoltType::from_serialize(&map).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+use crate::errors::*;
+use crate::graph::Graph;
+use crate::txn::{Txn, TxnOptions};
+use std::sync::{Arc, Mutex};
+
+/// A chain of transactions where every transaction sees the writes of the transactions committed
+/// in the session before, even when they run on different members of a cluster. The bookmark of
+/// every commit is passed on to the BEGIN of the next transaction.
+#[derive(Clone)]
+pub struct Session {
+    graph: Graph,
+    bookmarks: Arc<Mutex<Vec<String>>>,
+    impersonated_user: Option<String>,
+}
+
+impl Session {
+    pub(crate) fn new(graph: Graph) -> Self {
+        Session {
+            graph,
+            bookmarks: Arc::new(Mutex::new(Vec::new())),
+            impersonated_user: None,
+        }
+    }
+
+    /// Continues after the transactions that produced `bookmarks`, for example the last
+    /// bookmarks of another session
+    pub fn with_bookmarks(self, bookmarks: Vec<String>) -> Self {
+        *self.bookmarks.lock().unwrap() = bookmarks;
+        self
+    }
+
+    /// Runs the transactions of the session as `user`, see [`TxnOptions::with_impersonated_user`].
+    /// Starting a transaction fails with [`Error::UnsupportedVersion`] if the connection speaks a
+    /// bolt version before 4.4, which has to be proposed with [`crate::ConfigBuilder::versions`].
+    pub fn impersonate(mut self, user: &str) -> Self {
+        self.impersonated_user = Some(user.to_owned());
+        self
+    }
+
+    /// Starts a new transaction which begins once the server caught up with the last bookmarks
+    pub async fn start_txn(&self) -> Result<Txn> {
+        let mut options = TxnOptions::default().with_bookmarks(self.last_bookmarks());
+        if let Some(user) = &self.impersonated_user {
+            options = options.with_impersonated_user(user);
+        }
+        let txn = self.graph.start_txn_with(options).await?;
+        Ok(txn.chain(self.bookmarks.clone()))
+    }
+
+    /// The bookmark of the last commit in the session, or the bookmarks it was created with
+    pub fn last_bookmarks(&self) -> Vec<String> {
+        self.bookmarks.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::config;
+    use crate::graph::Graph;
+    use crate::testkit::{MockServer, Response};
+    use crate::version::Version;
+    use bytes::Bytes;
+
+    #[tokio::test]
+    async fn should_pass_bookmark_of_commit_to_next_begin() {
+        // HELLO, BEGIN, COMMIT answers with a bookmark
+        let mut server = MockServer::start(Version::V4_1).await.unwrap();
+        server.respond(vec![Response::success(vec![])]);
+        server.respond(vec![Response::success(vec![])]);
+        server.respond(vec![Response::success(vec![("bookmark", "bm:1".into())])]);
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .max_connections(1)
+            .build()
+            .unwrap();
+        let session = Graph::connect(config).await.unwrap().session();
+
+        let bookmark = session.start_txn().await.unwrap().commit().await.unwrap();
+        assert_eq!(bookmark.as_deref(), Some("bm:1"));
+        assert_eq!(session.last_bookmarks(), vec!["bm:1".to_owned()]);
+        session.start_txn().await.unwrap();
+
+        let sent: Vec<Bytes> = std::iter::from_fn(|| server.try_next_request()).collect();
+        assert_eq!(sent[1], vec![0xB1, 0x11, 0xA0]);
+        assert_eq!(
+            sent.last().unwrap()[..],
+            [
+                0xB1, 0x11, 0xA1, 0x89, b'b', b'o', b'o', b'k', b'm', b'a', b'r', b'k', b's', 0x91,
+                0x84, b'b', b'm', b':', b'1'
+            ]
+        );
+    }
+}
@@ -2,22 +2,38 @@ use crate::errors::*;
 use crate::messages::*;
 use crate::pool::*;
 use crate::row::*;
+use crate::summary::ResultSummary;
 use crate::types::*;
+use futures::Stream;
 use std::collections::VecDeque;
+use std::convert::TryFrom;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// The most rows a stream reserves room for up front, the buffer of a larger fetch size grows as
+/// its rows arrive
+const PREALLOCATED_ROWS: usize = 1024;
+
 /// An abstraction over a stream of rows, this is returned as a result of [`Graph::execute`] or
 /// [`Txn::execute`] operations
 ///
 /// A stream will contain a connection from the connection pool which will be released to the pool
-/// when the stream is dropped.
+/// when the stream is dropped. The pool resets the connection before it is used again, and
+/// replaces it if the reset fails, like when the stream was dropped while a batch of rows was
+/// still coming in. [`RowStream::cancel`] stops a query which is not consumed to the end and keeps
+/// the connection usable.
+///
+/// The streams of several queries of a transaction can be consumed in any order, every PULL
+/// names its query by the `qid` the server assigned to it in reply to RUN.
 pub struct RowStream {
     qid: i64,
-    fields: BoltList,
+    columns: Arc<Columns>,
     state: State,
-    fetch_size: usize,
+    fetch_size: i64,
     buffer: VecDeque<Row>,
+    t_first: Option<i64>,
+    summary: Option<ResultSummary>,
+    in_txn: bool,
     connection: Arc<Mutex<ManagedConnection>>,
 }
 
@@ -33,22 +49,37 @@ impl RowStream {
     pub(crate) fn new(
         qid: i64,
         fields: BoltList,
-        fetch_size: usize,
+        fetch_size: i64,
+        t_first: Option<i64>,
         connection: Arc<Mutex<ManagedConnection>>,
     ) -> RowStream {
         RowStream {
             qid,
-            fields,
+            columns: Arc::new(Columns::new(fields)),
             connection,
             fetch_size,
             state: State::Ready,
-            buffer: VecDeque::with_capacity(fetch_size),
+            buffer: VecDeque::with_capacity(
+                usize::try_from(fetch_size)
+                    .unwrap_or_default()
+                    .min(PREALLOCATED_ROWS),
+            ),
+            t_first,
+            summary: None,
+            in_txn: false,
         }
     }
 
+    /// Marks the stream as a result of a transaction, which must not be cancelled with RESET
+    pub(crate) fn in_txn(mut self) -> Self {
+        self.in_txn = true;
+        self
+    }
+
     /// A call to next() will return a row from an internal buffer if the buffer has any entries,
     /// if the buffer is empty and the server has more rows left to consume, then a new batch of rows are fetched from the server (using the
-    /// fetch_size value configured see [`ConfigBuilder::fetch_size`])
+    /// fetch size of the query or the one configured, see [`crate::Query::fetch_size`] and
+    /// [`ConfigBuilder::fetch_size`])
     pub async fn next(&mut self) -> Result<Option<Row>> {
         let mut connection = self.connection.lock().await;
         loop {
@@ -58,18 +89,23 @@ impl RowStream {
                     connection.send(pull).await?;
                     self.state = State::Streaming;
                 }
-                State::Streaming => match connection.recv().await {
-                    Ok(BoltResponse::SuccessMessage(s)) => {
+                State::Streaming => match connection.recv().await? {
+                    BoltResponse::SuccessMessage(s) => {
                         if s.get("has_more").unwrap_or(false) {
                             self.state = State::Buffered;
                         } else {
+                            self.summary = Some(ResultSummary::new(self.t_first, &s));
                             self.state = State::Complete;
                         }
                     }
-                    Ok(BoltResponse::RecordMessage(record)) => {
-                        let row = Row::new(self.fields.clone(), record.data);
+                    BoltResponse::RecordMessage(record) => {
+                        let row = Row::with_columns(self.columns.clone(), record.data);
                         self.buffer.push_back(row);
                     }
+                    BoltResponse::FailureMessage(failure) => {
+                        self.state = State::Complete;
+                        return Err(connection.failed(failure).await);
+                    }
                     msg => return Err(unexpected(msg, "PULL")),
                 },
                 State::Buffered => {
@@ -84,4 +120,602 @@ impl RowStream {
             }
         }
     }
+
+    /// The summary of the query, available once the server sent the last row
+    pub fn summary(&self) -> Option<&ResultSummary> {
+        self.summary.as_ref()
+    }
+
+    /// Throws away the rows which were not consumed yet and returns the summary of the query, the
+    /// server is told to skip the remaining records instead of sending them. Useful for writes
+    /// where only the counters are of interest.
+    pub async fn discard(mut self) -> Result<ResultSummary> {
+        self.buffer.clear();
+        let mut pending = match self.state {
+            State::Complete => {
+                return self.summary.take().ok_or_else(|| {
+                    Error::UnexpectedMessage("the stream ended without a summary".to_owned())
+                })
+            }
+            State::Ready | State::Buffered => 1,
+            // the summary of the interrupted PULL arrives before the one of DISCARD
+            State::Streaming => 2,
+        };
+        let mut connection = self.connection.lock().await;
+        connection.send(BoltRequest::discard_all(self.qid)).await?;
+        self.state = State::Complete;
+        loop {
+            match connection.recv().await? {
+                BoltResponse::SuccessMessage(success) if pending == 1 => {
+                    return Ok(ResultSummary::new(self.t_first, &success))
+                }
+                BoltResponse::SuccessMessage(_) => pending -= 1,
+                BoltResponse::RecordMessage(_) => {}
+                BoltResponse::FailureMessage(failure) => {
+                    return Err(connection.failed(failure).await)
+                }
+                msg => return Err(unexpected(msg, "DISCARD")),
+            }
+        }
+    }
+
+    /// Stops the query and throws away the rows which were not consumed yet, leaving the
+    /// connection ready for the next query. Outside of a transaction the server is told to stop
+    /// with RESET. A transaction would be rolled back by RESET, its streams are discarded with
+    /// DISCARD instead, see [`RowStream::discard`].
+    pub async fn cancel(mut self) -> Result<()> {
+        if self.in_txn {
+            return self.discard().await.map(|_| ());
+        }
+        self.buffer.clear();
+        let interrupted = match self.state {
+            State::Complete => return Ok(()),
+            State::Ready | State::Buffered => false,
+            State::Streaming => true,
+        };
+        let mut connection = self.connection.lock().await;
+        connection.send(BoltRequest::reset()).await?;
+        self.state = State::Complete;
+        if interrupted {
+            // the rest of the reply to the interrupted PULL arrives before the one to RESET
+            while let BoltResponse::RecordMessage(_) = connection.recv().await? {}
+        }
+        match connection.recv().await? {
+            BoltResponse::SuccessMessage(_) => Ok(()),
+            msg => Err(unexpected(msg, "RESET")),
+        }
+    }
+
+    /// Pulls all remaining rows and deserializes each of them, rows with a single column are
+    /// deserialized from the value of the column and all other rows as a whole, see
+    /// [`Row::to`]. Stops at the first row which fails to deserialize.
+    #[cfg(feature = "serde")]
+    pub async fn collect_as<T: serde::de::DeserializeOwned>(mut self) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        while let Some(row) = self.next().await? {
+            items.push(row.to_value_or_row()?);
+        }
+        Ok(items)
+    }
+
+    /// Turns the row stream into a [`futures::Stream`], rows are pulled from the server in
+    /// batches of `fetch_size` as the stream is polled.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Row>> {
+        futures::stream::try_unfold(self, |mut rows| async move {
+            Ok(rows.next().await?.map(|row| (row, rows)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::config;
+    use crate::errors::Error;
+    use crate::graph::{query, Graph};
+    use crate::summary::QueryType;
+    use crate::testkit::{MockServer, Response};
+    use crate::types::{BoltMap, BoltType};
+    use crate::version::Version;
+    use bytes::Bytes;
+    use futures::TryStreamExt;
+
+    /// A server answering with the responses of `script` in order, HELLO included
+    async fn serve(script: Vec<Vec<Response>>) -> MockServer {
+        let server = MockServer::start(Version::V4_1).await.unwrap();
+        for responses in script {
+            server.respond(responses);
+        }
+        server
+    }
+
+    /// The SUCCESS of RUN naming the `columns`
+    fn fields(columns: &[&str]) -> Response {
+        let columns: Vec<BoltType> = columns.iter().map(|column| (*column).into()).collect();
+        Response::success(vec![("fields", BoltType::List(columns.into()))])
+    }
+
+    fn has_more() -> Response {
+        Response::success(vec![("has_more", true.into())])
+    }
+
+    fn write_summary() -> Response {
+        let stats = vec![("nodes-created".into(), 3.into())]
+            .into_iter()
+            .collect();
+        Response::success(vec![("type", "w".into()), ("stats", BoltType::Map(stats))])
+    }
+
+    fn done() -> Response {
+        Response::success(vec![])
+    }
+
+    #[tokio::test]
+    async fn should_pull_in_batches_of_fetch_size() {
+        let mut server = serve(vec![
+            vec![done()],
+            vec![fields(&["n"])],
+            vec![
+                Response::record(vec![1.into()]),
+                Response::record(vec![2.into()]),
+                has_more(),
+            ],
+            vec![Response::record(vec![3.into()]), done()],
+        ])
+        .await;
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .fetch_size(2)
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+
+        let rows: Vec<i64> = graph
+            .execute(query("UNWIND [1, 2, 3] AS n RETURN n"))
+            .await
+            .unwrap()
+            .into_stream()
+            .map_ok(|row| row.get::<i64>("n").unwrap())
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(rows, vec![1, 2, 3]);
+        let _hello = server.next_request().await.unwrap();
+        let _run = server.next_request().await.unwrap();
+        for _ in 0..2 {
+            let pull = server.next_request().await.unwrap();
+            assert_eq!(&pull[..5], &[0xB1, 0x3F, 0xA2, 0x81, b'n']);
+            assert_eq!(pull[5], 2);
+        }
+        assert!(server.try_next_request().is_none());
+    }
+
+    #[tokio::test]
+    async fn should_not_preallocate_beyond_a_cap_for_large_fetch_sizes() {
+        let server = serve(vec![vec![done()], vec![fields(&["n"])]]).await;
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .fetch_size(1_000_000)
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+
+        let rows = graph.execute(query("RETURN 1 AS n")).await.unwrap();
+
+        assert!(rows.buffer.capacity() < 2 * super::PREALLOCATED_ROWS);
+    }
+
+    #[tokio::test]
+    async fn should_prefer_fetch_size_of_query_over_config() {
+        let mut server = serve(vec![]).await;
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .fetch_size(2)
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+
+        for q in [
+            query("RETURN 1"),
+            query("RETURN 1").fetch_size(5),
+            query("RETURN 1").fetch_size(-1),
+            query("RETURN 1").fetch_size(0),
+        ] {
+            let mut rows = graph.execute(q).await.unwrap();
+            assert!(rows.next().await.unwrap().is_none());
+        }
+
+        let sizes: Vec<i64> = std::iter::from_fn(|| server.try_next_request())
+            .filter(|request| request[1] == 0x3F)
+            .map(|pull| {
+                let extra = BoltMap::parse(Version::V4_1, &mut pull.slice(2..));
+                extra.unwrap().get("n").unwrap()
+            })
+            .collect();
+        assert_eq!(sizes, vec![2, 5, -1, -1]);
+    }
+
+    /// A graph whose server answers RUN with the column `n` and the first PULL with `pull`
+    async fn single_column_graph(pull: Vec<Response>) -> (Graph, MockServer) {
+        let server = serve(vec![vec![done()], vec![fields(&["n"])], pull]).await;
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .build()
+            .unwrap();
+        (Graph::connect(config).await.unwrap(), server)
+    }
+
+    #[tokio::test]
+    async fn should_fetch_first_row_only() {
+        let (graph, mut server) =
+            single_column_graph(vec![Response::record(vec![1.into()]), has_more()]).await;
+
+        let row = graph.execute_one("MATCH (n) RETURN n").await.unwrap();
+
+        assert_eq!(row.unwrap().get::<i64>("n"), Some(1));
+        let sent: Vec<Bytes> = std::iter::from_fn(|| server.try_next_request()).collect();
+        assert_eq!(&sent[2][..6], &[0xB1, 0x3F, 0xA2, 0x81, b'n', 0x01]);
+        assert_eq!(&sent[3][..2], &[0xB1, 0x2F]);
+        assert_eq!(sent.len(), 4);
+
+        let (graph, _) = single_column_graph(vec![done()]).await;
+        assert!(graph
+            .execute_one("RETURN 1 LIMIT 0")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn should_fetch_exactly_one_row() {
+        let (graph, mut server) =
+            single_column_graph(vec![Response::record(vec![1.into()]), done()]).await;
+        let row = graph.execute_exactly_one("RETURN 1 AS n").await.unwrap();
+        assert_eq!(row.get::<i64>("n"), Some(1));
+        let sent: Vec<Bytes> = std::iter::from_fn(|| server.try_next_request()).collect();
+        assert_eq!(sent[2][5], 0x02);
+        assert_eq!(sent.len(), 3);
+
+        let (graph, _) = single_column_graph(vec![done()]).await;
+        assert!(matches!(
+            graph.execute_exactly_one("RETURN 1 LIMIT 0").await,
+            Err(Error::UnexpectedRowCount(_))
+        ));
+
+        let (graph, _) = single_column_graph(vec![
+            Response::record(vec![1.into()]),
+            Response::record(vec![2.into()]),
+            has_more(),
+        ])
+        .await;
+        assert!(matches!(
+            graph
+                .execute_exactly_one("UNWIND [1, 2, 3] AS n RETURN n")
+                .await,
+            Err(Error::UnexpectedRowCount(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_discard_remaining_rows() {
+        let mut server = serve(vec![
+            vec![done()],
+            vec![fields(&["n"])],
+            vec![Response::record(vec![1.into()]), has_more()],
+            vec![write_summary()],
+        ])
+        .await;
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .fetch_size(1)
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+        let mut rows = graph
+            .execute(query("UNWIND range(1, 1000) AS n RETURN n"))
+            .await
+            .unwrap();
+
+        let first = rows.next().await.unwrap().unwrap();
+        let summary = rows.discard().await.unwrap();
+
+        assert_eq!(first.get::<i64>("n"), Some(1));
+        assert_eq!(summary.query_type(), Some(QueryType::Write));
+        assert_eq!(summary.counters().nodes_created(), 3);
+        let sent: Vec<Bytes> = std::iter::from_fn(|| server.try_next_request()).collect();
+        assert_eq!(sent.len(), 4);
+        let discard = &sent[3];
+        assert_eq!(&discard[..2], &[0xB1, 0x2F]);
+        assert_eq!(&discard[2..6], &[0xA2, 0x81, b'n', 0xFF]);
+    }
+
+    #[tokio::test]
+    async fn should_reuse_connection_after_cancel() {
+        let mut server = serve(vec![
+            vec![done()],
+            vec![fields(&["n"])],
+            vec![Response::record(vec![1.into()]), has_more()],
+        ])
+        .await;
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .fetch_size(1)
+            .max_connections(1)
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+        let mut rows = graph
+            .execute(query("UNWIND range(1, 1000) AS n RETURN n"))
+            .await
+            .unwrap();
+
+        let first = rows.next().await.unwrap().unwrap();
+        rows.cancel().await.unwrap();
+        graph.run(query("RETURN 1")).await.unwrap();
+
+        assert_eq!(first.get::<i64>("n"), Some(1));
+        let sent: Vec<u8> = std::iter::from_fn(|| server.try_next_request())
+            .map(|request| request[1])
+            .collect();
+        // the pool resets the connection once more when it is checked out again
+        assert_eq!(sent, vec![0x01, 0x10, 0x3F, 0x0F, 0x0F, 0x10, 0x2F]);
+    }
+
+    #[tokio::test]
+    async fn should_cancel_stream_of_transaction_with_discard() {
+        let mut server = serve(vec![
+            vec![done()],
+            vec![done()],
+            vec![fields(&["n"])],
+            vec![Response::record(vec![1.into()]), has_more()],
+        ])
+        .await;
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .fetch_size(1)
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+        let txn = graph.start_txn().await.unwrap();
+        let mut rows = txn
+            .execute(query("UNWIND range(1, 1000) AS n RETURN n"))
+            .await
+            .unwrap();
+
+        rows.next().await.unwrap().unwrap();
+        rows.cancel().await.unwrap();
+        txn.commit().await.unwrap();
+
+        let sent: Vec<u8> = std::iter::from_fn(|| server.try_next_request())
+            .map(|request| request[1])
+            .collect();
+        assert_eq!(sent, vec![0x01, 0x11, 0x10, 0x3F, 0x2F, 0x12]);
+    }
+
+    #[tokio::test]
+    async fn should_interleave_streams_of_transaction_by_qid() {
+        let fields_of = |qid: i64| {
+            Response::success(vec![
+                ("fields", BoltType::List(vec!["n".into()].into())),
+                ("qid", qid.into()),
+            ])
+        };
+        let mut server = serve(vec![
+            vec![done()],
+            vec![done()],
+            vec![fields_of(0)],
+            vec![fields_of(1)],
+            vec![Response::record(vec![10.into()]), has_more()],
+            vec![Response::record(vec![1.into()]), done()],
+            vec![Response::record(vec![11.into()]), done()],
+        ])
+        .await;
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .fetch_size(1)
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+        let txn = graph.start_txn().await.unwrap();
+        let first = txn.execute("RETURN 1 AS n").await.unwrap();
+        let second = txn.execute("UNWIND [10, 11] AS n RETURN n").await.unwrap();
+        let mut streams = [first, second];
+
+        let mut values = Vec::new();
+        for &index in [1, 0, 1, 0, 1].iter() {
+            if let Some(row) = streams[index].next().await.unwrap() {
+                values.push(row.get::<i64>("n").unwrap());
+            }
+        }
+        txn.commit().await.unwrap();
+
+        assert_eq!(values, vec![10, 1, 11]);
+        let sent: Vec<Bytes> = std::iter::from_fn(|| server.try_next_request()).collect();
+        let pulled: Vec<i64> = sent
+            .iter()
+            .filter(|request| request[1] == 0x3F)
+            .map(|pull| {
+                let extra = BoltMap::parse(Version::V4_1, &mut pull.slice(2..));
+                extra.unwrap().get("qid").unwrap()
+            })
+            .collect();
+        assert_eq!(pulled, vec![1, 0, 1]);
+    }
+
+    #[tokio::test]
+    async fn should_report_summary_after_last_row() {
+        let server = serve(vec![
+            vec![done()],
+            vec![fields(&["n"])],
+            vec![Response::record(vec![1.into()]), write_summary()],
+        ])
+        .await;
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+        let mut rows = graph
+            .execute(query("CREATE (n) RETURN 1 AS n"))
+            .await
+            .unwrap();
+
+        assert!(rows.summary().is_none());
+        assert!(rows.next().await.unwrap().is_some());
+        assert!(rows.next().await.unwrap().is_none());
+
+        let summary = rows.summary().unwrap();
+        assert_eq!(summary.counters().nodes_created(), 3);
+        assert_eq!(
+            rows.discard().await.unwrap().query_type(),
+            Some(QueryType::Write)
+        );
+    }
+
+    #[tokio::test]
+    async fn should_end_empty_result_with_summary() {
+        let mut server = serve(vec![vec![done()], vec![fields(&[])], vec![write_summary()]]).await;
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+        let mut rows = graph.execute(query("CREATE (n)")).await.unwrap();
+
+        assert!(rows.next().await.unwrap().is_none());
+        assert!(rows.next().await.unwrap().is_none());
+
+        let summary = rows.summary().unwrap();
+        assert_eq!(summary.counters().nodes_created(), 3);
+        assert_eq!(summary.query_type(), Some(QueryType::Write));
+        let mut signatures = Vec::new();
+        while let Some(request) = server.try_next_request() {
+            signatures.push(request[1]);
+        }
+        assert_eq!(signatures, vec![0x01, 0x10, 0x3F]);
+    }
+
+    #[tokio::test]
+    async fn should_time_out_waiting_for_records() {
+        let server = serve(vec![vec![done()], vec![fields(&["n"])], vec![]]).await;
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .read_timeout(std::time::Duration::from_millis(50))
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+        let mut rows = graph.execute(query("RETURN 1 AS n")).await.unwrap();
+
+        let result = rows.next().await;
+
+        assert!(matches!(result, Err(Error::Timeout(_))));
+    }
+
+    #[cfg(feature = "serde")]
+    async fn collect_as<T: serde::de::DeserializeOwned>(
+        script: Vec<Vec<Response>>,
+    ) -> crate::errors::Result<Vec<T>> {
+        let server = serve(script).await;
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+        graph
+            .execute(query("MATCH (m:Movie) RETURN m"))
+            .await
+            .unwrap()
+            .collect_as()
+            .await
+    }
+
+    #[cfg(feature = "serde")]
+    #[tokio::test]
+    async fn should_collect_single_column_into_structs() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Movie {
+            title: String,
+            released: i64,
+        }
+        let movie = |title: &str, released: i64| {
+            BoltType::Map(
+                vec![
+                    ("title".into(), title.into()),
+                    ("released".into(), released.into()),
+                ]
+                .into_iter()
+                .collect(),
+            )
+        };
+        let records = vec![
+            Response::record(vec![movie("The Matrix", 1999)]),
+            Response::record(vec![movie("Heat", 1995)]),
+            done(),
+        ];
+
+        let movies: Vec<Movie> = collect_as(vec![vec![done()], vec![fields(&["m"])], records])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            movies,
+            vec![
+                Movie {
+                    title: "The Matrix".to_owned(),
+                    released: 1999
+                },
+                Movie {
+                    title: "Heat".to_owned(),
+                    released: 1995
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[tokio::test]
+    async fn should_collect_columns_into_tuples() {
+        let records = vec![
+            Response::record(vec!["The Matrix".into(), 1999.into()]),
+            Response::record(vec!["Heat".into(), 1995.into()]),
+            done(),
+        ];
+        let script = vec![vec![done()], vec![fields(&["title", "released"])], records];
+
+        let movies: Vec<(String, i64)> = collect_as(script.clone()).await.unwrap();
+        let invalid = collect_as::<(String, String)>(script).await;
+
+        assert_eq!(
+            movies,
+            vec![("The Matrix".to_owned(), 1999), ("Heat".to_owned(), 1995)]
+        );
+        assert!(matches!(
+            invalid,
+            Err(crate::errors::Error::DeserializationError(_))
+        ));
+    }
 }
@@ -0,0 +1,490 @@
+use crate::messages::Success;
+use crate::types::*;
+use std::convert::TryFrom;
+
+/// What a query did according to the server, see [`ResultSummary::query_type`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum QueryType {
+    Read,
+    ReadWrite,
+    Write,
+    Schema,
+}
+
+/// The changes a query made to the database, counters the server did not report are 0.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Counters {
+    stats: BoltMap,
+}
+
+impl Counters {
+    fn count(&self, key: &str) -> i64 {
+        self.stats.get(key).unwrap_or(0)
+    }
+
+    pub fn nodes_created(&self) -> i64 {
+        self.count("nodes-created")
+    }
+
+    pub fn nodes_deleted(&self) -> i64 {
+        self.count("nodes-deleted")
+    }
+
+    pub fn relationships_created(&self) -> i64 {
+        self.count("relationships-created")
+    }
+
+    pub fn relationships_deleted(&self) -> i64 {
+        self.count("relationships-deleted")
+    }
+
+    pub fn properties_set(&self) -> i64 {
+        self.count("properties-set")
+    }
+
+    pub fn labels_added(&self) -> i64 {
+        self.count("labels-added")
+    }
+
+    pub fn labels_removed(&self) -> i64 {
+        self.count("labels-removed")
+    }
+
+    pub fn indexes_added(&self) -> i64 {
+        self.count("indexes-added")
+    }
+
+    pub fn indexes_removed(&self) -> i64 {
+        self.count("indexes-removed")
+    }
+
+    pub fn constraints_added(&self) -> i64 {
+        self.count("constraints-added")
+    }
+
+    pub fn constraints_removed(&self) -> i64 {
+        self.count("constraints-removed")
+    }
+
+    pub fn system_updates(&self) -> i64 {
+        self.count("system-updates")
+    }
+
+    /// Whether the query changed any data or schema
+    pub fn contains_updates(&self) -> bool {
+        self.stats.get("contains-updates").unwrap_or_else(|| {
+            self.stats
+                .value
+                .iter()
+                .any(|(key, value)| key.value != "system-updates" && *value != 0.into())
+        })
+    }
+}
+
+/// Sums up the counters of several queries, like the batches of [`crate::Txn::bulk_unwind`]
+impl std::ops::AddAssign<&Counters> for Counters {
+    fn add_assign(&mut self, other: &Counters) {
+        for (key, value) in &other.stats.value {
+            let sum = match (self.stats.value.get(key), value) {
+                (Some(BoltType::Integer(a)), BoltType::Integer(b)) => {
+                    BoltType::from(a.value.saturating_add(b.value))
+                }
+                (Some(BoltType::Boolean(a)), BoltType::Boolean(b)) => {
+                    BoltType::from(a.value || b.value)
+                }
+                _ => value.clone(),
+            };
+            self.stats.value.insert(key.clone(), sum);
+        }
+    }
+}
+
+/// An operator of the plan of an `EXPLAIN` or `PROFILE` query, see [`ResultSummary::plan`].
+/// The operators it reads its input from are its children.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlanNode {
+    operator_type: String,
+    args: BoltMap,
+    identifiers: Vec<String>,
+    children: Vec<PlanNode>,
+    db_hits: Option<i64>,
+    rows: Option<i64>,
+}
+
+impl PlanNode {
+    fn parse(mut plan: BoltMap) -> PlanNode {
+        let mut take = |key: &str| plan.value.remove(&BoltString::from(key));
+        let children = match take("children") {
+            Some(BoltType::List(children)) => children
+                .value
+                .into_iter()
+                .filter_map(|child| BoltMap::try_from(child).ok())
+                .map(PlanNode::parse)
+                .collect(),
+            _ => Vec::new(),
+        };
+        PlanNode {
+            operator_type: take("operatorType")
+                .and_then(|value| String::try_from(value).ok())
+                .unwrap_or_default(),
+            args: take("args")
+                .and_then(|value| BoltMap::try_from(value).ok())
+                .unwrap_or_default(),
+            identifiers: take("identifiers")
+                .and_then(|value| Vec::try_from(value).ok())
+                .unwrap_or_default(),
+            children,
+            db_hits: take("dbHits").and_then(|value| i64::try_from(value).ok()),
+            rows: take("rows").and_then(|value| i64::try_from(value).ok()),
+        }
+    }
+
+    /// The name of the operator, like `NodeByLabelScan@neo4j`
+    pub fn operator_type(&self) -> &str {
+        &self.operator_type
+    }
+
+    /// The details of the operator, like `EstimatedRows` or `Details`
+    pub fn args(&self) -> &BoltMap {
+        &self.args
+    }
+
+    /// The variables the operator produces
+    pub fn identifiers(&self) -> &[String] {
+        &self.identifiers
+    }
+
+    pub fn children(&self) -> &[PlanNode] {
+        &self.children
+    }
+
+    /// The database accesses of the operator, profiled plans only
+    pub fn db_hits(&self) -> Option<i64> {
+        self.db_hits
+    }
+
+    /// The rows the operator produced, profiled plans only
+    pub fn rows(&self) -> Option<i64> {
+        self.rows
+    }
+}
+
+/// A warning or hint of the server about a query, like a cartesian product or an unbound
+/// variable, see [`ResultSummary::notifications`]. Fields the server did not send are empty.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Notification {
+    code: String,
+    title: String,
+    description: String,
+    severity: String,
+    position: Option<InputPosition>,
+}
+
+/// A position in the query text, `offset` counts characters from 0 while `line` and `column`
+/// count from 1
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InputPosition {
+    pub offset: i64,
+    pub line: i64,
+    pub column: i64,
+}
+
+impl Notification {
+    fn parse(notification: &BoltMap) -> Notification {
+        let text = |key: &str| notification.get::<String>(key).unwrap_or_default();
+        let position = notification.get::<BoltMap>("position").map(|position| {
+            let number = |key: &str| position.get::<i64>(key).unwrap_or_default();
+            InputPosition {
+                offset: number("offset"),
+                line: number("line"),
+                column: number("column"),
+            }
+        });
+        Notification {
+            code: text("code"),
+            title: text("title"),
+            description: text("description"),
+            severity: text("severity"),
+            position,
+        }
+    }
+
+    /// The status code, like `Neo.ClientNotification.Statement.CartesianProduct`
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// `WARNING` or `INFORMATION`
+    pub fn severity(&self) -> &str {
+        &self.severity
+    }
+
+    /// Where in the query the notification applies, if it applies to a part of it
+    pub fn position(&self) -> Option<InputPosition> {
+        self.position
+    }
+}
+
+/// The summary the server sends once all rows of a query are consumed or discarded, see
+/// [`crate::RowStream::summary`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct ResultSummary {
+    query_type: Option<QueryType>,
+    counters: Counters,
+    t_first: Option<i64>,
+    t_last: Option<i64>,
+    plan: Option<PlanNode>,
+    notifications: Vec<Notification>,
+}
+
+impl ResultSummary {
+    /// The summary from the SUCCESS of the last PULL or DISCARD, `t_first` is reported in the
+    /// SUCCESS of RUN.
+    pub(crate) fn new(t_first: Option<i64>, success: &Success) -> ResultSummary {
+        let query_type = match success.get::<String>("type").as_deref() {
+            Some("r") => Some(QueryType::Read),
+            Some("rw") => Some(QueryType::ReadWrite),
+            Some("w") => Some(QueryType::Write),
+            Some("s") => Some(QueryType::Schema),
+            _ => None,
+        };
+        ResultSummary {
+            query_type,
+            counters: Counters {
+                stats: success.get("stats").unwrap_or_default(),
+            },
+            t_first,
+            t_last: success.get("t_last"),
+            plan: success
+                .get("profile")
+                .or_else(|| success.get("plan"))
+                .map(PlanNode::parse),
+            notifications: success
+                .get::<Vec<BoltMap>>("notifications")
+                .unwrap_or_default()
+                .iter()
+                .map(Notification::parse)
+                .collect(),
+        }
+    }
+
+    pub fn query_type(&self) -> Option<QueryType> {
+        self.query_type
+    }
+
+    pub fn counters(&self) -> &Counters {
+        &self.counters
+    }
+
+    /// Milliseconds until the first row was available
+    pub fn t_first(&self) -> Option<i64> {
+        self.t_first
+    }
+
+    /// Milliseconds until the last row was consumed
+    pub fn t_last(&self) -> Option<i64> {
+        self.t_last
+    }
+
+    /// The plan of an `EXPLAIN` query, or the plan with db hits and rows of a `PROFILE` query.
+    /// `None` for queries without either prefix.
+    pub fn plan(&self) -> Option<&PlanNode> {
+        self.plan.as_ref()
+    }
+
+    /// The warnings and hints of the server about the query, empty if it had none
+    pub fn notifications(&self) -> &[Notification] {
+        &self.notifications
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::Version;
+    use bytes::*;
+
+    fn success(metadata: BoltMap) -> Success {
+        let mut bytes = BytesMut::from(&[0xB1, 0x70][..]);
+        bytes.put(metadata.into_bytes(Version::V4_1).unwrap());
+        Success::parse(Version::V4_1, &mut bytes.freeze()).unwrap()
+    }
+
+    #[test]
+    fn should_sum_counters() {
+        let counters = |stats: Vec<(BoltString, BoltType)>| Counters {
+            stats: stats.into_iter().collect(),
+        };
+        let mut sum = counters(vec![
+            ("nodes-created".into(), 2.into()),
+            ("contains-updates".into(), false.into()),
+        ]);
+
+        sum += &counters(vec![
+            ("nodes-created".into(), 3.into()),
+            ("properties-set".into(), 1.into()),
+            ("contains-updates".into(), true.into()),
+        ]);
+
+        assert_eq!(sum.nodes_created(), 5);
+        assert_eq!(sum.properties_set(), 1);
+        assert!(sum.contains_updates());
+    }
+
+    #[test]
+    fn should_read_counters_and_timings() {
+        let stats: BoltMap = vec![
+            ("nodes-created".into(), 2.into()),
+            ("properties-set".into(), 5.into()),
+        ]
+        .into_iter()
+        .collect();
+        let metadata = vec![
+            ("type".into(), "w".into()),
+            ("t_last".into(), 3.into()),
+            ("stats".into(), stats.into()),
+        ]
+        .into_iter()
+        .collect();
+
+        let summary = ResultSummary::new(Some(7), &success(metadata));
+
+        assert_eq!(summary.query_type(), Some(QueryType::Write));
+        assert_eq!(summary.t_first(), Some(7));
+        assert_eq!(summary.t_last(), Some(3));
+        let counters = summary.counters();
+        assert_eq!(counters.nodes_created(), 2);
+        assert_eq!(counters.properties_set(), 5);
+        assert_eq!(counters.relationships_created(), 0);
+        assert!(counters.contains_updates());
+    }
+
+    #[test]
+    fn should_default_missing_stats() {
+        let summary = ResultSummary::new(None, &success(BoltMap::default()));
+
+        assert_eq!(summary.query_type(), None);
+        assert_eq!(summary.plan(), None);
+        assert!(summary.notifications().is_empty());
+        assert_eq!(summary.counters(), &Counters::default());
+        assert_eq!(summary.counters().nodes_deleted(), 0);
+        assert!(!summary.counters().contains_updates());
+    }
+
+    #[test]
+    fn should_parse_profiled_plan() {
+        let operator = |name: &str, db_hits: i64, children: Vec<BoltType>| -> BoltType {
+            let args: BoltMap = vec![("EstimatedRows".into(), 10.0.into())]
+                .into_iter()
+                .collect();
+            let plan: BoltMap = vec![
+                ("operatorType".into(), name.into()),
+                ("args".into(), args.into()),
+                (
+                    "identifiers".into(),
+                    BoltType::List(vec!["n".into()].into()),
+                ),
+                ("children".into(), BoltType::List(children.into())),
+                ("dbHits".into(), db_hits.into()),
+                ("rows".into(), 3.into()),
+                ("pageCacheHits".into(), 1.into()),
+            ]
+            .into_iter()
+            .collect();
+            plan.into()
+        };
+        let scan = operator("NodeByLabelScan@neo4j", 4, vec![]);
+        let filter = operator("Filter@neo4j", 8, vec![scan]);
+        let profile = operator("ProduceResults@neo4j", 0, vec![filter]);
+        let metadata = vec![("profile".into(), profile)].into_iter().collect();
+
+        let summary = ResultSummary::new(None, &success(metadata));
+
+        let root = summary.plan().unwrap();
+        assert_eq!(root.operator_type(), "ProduceResults@neo4j");
+        assert_eq!(root.identifiers(), &["n".to_owned()]);
+        assert_eq!(root.args().get::<f64>("EstimatedRows"), Some(10.0));
+        assert_eq!((root.db_hits(), root.rows()), (Some(0), Some(3)));
+        let scan = &root.children()[0].children()[0];
+        assert_eq!(scan.operator_type(), "NodeByLabelScan@neo4j");
+        assert_eq!(scan.db_hits(), Some(4));
+        assert!(scan.children().is_empty());
+    }
+
+    #[test]
+    fn should_parse_explained_plan_without_statistics() {
+        let plan: BoltMap = vec![("operatorType".into(), "ProduceResults@neo4j".into())]
+            .into_iter()
+            .collect();
+        let metadata = vec![("plan".into(), plan.into())].into_iter().collect();
+
+        let summary = ResultSummary::new(None, &success(metadata));
+
+        let root = summary.plan().unwrap();
+        assert_eq!(root.operator_type(), "ProduceResults@neo4j");
+        assert_eq!((root.db_hits(), root.rows()), (None, None));
+        assert!(root.children().is_empty() && root.args().value.is_empty());
+    }
+
+    #[test]
+    fn should_parse_notifications() {
+        let position: BoltMap = vec![
+            ("offset".into(), 0.into()),
+            ("line".into(), 1.into()),
+            ("column".into(), 1.into()),
+        ]
+        .into_iter()
+        .collect();
+        let notification: BoltMap = vec![
+            (
+                "code".into(),
+                "Neo.ClientNotification.Statement.CartesianProduct".into(),
+            ),
+            (
+                "title".into(),
+                "This query builds a cartesian product".into(),
+            ),
+            ("severity".into(), "WARNING".into()),
+            ("position".into(), position.into()),
+        ]
+        .into_iter()
+        .collect();
+        let metadata = vec![(
+            "notifications".into(),
+            BoltType::List(vec![notification.into()].into()),
+        )]
+        .into_iter()
+        .collect();
+
+        let summary = ResultSummary::new(None, &success(metadata));
+
+        assert_eq!(summary.notifications().len(), 1);
+        let notification = &summary.notifications()[0];
+        assert_eq!(
+            notification.code(),
+            "Neo.ClientNotification.Statement.CartesianProduct"
+        );
+        assert_eq!(
+            notification.title(),
+            "This query builds a cartesian product"
+        );
+        assert_eq!(notification.description(), "");
+        assert_eq!(notification.severity(), "WARNING");
+        assert_eq!(
+            notification.position(),
+            Some(InputPosition {
+                offset: 0,
+                line: 1,
+                column: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,418 @@
+use crate::chunk::{chunk, ChunkedReader, ChunkedWriter};
+use crate::errors::{Error, Result};
+use crate::messages::{BoltRequest, BoltResponse, Run};
+use crate::types::*;
+use crate::version::Version;
+use bytes::Bytes;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+type Script = Arc<Mutex<VecDeque<Reply>>>;
+
+/// What the connection receiving the next unanswered request does with it
+enum Reply {
+    Respond(Vec<Response>),
+    HangUp,
+}
+
+/// A response of the [`MockServer`], encoded in the version it negotiated.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Response {
+    Success(BoltMap),
+    Record(BoltList),
+    Failure { code: String, message: String },
+    Ignored,
+}
+
+impl Response {
+    /// A SUCCESS with the `metadata` entries
+    pub fn success<'a>(metadata: impl IntoIterator<Item = (&'a str, BoltType)>) -> Response {
+        Response::Success(
+            metadata
+                .into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        )
+    }
+
+    /// A RECORD with the `values` of a row
+    pub fn record(values: Vec<BoltType>) -> Response {
+        Response::Record(values.into())
+    }
+
+    /// A FAILURE with the `code` and `message` of the error
+    pub fn failure(code: &str, message: &str) -> Response {
+        Response::Failure {
+            code: code.to_owned(),
+            message: message.to_owned(),
+        }
+    }
+
+    /// Decodes a response read from the wire, without its chunking. A FAILURE without code or
+    /// message decodes with empty strings.
+    ///
+    /// ```
+    /// use neo4rs::testkit::Response;
+    /// use neo4rs::Version;
+    ///
+    /// let response = Response::parse(Version::V4_4, vec![0xB1, 0x71, 0x91, 0x2A].into());
+    ///
+    /// assert_eq!(response.unwrap(), Response::Record(vec![42.into()].into()));
+    /// ```
+    pub fn parse(version: Version, bytes: Bytes) -> Result<Response> {
+        let response = match BoltResponse::parse(version, bytes)? {
+            BoltResponse::SuccessMessage(success) => Response::Success(success.metadata),
+            BoltResponse::RecordMessage(record) => Response::Record(record.data),
+            BoltResponse::FailureMessage(failure) => Response::Failure {
+                code: failure.get("code").unwrap_or_default(),
+                message: failure.get("message").unwrap_or_default(),
+            },
+            BoltResponse::IgnoredMessage(_) => Response::Ignored,
+        };
+        Ok(response)
+    }
+
+    fn into_bytes(self, version: Version) -> Result<Bytes> {
+        let (signature, fields) = match self {
+            Response::Success(metadata) => (0x70, vec![BoltType::Map(metadata)]),
+            Response::Record(fields) => (0x71, vec![BoltType::List(fields)]),
+            Response::Failure { code, message } => {
+                let metadata = vec![
+                    ("code".into(), code.into()),
+                    ("message".into(), message.into()),
+                ];
+                (0x7F, vec![BoltType::Map(metadata.into_iter().collect())])
+            }
+            Response::Ignored => (0x7E, vec![]),
+        };
+        BoltStructure::new(signature, fields.into()).into_bytes(version)
+    }
+}
+
+/// A bolt server on a local port for tests without a database. It accepts any number of
+/// connections, completes their handshake with a fixed version and answers every request with
+/// the responses queued next through [`MockServer::respond`], or with an empty SUCCESS if none
+/// are queued. The queue is shared by all connections.
+pub struct MockServer {
+    addr: String,
+    script: Script,
+    requests: UnboundedReceiver<Bytes>,
+}
+
+impl MockServer {
+    /// Starts a server which accepts connections proposing `version`
+    pub async fn start(version: Version) -> Result<MockServer> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?.to_string();
+        let script: Script = Default::default();
+        let (sender, requests) = unbounded_channel();
+        let accepted = script.clone();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(handle(socket, version, accepted.clone(), sender.clone()));
+            }
+        });
+        Ok(MockServer {
+            addr,
+            script,
+            requests,
+        })
+    }
+
+    /// The uri to configure the driver with
+    pub fn uri(&self) -> String {
+        format!("bolt://{}", self.addr)
+    }
+
+    /// The `host:port` the server listens on, as the routing table of a cluster lists it
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+
+    /// Queues the responses to the next request that has none queued yet, the HELLO of every
+    /// connection is a request as well.
+    pub fn respond(&self, responses: Vec<Response>) {
+        self.script
+            .lock()
+            .unwrap()
+            .push_back(Reply::Respond(responses));
+    }
+
+    /// Closes the connection which receives the next request that has no responses queued yet,
+    /// instead of answering it, like a server that went down.
+    pub fn hang_up(&self) {
+        self.script.lock().unwrap().push_back(Reply::HangUp);
+    }
+
+    /// The next request received on any of the connections, without its chunking, or `None`
+    /// once the server stopped.
+    pub async fn next_request(&mut self) -> Option<Bytes> {
+        self.requests.recv().await
+    }
+
+    /// The next request if one was received already, to check that no further request was sent
+    pub fn try_next_request(&mut self) -> Option<Bytes> {
+        self.requests.try_recv().ok()
+    }
+}
+
+/// Encodes a RUN of `query` with `params` and the `extra` map as it is written to the socket,
+/// split into chunks and followed by the end marker.
+///
+/// ```
+/// use neo4rs::testkit::encode_run;
+/// use neo4rs::types::BoltMap;
+/// use neo4rs::Version;
+///
+/// let bytes = encode_run(Version::V4_4, "RETURN 1", BoltMap::default(), BoltMap::default());
+///
+/// assert_eq!(&bytes.unwrap()[..6], &[0x00, 0x0D, 0xB3, 0x10, 0x88, b'R']);
+/// ```
+pub fn encode_run(version: Version, query: &str, params: BoltMap, extra: BoltMap) -> Result<Bytes> {
+    let run = Run::with_extra(query.into(), params, extra).into_bytes(version)?;
+    Ok(chunk(&run))
+}
+
+/// Encodes a RUN like [`encode_run`], with the parameters prepared in `prepared` sent in the
+/// same map as `params`.
+///
+/// ```
+/// use neo4rs::testkit::{encode_prepared_run, encode_run};
+/// use neo4rs::types::{BoltMap, PreparedParams};
+/// use neo4rs::Version;
+///
+/// let mut limit = BoltMap::default();
+/// limit.put("limit".into(), 10.into());
+/// let prepared = PreparedParams::new(limit.clone()).unwrap();
+///
+/// assert_eq!(
+///     encode_prepared_run(Version::V4_4, "RETURN 1", &prepared, BoltMap::default(), BoltMap::default()).unwrap(),
+///     encode_run(Version::V4_4, "RETURN 1", limit, BoltMap::default()).unwrap()
+/// );
+/// ```
+pub fn encode_prepared_run(
+    version: Version,
+    query: &str,
+    prepared: &PreparedParams,
+    params: BoltMap,
+    extra: BoltMap,
+) -> Result<Bytes> {
+    let run = Run::with_extra(query.into(), params, extra)
+        .with_prepared(prepared.clone())
+        .into_bytes(version)?;
+    Ok(chunk(&run))
+}
+
+/// A bolt message decoded by [`decode_message`], the fields as the generic structure decoder
+/// reads them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    /// The name of the message, like `RECORD` or `RUN`
+    pub name: &'static str,
+    pub signature: u8,
+    pub fields: BoltList,
+}
+
+/// Decodes a chunked message as it was captured from the wire, in either direction. Useful to
+/// find out what a hex dump of a connection means.
+///
+/// ```
+/// use neo4rs::testkit::decode_message;
+/// use neo4rs::Version;
+///
+/// // a RECORD with the values 1 and "a", split into two chunks
+/// let dump = [0x00, 0x03, 0xB1, 0x71, 0x92, 0x00, 0x03, 0x01, 0x81, b'a', 0x00, 0x00];
+/// let message = decode_message(Version::V4_4, &dump).unwrap();
+///
+/// assert_eq!(message.name, "RECORD");
+/// let values = message.fields.get(0).and_then(|values| values.as_list()).unwrap();
+/// assert_eq!(values.get(0).unwrap().as_i64(), Some(1));
+/// assert_eq!(values.get(1).unwrap().as_str(), Some("a"));
+/// ```
+pub fn decode_message(version: Version, dump: &[u8]) -> Result<Message> {
+    let mut input = dump;
+    let mut bytes = futures::executor::block_on(ChunkedReader::new(&mut input).read_message())?;
+    if !input.is_empty() {
+        return Err(Error::DeserializationError(format!(
+            "{} bytes left after the message",
+            input.len()
+        )));
+    }
+    let message = bytes.clone();
+    let structure = BoltStructure::parse(version, &mut bytes)?;
+    if !bytes.is_empty() {
+        return Err(Error::DeserializationError(format!(
+            "{} bytes left after the fields of the message",
+            bytes.len()
+        )));
+    }
+    let name = match BoltResponse::parse(version, message.clone()) {
+        Ok(response) => response.name(),
+        Err(Error::UnknownMessage(_)) => BoltRequest::parse(version, message)?.name(),
+        Err(e) => return Err(e),
+    };
+    Ok(Message {
+        name,
+        signature: structure.signature,
+        fields: structure.fields,
+    })
+}
+
+async fn handle(
+    mut socket: TcpStream,
+    version: Version,
+    script: Script,
+    requests: UnboundedSender<Bytes>,
+) -> Result<()> {
+    let mut handshake = [0; 20];
+    socket.read_exact(&mut handshake).await?;
+    socket.write_u32(version.as_u32()).await?;
+    loop {
+        let request = ChunkedReader::new(&mut socket).read_message().await?;
+        let _ = requests.send(request);
+        let reply = script.lock().unwrap().pop_front();
+        let responses = match reply {
+            Some(Reply::Respond(responses)) => responses,
+            Some(Reply::HangUp) => return Ok(()),
+            None => vec![Response::Success(BoltMap::default())],
+        };
+        for response in responses {
+            ChunkedWriter::new(&mut socket)
+                .write_message(&response.into_bytes(version)?)
+                .await?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::config;
+    use crate::graph::{query, Graph};
+
+    #[tokio::test]
+    async fn should_run_and_pull_from_mock_server() {
+        let mut server = MockServer::start(Version::V4_1).await.unwrap();
+        let fields = vec![("fields".into(), BoltType::List(vec!["n".into()].into()))];
+        server.respond(vec![Response::Success(BoltMap::default())]);
+        server.respond(vec![Response::Success(fields.into_iter().collect())]);
+        server.respond(vec![
+            Response::Record(vec![42.into()].into()),
+            Response::Success(BoltMap::default()),
+        ]);
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .versions(&[Version::V4_1])
+            .build()
+            .unwrap();
+        let graph = Graph::connect(config).await.unwrap();
+
+        let mut rows = graph.execute(query("RETURN 42 AS n")).await.unwrap();
+        let row = rows.next().await.unwrap().unwrap();
+        assert_eq!(row.get::<i64>("n"), Some(42));
+        assert!(rows.next().await.unwrap().is_none());
+
+        let mut signatures = Vec::new();
+        for _ in 0..3 {
+            signatures.push(server.next_request().await.unwrap()[1]);
+        }
+        assert_eq!(signatures, vec![0x01, 0x10, 0x3F]);
+    }
+
+    #[test]
+    fn should_decode_captured_messages() {
+        let record = decode_message(
+            Version::V4_4,
+            &[0x00, 0x05, 0xB1, 0x71, 0x92, 0x2A, 0xC0, 0x00, 0x00],
+        )
+        .unwrap();
+        assert_eq!(record.name, "RECORD");
+        assert_eq!(
+            record.fields,
+            vec![BoltType::List(vec![42.into(), BoltType::null()].into())].into()
+        );
+
+        let reset = decode_message(Version::V4_4, &[0x00, 0x02, 0xB0, 0x0F, 0x00, 0x00]).unwrap();
+        assert_eq!((reset.name, reset.signature), ("RESET", 0x0F));
+        assert!(reset.fields.is_empty());
+    }
+
+    #[test]
+    fn should_reject_undecodable_dumps() {
+        let unknown = decode_message(Version::V4_4, &[0x00, 0x02, 0xB0, 0x55, 0x00, 0x00]);
+        assert!(matches!(unknown, Err(Error::UnknownMessage(_))));
+        let trailing = decode_message(Version::V4_4, &[0x00, 0x03, 0xB0, 0x0F, 0x01, 0x00, 0x00]);
+        assert!(matches!(trailing, Err(Error::DeserializationError(_))));
+        let truncated = decode_message(Version::V4_4, &[0x00, 0x04, 0xB1, 0x71]);
+        assert!(truncated.is_err());
+    }
+
+    #[test]
+    fn should_parse_each_response() {
+        let parse =
+            |bytes: &'static [u8]| Response::parse(Version::V4_4, Bytes::from_static(bytes));
+
+        let success = parse(&[0xB1, 0x70, 0xA1, 0x81, b't', 0x05]).unwrap();
+        let metadata = vec![("t".into(), 5.into())].into_iter().collect();
+        assert_eq!(success, Response::Success(metadata));
+
+        let record = parse(&[0xB1, 0x71, 0x92, 0x01, 0x81, b'a']).unwrap();
+        assert_eq!(record, Response::Record(vec![1.into(), "a".into()].into()));
+
+        let failure = parse(&[
+            0xB1, 0x7F, 0xA2, 0x84, b'c', b'o', b'd', b'e', 0x81, b'E', 0x87, b'm', b'e', b's',
+            b's', b'a', b'g', b'e', 0x83, b'b', b'a', b'd',
+        ])
+        .unwrap();
+        assert_eq!(
+            failure,
+            Response::Failure {
+                code: "E".to_owned(),
+                message: "bad".to_owned()
+            }
+        );
+
+        assert_eq!(parse(&[0xB0, 0x7E]).unwrap(), Response::Ignored);
+        assert!(matches!(
+            parse(&[0xB0, 0x10]),
+            Err(Error::UnknownMessage(_))
+        ));
+    }
+
+    #[test]
+    fn should_parse_what_the_mock_server_encodes() {
+        let failure = Response::Failure {
+            code: "Neo.ClientError.Statement.SyntaxError".to_owned(),
+            message: "invalid".to_owned(),
+        };
+        let bytes = failure.clone().into_bytes(Version::V5_0).unwrap();
+
+        assert_eq!(Response::parse(Version::V5_0, bytes).unwrap(), failure);
+    }
+
+    #[test]
+    fn should_encode_chunked_run() {
+        let extra = vec![("db".into(), "neo4j".into())].into_iter().collect();
+
+        let bytes = encode_run(Version::V4_4, "RETURN 1", BoltMap::default(), extra).unwrap();
+
+        let expected: Vec<u8> = [
+            &[0x00, 0x16, 0xB3, 0x10, 0x88][..],
+            b"RETURN 1",
+            &[0xA0, 0xA1, 0x82],
+            b"db",
+            &[0x85],
+            b"neo4j",
+            &[0x00, 0x00],
+        ]
+        .concat();
+        assert_eq!(bytes, Bytes::from(expected));
+        let run = decode_message(Version::V4_4, &bytes).unwrap();
+        assert_eq!((run.name, run.fields.len()), ("RUN", 3));
+    }
+}
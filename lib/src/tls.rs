@@ -0,0 +1,107 @@
+use crate::errors::{Error, Result};
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::client::{ServerCertVerified, ServerCertVerifier};
+use tokio_rustls::rustls::{self, Certificate, ClientConfig, RootCertStore, ServerName};
+use tokio_rustls::TlsConnector;
+
+/// Wraps the tcp stream into a tls session with the server.
+///
+/// The server certificate is verified against the roots of the operating system unless a
+/// `ca_cert` is configured, in that case only certificates signed by the given CA are trusted.
+/// With `trust_any_certificate` every certificate is accepted, which is what `+ssc` uris ask for
+/// to reach servers with self signed certificates.
+pub async fn connect(
+    stream: TcpStream,
+    host: &str,
+    ca_cert: Option<&Path>,
+    trust_any_certificate: bool,
+) -> Result<TlsStream<TcpStream>> {
+    let builder = ClientConfig::builder().with_safe_defaults();
+    let config = if trust_any_certificate {
+        builder
+            .with_custom_certificate_verifier(Arc::new(AnyCertificate))
+            .with_no_client_auth()
+    } else {
+        let roots = match ca_cert {
+            Some(path) => pinned_roots(path)?,
+            None => native_roots()?,
+        };
+        builder.with_root_certificates(roots).with_no_client_auth()
+    };
+    let domain = ServerName::try_from(host)
+        .map_err(|_| Error::TlsError(format!("invalid server name {}", host)))?;
+    Ok(TlsConnector::from(Arc::new(config))
+        .connect(domain, stream)
+        .await?)
+}
+
+/// Accepts whatever certificate the server presents, the handshake signatures are still checked.
+struct AnyCertificate;
+
+impl ServerCertVerifier for AnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+fn native_roots() -> Result<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+    let certs = rustls_native_certs::load_native_certs()?;
+    roots.add_parsable_certificates(&certs);
+    Ok(roots)
+}
+
+fn pinned_roots(path: &Path) -> Result<RootCertStore> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader)?;
+    if certs.is_empty() {
+        return Err(Error::TlsError(format!(
+            "no certificates found in {}",
+            path.display()
+        )));
+    }
+    let mut roots = RootCertStore::empty();
+    for cert in certs {
+        roots
+            .add(&Certificate(cert))
+            .map_err(|e| Error::TlsError(e.to_string()))?;
+    }
+    Ok(roots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_reject_ca_file_without_certificates() {
+        let path = std::env::temp_dir().join(format!("neo4rs-empty-ca-{}.pem", std::process::id()));
+        std::fs::write(&path, "not a certificate").unwrap();
+
+        let result = pinned_roots(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(Error::TlsError(_))));
+    }
+
+    #[test]
+    fn should_fail_on_missing_ca_file() {
+        let result = pinned_roots(Path::new("/does/not/exist.pem"));
+        assert!(matches!(result, Err(Error::IOError { .. })));
+    }
+}
@@ -1,66 +1,635 @@
+use crate::auth::Auth;
 use crate::config::Config;
 use crate::errors::*;
 use crate::messages::*;
 use crate::pool::*;
 use crate::query::*;
+use crate::routing::AccessMode;
+use crate::row::Row;
 use crate::stream::*;
+use crate::summary::Counters;
+use crate::types::{BoltEncode, BoltList, BoltMap, BoltType};
+use log::warn;
+use std::convert::TryFrom;
+use std::mem;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
+/// The number of requests [`Txn::run_queries`] sends ahead of their responses
+const PIPELINE_DEPTH: usize = 32;
+
+/// The encoded size at which [`Txn::bulk_unwind`] ends a batch early, so that the RUN of a batch
+/// of large rows stays reasonably small
+const MAX_BATCH_BYTES: usize = 1 << 20;
+
+/// Options for beginning a transaction, see [`crate::Graph::start_txn_with`]
+#[derive(Debug, Clone, Default)]
+pub struct TxnOptions {
+    bookmarks: Vec<String>,
+    timeout: Option<Duration>,
+    metadata: BoltMap,
+    mode: AccessMode,
+    impersonated_user: Option<String>,
+    auth: Option<Auth>,
+}
+
+impl TxnOptions {
+    /// The bookmarks of earlier transactions, the transaction begins once the server caught up
+    /// with them
+    pub fn with_bookmarks(mut self, bookmarks: Vec<String>) -> Self {
+        self.bookmarks = bookmarks;
+        self
+    }
+
+    /// The server terminates the transaction if it runs longer than `timeout`, instead of the
+    /// timeout configured on the server. The timeout is sent in whole milliseconds, shorter ones
+    /// are rounded up to one millisecond as the server reads zero as no timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Metadata which is shown with the transaction in the query log and
+    /// `dbms.listTransactions`
+    pub fn with_metadata(mut self, metadata: BoltMap) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Whether the transaction only reads, the server of a [`crate::RoutingDriver`] transaction
+    /// is picked accordingly.
+    pub fn with_mode(mut self, mode: AccessMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Runs the transaction as `user` instead of the authenticated user, which requires bolt 4.4
+    /// or later and the permission to impersonate on the server
+    pub fn with_impersonated_user(mut self, user: &str) -> Self {
+        self.impersonated_user = Some(user.to_owned());
+        self
+    }
+
+    /// Runs the transaction as the user of `auth` instead of the configured user, which requires
+    /// bolt 5.1 or later. The connection is authenticated again with LOGOFF and LOGON, and switches
+    /// back to the configured user when it returns to the pool.
+    pub fn with_auth(mut self, auth: Auth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    pub(crate) fn mode(&self) -> AccessMode {
+        self.mode
+    }
+
+    /// The extra map of BEGIN, the home database is used if `db` is empty
+    fn extra(&self, db: &str) -> BoltMap {
+        let mut extra = BoltMap::default();
+        if !db.is_empty() {
+            extra.put("db".into(), db.into());
+        }
+        if !self.bookmarks.is_empty() {
+            extra.put("bookmarks".into(), self.bookmarks.clone().into());
+        }
+        if let Some(timeout) = self.timeout {
+            let millis = i64::try_from(timeout.as_millis())
+                .unwrap_or(i64::MAX)
+                .max(1);
+            extra.put("tx_timeout".into(), millis.into());
+        }
+        if !self.metadata.value.is_empty() {
+            extra.put("tx_metadata".into(), self.metadata.clone().into());
+        }
+        if let Some(user) = &self.impersonated_user {
+            extra.put("imp_user".into(), user.as_str().into());
+        }
+        self.mode.put_into(&mut extra);
+        extra
+    }
+}
+
 /// A handle which is used to control a transaction, created as a result of [`Graph::start_txn`]
 ///
 /// When a transation is started, a dedicated connection is resered and moved into the handle which
 /// will be released to the connection pool when the [`Txn`] handle is dropped.
+///
+/// A transaction which is dropped without calling [`Txn::commit`] or [`Txn::rollback`] is rolled
+/// back in the background.
 pub struct Txn {
     config: Config,
     connection: Arc<Mutex<ManagedConnection>>,
+    bookmarks: Option<Arc<std::sync::Mutex<Vec<String>>>>,
+    finished: bool,
 }
 
 impl Txn {
-    pub(crate) async fn new(config: Config, mut connection: ManagedConnection) -> Result<Self> {
-        let begin = BoltRequest::begin();
+    pub(crate) async fn new(
+        config: Config,
+        mut connection: ManagedConnection,
+        options: TxnOptions,
+    ) -> Result<Self> {
+        if options.impersonated_user.is_some() && !connection.version().has_impersonation() {
+            return Err(Error::UnsupportedVersion(format!(
+                "impersonation requires bolt 4.4 or later, the server speaks {:?}",
+                connection.version()
+            )));
+        }
+        if let Some(auth) = &options.auth {
+            connection.reauthenticate(auth).await?;
+        }
+        let begin = BoltRequest::begin(options.extra(&config.db));
         match connection.send_recv(begin).await? {
             BoltResponse::SuccessMessage(_) => Ok(Txn {
-                config,
+                // the database is selected by BEGIN, queries of the transaction must not repeat it
+                config: Config {
+                    db: String::new(),
+                    ..config
+                },
                 connection: Arc::new(Mutex::new(connection)),
+                bookmarks: None,
+                finished: false,
             }),
+            BoltResponse::FailureMessage(failure) => Err(connection.failed(failure).await),
             msg => Err(unexpected(msg, "BEGIN")),
         }
     }
 
-    /// Runs multiple queries one after the other in the same connection
+    /// Stores the bookmark of the commit in `bookmarks`, which a [`crate::Session`] passes on to
+    /// its next transaction
+    pub(crate) fn chain(mut self, bookmarks: Arc<std::sync::Mutex<Vec<String>>>) -> Self {
+        self.bookmarks = Some(bookmarks);
+        self
+    }
+
+    /// Runs multiple queries one after the other in the same connection and discards their
+    /// streams. The queries are pipelined, they are sent without waiting for the server to answer
+    /// the ones before. Queries after a failing query are ignored by the server.
     pub async fn run_queries(&self, queries: Vec<Query>) -> Result<()> {
+        let mut connection = self.connection.lock().await;
+        let mut pipeline = connection.pipeline(PIPELINE_DEPTH);
         for query in queries.into_iter() {
-            self.run(query).await?;
+            pipeline
+                .send(query.in_txn().into_request(&self.config))
+                .await?;
+            pipeline.send(BoltRequest::discard()).await?;
         }
-        Ok(())
+        pipeline.finish().await
     }
 
     /// Runs a single query and discards the stream.
-    pub async fn run(&self, q: Query) -> Result<()> {
-        q.run(&self.config, self.connection.clone()).await
+    pub async fn run(&self, q: impl Into<Query>) -> Result<()> {
+        q.into()
+            .in_txn()
+            .run(&self.config, self.connection.clone())
+            .await
     }
 
     /// Executes a query and returns a [`RowStream`]
-    pub async fn execute(&self, q: Query) -> Result<RowStream> {
-        q.execute(&self.config, self.connection.clone()).await
+    pub async fn execute(&self, q: impl Into<Query>) -> Result<RowStream> {
+        q.into()
+            .in_txn()
+            .execute(&self.config, self.connection.clone())
+            .await
+    }
+
+    /// Executes a query and returns its first row, see [`crate::Graph::execute_one`]
+    pub async fn execute_one(&self, q: impl Into<Query>) -> Result<Option<Row>> {
+        q.into()
+            .in_txn()
+            .fetch_one(&self.config, self.connection.clone())
+            .await
+    }
+
+    /// Executes a query which is expected to return a single row, see
+    /// [`crate::Graph::execute_exactly_one`]
+    pub async fn execute_exactly_one(&self, q: impl Into<Query>) -> Result<Row> {
+        q.into()
+            .in_txn()
+            .fetch_exactly_one(&self.config, self.connection.clone())
+            .await
     }
 
-    /// Commits the transaction in progress
-    pub async fn commit(self) -> Result<()> {
+    /// Runs `UNWIND $rows AS row` followed by `query` for batches of `batch_size` rows and sums
+    /// up the counters of all batches. A batch ends early once its rows encode to 1 MiB, a single
+    /// larger row is sent in a batch of its own.
+    ///
+    /// ```no_run
+    /// # async fn example(txn: neo4rs::Txn, people: Vec<neo4rs::types::BoltMap>) -> neo4rs::Result<()> {
+    /// let counters = txn
+    ///     .bulk_unwind("CREATE (:Person {name: row.name})", people, 1000)
+    ///     .await?;
+    /// println!("created {} people", counters.nodes_created());
+    /// txn.commit().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn bulk_unwind(
+        &self,
+        query: &str,
+        rows: impl IntoIterator<Item = BoltMap>,
+        batch_size: usize,
+    ) -> Result<Counters> {
+        let statement = format!("UNWIND $rows AS row {}", query);
+        let encoding = self.connection.lock().await.encoding();
+        let mut counters = Counters::default();
+        let mut batch = BoltList::new();
+        let mut batch_bytes = 0;
+        for row in rows {
+            let row_bytes = row.encoded_len(encoding);
+            if !batch.is_empty()
+                && (batch.len() >= batch_size || batch_bytes + row_bytes > MAX_BATCH_BYTES)
+            {
+                counters += &self.unwind(&statement, mem::take(&mut batch)).await?;
+                batch_bytes = 0;
+            }
+            batch.push(BoltType::Map(row));
+            batch_bytes += row_bytes;
+        }
+        if !batch.is_empty() {
+            counters += &self.unwind(&statement, batch).await?;
+        }
+        Ok(counters)
+    }
+
+    async fn unwind(&self, statement: &str, rows: BoltList) -> Result<Counters> {
+        let query = Query::new(statement.to_owned()).param("rows", BoltType::List(rows));
+        let summary = self.execute(query).await?.discard().await?;
+        Ok(summary.counters().clone())
+    }
+
+    /// Commits the transaction in progress and returns the bookmark of the commit, which a later
+    /// transaction can wait for with [`TxnOptions::with_bookmarks`]. Servers which don't support
+    /// bookmarks answer without one.
+    pub async fn commit(mut self) -> Result<Option<String>> {
+        self.finished = true;
         let commit = BoltRequest::commit();
-        match self.connection.lock().await.send_recv(commit).await? {
-            BoltResponse::SuccessMessage(_) => Ok(()),
+        let mut connection = self.connection.lock().await;
+        match connection.send_recv(commit).await? {
+            BoltResponse::SuccessMessage(success) => {
+                let bookmark: Option<String> = success.get("bookmark");
+                if let (Some(bookmarks), Some(bookmark)) = (&self.bookmarks, &bookmark) {
+                    *bookmarks.lock().unwrap() = vec![bookmark.clone()];
+                }
+                Ok(bookmark)
+            }
+            BoltResponse::FailureMessage(failure) => Err(connection.failed(failure).await),
             msg => Err(unexpected(msg, "COMMIT")),
         }
     }
 
     /// rollback/abort the current transaction
-    pub async fn rollback(self) -> Result<()> {
+    ///
+    /// If a query of the transaction failed the server ignores the rollback, the connection is
+    /// reset instead which discards the transaction as well.
+    pub async fn rollback(mut self) -> Result<()> {
+        self.finished = true;
         let rollback = BoltRequest::rollback();
-        match self.connection.lock().await.send_recv(rollback).await? {
+        let mut connection = self.connection.lock().await;
+        match connection.send_recv(rollback).await? {
             BoltResponse::SuccessMessage(_) => Ok(()),
+            BoltResponse::FailureMessage(_) | BoltResponse::IgnoredMessage(_) => {
+                connection.reset().await
+            }
             msg => Err(unexpected(msg, "ROLLBACK")),
         }
     }
 }
+
+impl Drop for Txn {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let connection = self.connection.clone();
+            handle.spawn(async move {
+                if let Err(e) = connection.lock().await.reset().await {
+                    warn!("failed to roll back dropped transaction: {:?}", e);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::config;
+    use crate::testkit::{MockServer, Response};
+    use crate::types::BoltString;
+    use crate::version::Version;
+    use bytes::Bytes;
+
+    async fn start_txn(script: Vec<Vec<Response>>) -> (Txn, MockServer) {
+        let mut server = MockServer::start(Version::V4_1).await.unwrap();
+        for responses in script {
+            server.respond(responses);
+        }
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .build()
+            .unwrap();
+        let connection = create_pool(&config).await.get().await.unwrap();
+        let txn = Txn::new(config, connection, TxnOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB1, 0x01]);
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB1, 0x11]);
+        (txn, server)
+    }
+
+    /// The SUCCESS of a DISCARD after a query which created two nodes
+    fn two_created() -> Vec<Response> {
+        let stats = vec![("nodes-created".into(), 2.into())]
+            .into_iter()
+            .collect();
+        vec![Response::success(vec![("stats", BoltType::Map(stats))])]
+    }
+
+    /// The number of rows the RUN of a `bulk_unwind` batch carries
+    fn batch_len(run: Bytes) -> usize {
+        assert_eq!(run[1], 0x10);
+        let mut fields = run.slice(2..);
+        let query = BoltString::parse(Version::V4_1, &mut fields).unwrap();
+        assert!(query.value.starts_with("UNWIND $rows AS row CREATE"));
+        let params = BoltMap::parse(Version::V4_1, &mut fields).unwrap();
+        params.get::<BoltList>("rows").unwrap().len()
+    }
+
+    fn person(name: String) -> BoltMap {
+        vec![("name".into(), name.into())].into_iter().collect()
+    }
+
+    #[tokio::test]
+    async fn should_unwind_rows_in_batches() {
+        let (txn, mut server) = start_txn(vec![
+            vec![Response::success(vec![])],
+            vec![Response::success(vec![])],
+            vec![Response::success(vec![])],
+            two_created(),
+            vec![Response::success(vec![])],
+            two_created(),
+            vec![Response::success(vec![])],
+            two_created(),
+        ])
+        .await;
+
+        let people = (0..5).map(|i| person(i.to_string()));
+        let counters = txn
+            .bulk_unwind("CREATE (:Person {name: row.name})", people, 2)
+            .await
+            .unwrap();
+
+        assert_eq!(counters.nodes_created(), 6);
+        let mut batches = Vec::new();
+        for _ in 0..3 {
+            batches.push(batch_len(server.next_request().await.unwrap()));
+            assert_eq!(server.next_request().await.unwrap()[1], 0x2F);
+        }
+        assert_eq!(batches, vec![2, 2, 1]);
+        assert!(server.try_next_request().is_none());
+    }
+
+    #[tokio::test]
+    async fn should_end_unwind_batches_at_size_limit() {
+        let (txn, mut server) = start_txn(vec![
+            vec![Response::success(vec![])],
+            vec![Response::success(vec![])],
+        ])
+        .await;
+
+        let people = (0..3).map(|_| person("x".repeat(MAX_BATCH_BYTES / 2)));
+        txn.bulk_unwind("CREATE (:Person {name: row.name})", people, 100)
+            .await
+            .unwrap();
+
+        let mut batches = Vec::new();
+        for _ in 0..3 {
+            batches.push(batch_len(server.next_request().await.unwrap()));
+            server.next_request().await.unwrap();
+        }
+        assert_eq!(batches, vec![1, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn should_pipeline_queries_until_failure() {
+        let (txn, mut server) = start_txn(vec![
+            vec![Response::success(vec![])],
+            vec![Response::success(vec![])],
+            vec![Response::success(vec![])],
+            vec![Response::success(vec![])],
+            vec![Response::failure("", "fail")],
+            vec![Response::Ignored],
+            vec![Response::Ignored],
+            vec![Response::Ignored],
+            vec![Response::success(vec![])],
+        ])
+        .await;
+
+        let queries = vec![
+            Query::new("CREATE (n)".to_owned()),
+            Query::new("RETURN 1/0".to_owned()),
+            Query::new("CREATE (m)".to_owned()),
+        ];
+        assert!(txn.run_queries(queries).await.is_err());
+
+        let mut signatures = Vec::new();
+        for _ in 0..7 {
+            signatures.push(server.next_request().await.unwrap()[1]);
+        }
+        assert_eq!(signatures, vec![0x10, 0x2F, 0x10, 0x2F, 0x10, 0x2F, 0x0F]);
+    }
+
+    #[tokio::test]
+    async fn should_reset_when_rollback_is_ignored_after_failure() {
+        let (txn, mut server) = start_txn(vec![
+            vec![Response::success(vec![])],
+            vec![Response::success(vec![])],
+            vec![Response::failure("", "fail")],
+            vec![Response::success(vec![])],
+            vec![Response::Ignored],
+        ])
+        .await;
+
+        assert!(txn.run(Query::new("RETURN 1/0".to_owned())).await.is_err());
+        txn.rollback().await.unwrap();
+
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB3, 0x10]);
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB0, 0x0F]);
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB0, 0x13]);
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB0, 0x0F]);
+    }
+
+    #[tokio::test]
+    async fn should_report_server_failures_with_code_and_message() {
+        let (txn, mut server) = start_txn(vec![
+            vec![Response::success(vec![])],
+            vec![Response::success(vec![])],
+            vec![Response::failure(
+                "Neo.ClientError.Statement.SyntaxError",
+                "oops",
+            )],
+        ])
+        .await;
+
+        match txn.execute(Query::new("RETRUN 1".to_owned())).await {
+            Err(Error::Neo4j { code, message }) => {
+                assert_eq!(code, "Neo.ClientError.Statement.SyntaxError");
+                assert_eq!(message, "oops");
+            }
+            result => panic!("unexpected result {:?}", result.map(|_| ())),
+        }
+
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB3, 0x10]);
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB0, 0x0F]);
+    }
+
+    #[tokio::test]
+    async fn should_roll_back_when_dropped() {
+        let (txn, mut server) = start_txn(vec![]).await;
+
+        drop(txn);
+
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB0, 0x0F]);
+    }
+
+    #[tokio::test]
+    async fn should_not_roll_back_after_commit() {
+        let (txn, mut server) = start_txn(vec![]).await;
+
+        assert_eq!(txn.commit().await.unwrap(), None);
+
+        assert_eq!(server.next_request().await.unwrap()[..2], [0xB0, 0x12]);
+        tokio::task::yield_now().await;
+        assert!(server.try_next_request().is_none());
+    }
+
+    #[tokio::test]
+    async fn should_return_bookmark_of_commit() {
+        let (txn, _server) = start_txn(vec![
+            vec![Response::success(vec![])],
+            vec![Response::success(vec![])],
+            vec![Response::success(vec![("bookmark", "bm:7".into())])],
+        ])
+        .await;
+
+        assert_eq!(txn.commit().await.unwrap().as_deref(), Some("bm:7"));
+    }
+
+    #[tokio::test]
+    async fn should_impersonate_user_on_begin() {
+        let mut server = MockServer::start(Version::V4_4).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .versions(&[Version::V4_4])
+            .build()
+            .unwrap();
+        let connection = create_pool(&config).await.get().await.unwrap();
+        let options = TxnOptions::default().with_impersonated_user("alice");
+
+        Txn::new(config, connection, options).await.unwrap();
+
+        let _hello = server.next_request().await.unwrap();
+        let begin = server.next_request().await.unwrap();
+        let expected = Begin::new(
+            vec![("imp_user".into(), "alice".into())]
+                .into_iter()
+                .collect(),
+        );
+        assert_eq!(
+            Begin::parse(Version::V4_4, &mut begin.clone()).unwrap(),
+            expected
+        );
+    }
+
+    #[tokio::test]
+    async fn should_reject_impersonation_before_bolt_4_4() {
+        let mut server = MockServer::start(Version::V4_1).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .build()
+            .unwrap();
+        let connection = create_pool(&config).await.get().await.unwrap();
+        let options = TxnOptions::default().with_impersonated_user("alice");
+
+        let result = Txn::new(config, connection, options).await;
+
+        assert!(matches!(result, Err(Error::UnsupportedVersion(_))));
+        let _hello = server.next_request().await.unwrap();
+        assert!(server.try_next_request().is_none());
+    }
+
+    #[tokio::test]
+    async fn should_select_database_on_begin() {
+        let mut server = MockServer::start(Version::V4_1).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .db("analytics")
+            .build()
+            .unwrap();
+        let connection = create_pool(&config).await.get().await.unwrap();
+        let txn = Txn::new(config, connection, TxnOptions::default())
+            .await
+            .unwrap();
+
+        txn.run(Query::new("RETURN 1".to_owned()).db("other"))
+            .await
+            .unwrap();
+
+        let _hello = server.next_request().await.unwrap();
+        let begin = server.next_request().await.unwrap();
+        assert_eq!(
+            &begin[..],
+            &[
+                0xB1, 0x11, 0xA1, 0x82, b'd', b'b', 0x89, b'a', b'n', b'a', b'l', b'y', b't', b'i',
+                b'c', b's'
+            ]
+        );
+        let run = server.next_request().await.unwrap();
+        assert_eq!(&run[run.len() - 2..], &[0xA0, 0xA0]);
+    }
+
+    #[tokio::test]
+    async fn should_send_timeout_and_metadata_on_begin() {
+        let mut server = MockServer::start(Version::V4_1).await.unwrap();
+        let config = config()
+            .uri(&server.uri())
+            .user("neo4j")
+            .password("neo")
+            .build()
+            .unwrap();
+        let connection = create_pool(&config).await.get().await.unwrap();
+        let metadata: BoltMap = vec![("request_id".into(), "abc-123".into())]
+            .into_iter()
+            .collect();
+        let options = TxnOptions::default()
+            .with_timeout(Duration::from_millis(1500))
+            .with_metadata(metadata.clone());
+
+        Txn::new(config, connection, options).await.unwrap();
+
+        let _hello = server.next_request().await.unwrap();
+        let mut begin = server.next_request().await.unwrap();
+        let expected = Begin::new(
+            vec![
+                ("tx_timeout".into(), 1500.into()),
+                ("tx_metadata".into(), metadata.into()),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        assert_eq!(Begin::parse(Version::V4_1, &mut begin).unwrap(), expected);
+
+        let sub_millisecond = TxnOptions::default().with_timeout(Duration::from_micros(300));
+        assert_eq!(sub_millisecond.extra("").get::<i64>("tx_timeout"), Some(1));
+    }
+}
@@ -5,14 +5,19 @@ pub mod date_time;
 pub mod duration;
 pub mod float;
 pub mod integer;
+pub mod lazy;
 pub mod list;
 pub mod map;
+pub mod marker;
 pub mod node;
 pub mod null;
 pub mod path;
 pub mod point;
+pub mod prepared;
 pub mod relation;
+pub mod signature;
 pub mod string;
+pub mod structure;
 pub mod time;
 pub use binary::BoltBytes;
 pub use boolean::BoltBoolean;
@@ -20,24 +25,34 @@ pub use date::BoltDate;
 pub use date_time::{BoltDateTime, BoltDateTimeZoneId, BoltLocalDateTime};
 pub use duration::BoltDuration;
 pub use float::BoltFloat;
-pub use integer::BoltInteger;
+pub use integer::{BoltInteger, IntegerEncoding};
+pub use lazy::LazyMap;
 pub use list::BoltList;
 pub use map::BoltMap;
-pub use node::BoltNode;
+pub use marker::{marker_kind, peek_marker, BoltKind};
+pub use node::{BoltNode, LazyNode};
 pub use null::BoltNull;
 pub use path::BoltPath;
 pub use point::{BoltPoint2D, BoltPoint3D};
-pub use relation::{BoltRelation, BoltUnboundedRelation};
-pub use string::BoltString;
+pub use prepared::PreparedParams;
+pub use relation::{BoltRelation, BoltUnboundedRelation, Direction};
+pub use signature::Signature;
+pub use string::{BoltStr, BoltString};
+pub use structure::BoltStructure;
 pub use time::{BoltLocalTime, BoltTime};
 
 use crate::errors::*;
 use crate::version::Version;
-use bytes::Bytes;
-use std::cell::RefCell;
+use bytes::{Buf, Bytes, BytesMut};
 use std::fmt::Display;
-use std::rc::Rc;
+use std::hash::{Hash, Hasher};
 
+/// Any bolt value.
+///
+/// Values are `Eq` and `Hash` so that they can be used as keys, floats are hashed by their bit
+/// pattern. As NaN is not equal to itself, a value containing NaN is never found in a set.
+/// Nodes and relationships are hashed by their ids, temporal values, paths and structures only by
+/// their kind.
 #[derive(Debug, PartialEq, Clone)]
 pub enum BoltType {
     String(BoltString),
@@ -61,112 +76,794 @@ pub enum BoltType {
     DateTime(BoltDateTime),
     LocalDateTime(BoltLocalDateTime),
     DateTimeZoneId(BoltDateTimeZoneId),
+    Structure(BoltStructure),
 }
 
+/// What the encoding of a value depends on besides the value, the version of the connection and
+/// how the integers are encoded. A version alone encodes integers with [`IntegerEncoding::Minimal`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Encoding {
+    pub version: Version,
+    pub integers: IntegerEncoding,
+}
+
+impl From<Version> for Encoding {
+    fn from(version: Version) -> Encoding {
+        Encoding {
+            version,
+            integers: IntegerEncoding::Minimal,
+        }
+    }
+}
+
+/// Encodes a value into a buffer that is shared with other values, which saves allocating a buffer
+/// per value when large messages are serialized.
+pub trait BoltEncode {
+    /// Appends the encoded value to `dst`
+    fn encode(&self, encoding: Encoding, dst: &mut BytesMut) -> Result<()>;
+
+    /// The number of bytes `encode` appends, computed without encoding the value
+    fn encoded_len(&self, encoding: Encoding) -> usize;
+
+    /// Encodes the value into a buffer of its own
+    fn to_bytes(&self, encoding: impl Into<Encoding>) -> Result<Bytes> {
+        let encoding = encoding.into();
+        let mut bytes = BytesMut::with_capacity(self.encoded_len(encoding));
+        self.encode(encoding, &mut bytes)?;
+        Ok(bytes.freeze())
+    }
+}
+
+/// Decodes a value from the front of a buffer, the counterpart of [`BoltEncode`].
+pub trait BoltDecode: Sized {
+    /// Decodes the next value and advances `input` past it
+    fn decode(version: Version, input: &mut Bytes) -> Result<Self>;
+
+    /// Decodes a value which fills all of `input`
+    fn from_slice(version: Version, input: &[u8]) -> Result<Self> {
+        let mut input = Bytes::copy_from_slice(input);
+        let value = Self::decode(version, &mut input)?;
+        if !input.is_empty() {
+            return Err(Error::DeserializationError(format!(
+                "{} bytes left after the value",
+                input.len()
+            )));
+        }
+        Ok(value)
+    }
+}
+
+macro_rules! decode_with_parse {
+    ($($t:ty),*) => {
+        $(
+            impl BoltDecode for $t {
+                fn decode(version: Version, input: &mut Bytes) -> Result<Self> {
+                    <$t>::parse(version, input)
+                }
+            }
+        )*
+    };
+}
+
+decode_with_parse!(
+    BoltType,
+    BoltBytes,
+    BoltBoolean,
+    BoltDate,
+    BoltDateTime,
+    BoltLocalDateTime,
+    BoltDateTimeZoneId,
+    BoltDuration,
+    BoltFloat,
+    BoltInteger,
+    BoltList,
+    BoltMap,
+    BoltNode,
+    BoltNull,
+    BoltPath,
+    BoltPoint2D,
+    BoltPoint3D,
+    BoltRelation,
+    BoltUnboundedRelation,
+    BoltString,
+    BoltStr,
+    BoltStructure,
+    BoltTime,
+    BoltLocalTime
+);
+
+impl Eq for BoltType {}
+
+impl Hash for BoltType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            BoltType::Null(_) => {}
+            BoltType::Boolean(b) => b.hash(state),
+            BoltType::Integer(i) => i.hash(state),
+            BoltType::Float(x) => hash_float(x, state),
+            BoltType::String(s) => s.hash(state),
+            BoltType::Bytes(b) => b.value.hash(state),
+            BoltType::List(list) => list.hash(state),
+            BoltType::Map(map) => map.hash(state),
+            // nodes with the same element id have the same id as well, as both are assigned by
+            // the same database
+            BoltType::Node(node) => node.id.hash(state),
+            BoltType::Relation(rel) => {
+                rel.id.hash(state);
+                rel.element_id.hash(state);
+            }
+            BoltType::UnboundedRelation(rel) => {
+                rel.id.hash(state);
+                rel.element_id.hash(state);
+            }
+            BoltType::Point2D(p) => {
+                p.sr_id.hash(state);
+                hash_float(&p.x, state);
+                hash_float(&p.y, state);
+            }
+            BoltType::Point3D(p) => {
+                p.sr_id.hash(state);
+                hash_float(&p.x, state);
+                hash_float(&p.y, state);
+                hash_float(&p.z, state);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Hashes the bits of the float, `0.0` and `-0.0` are equal and hashed alike.
+fn hash_float<H: Hasher>(float: &BoltFloat, state: &mut H) {
+    let value = if float.value == 0.0 { 0.0 } else { float.value };
+    value.to_bits().hash(state);
+}
+
+/// Renders the value like a cypher literal, e.g. `{name: "Mark", tags: [1, 2.5, null]}`. Nodes
+/// and relationships are rendered like patterns, values without a literal such as temporal
+/// types fall back to their `Debug` representation.
 impl Display for BoltType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let value = match self {
-            BoltType::String(s) => s.to_string(),
-            _ => "to_string not implemented".to_owned(),
-        };
-        write!(f, "{}", value)
+        match self {
+            BoltType::Null(_) => write!(f, "null"),
+            BoltType::Boolean(b) => write!(f, "{}", b.value),
+            BoltType::Integer(i) => write!(f, "{}", i.value),
+            BoltType::Float(x) => write!(f, "{:?}", x.value),
+            BoltType::String(s) => write!(f, "{:?}", s.value),
+            BoltType::Bytes(b) => {
+                write!(f, "0x")?;
+                b.value
+                    .iter()
+                    .try_for_each(|byte| write!(f, "{:02x}", byte))
+            }
+            BoltType::List(list) => write_list(f, list),
+            BoltType::Map(map) => write_map(f, map),
+            BoltType::Node(node) => {
+                write!(f, "(")?;
+                write_labels(f, &node.labels)?;
+                write_properties(f, &node.properties)?;
+                write!(f, ")")
+            }
+            BoltType::Relation(rel) => {
+                write!(f, "[:{}", rel.typ)?;
+                write_properties(f, &rel.properties)?;
+                write!(f, "]")
+            }
+            BoltType::UnboundedRelation(rel) => {
+                write!(f, "[:{}", rel.typ)?;
+                write_properties(f, &rel.properties)?;
+                write!(f, "]")
+            }
+            BoltType::Point2D(p) => write!(
+                f,
+                "point({{srid: {}, x: {:?}, y: {:?}}})",
+                p.sr_id.value, p.x.value, p.y.value
+            ),
+            BoltType::Point3D(p) => write!(
+                f,
+                "point({{srid: {}, x: {:?}, y: {:?}, z: {:?}}})",
+                p.sr_id.value, p.x.value, p.y.value, p.z.value
+            ),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+fn write_list(f: &mut std::fmt::Formatter<'_>, list: &BoltList) -> std::fmt::Result {
+    write!(f, "[")?;
+    for (i, value) in list.value.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", value)?;
+    }
+    write!(f, "]")
+}
+
+fn write_map(f: &mut std::fmt::Formatter<'_>, map: &BoltMap) -> std::fmt::Result {
+    write!(f, "{{")?;
+    for (i, (key, value)) in map.value.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}: {}", key, value)?;
+    }
+    write!(f, "}}")
+}
+
+fn write_labels(f: &mut std::fmt::Formatter<'_>, labels: &BoltList) -> std::fmt::Result {
+    labels.value.iter().try_for_each(|label| match label {
+        BoltType::String(label) => write!(f, ":{}", label),
+        other => write!(f, ":{}", other),
+    })
+}
+
+fn write_properties(f: &mut std::fmt::Formatter<'_>, properties: &BoltMap) -> std::fmt::Result {
+    if properties.value.is_empty() {
+        return Ok(());
     }
+    write!(f, " ")?;
+    write_map(f, properties)
 }
 
 impl BoltType {
-    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+    pub fn null() -> BoltType {
+        BoltType::Null(BoltNull)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, BoltType::Null(_))
+    }
+
+    /// The kind of the value as it is encoded on the wire, all graph and temporal types are
+    /// structures.
+    pub fn kind(&self) -> BoltKind {
+        match self {
+            BoltType::Null(_) => BoltKind::Null,
+            BoltType::Boolean(_) => BoltKind::Boolean,
+            BoltType::Integer(_) => BoltKind::Integer,
+            BoltType::Float(_) => BoltKind::Float,
+            BoltType::String(_) => BoltKind::String,
+            BoltType::Bytes(_) => BoltKind::Bytes,
+            BoltType::List(_) => BoltKind::List,
+            BoltType::Map(_) => BoltKind::Map,
+            _ => BoltKind::Structure,
+        }
+    }
+
+    /// The string, or `None` if the value is not a string
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            BoltType::String(s) => Some(&s.value),
+            _ => None,
+        }
+    }
+
+    /// The integer, or `None` if the value is not an integer
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            BoltType::Integer(i) => Some(i.value),
+            _ => None,
+        }
+    }
+
+    /// The float, or `None` if the value is not a float
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            BoltType::Float(f) => Some(f.value),
+            _ => None,
+        }
+    }
+
+    /// The boolean, or `None` if the value is not a boolean
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            BoltType::Boolean(b) => Some(b.value),
+            _ => None,
+        }
+    }
+
+    /// The list, or `None` if the value is not a list
+    pub fn as_list(&self) -> Option<&BoltList> {
+        match self {
+            BoltType::List(l) => Some(l),
+            _ => None,
+        }
+    }
+
+    /// The map, or `None` if the value is not a map
+    pub fn as_map(&self) -> Option<&BoltMap> {
         match self {
-            BoltType::Null(t) => t.into_bytes(version),
-            BoltType::Boolean(t) => t.into_bytes(version),
-            BoltType::Integer(t) => t.into_bytes(version),
-            BoltType::Float(t) => t.into_bytes(version),
-            BoltType::String(t) => t.into_bytes(version),
-            BoltType::List(t) => t.into_bytes(version),
-            BoltType::Point2D(t) => t.into_bytes(version),
-            BoltType::Point3D(t) => t.into_bytes(version),
-            BoltType::Map(t) => t.into_bytes(version),
-            BoltType::Node(t) => t.into_bytes(version),
-            BoltType::Path(t) => t.into_bytes(version),
-            BoltType::Relation(t) => t.into_bytes(version),
-            BoltType::UnboundedRelation(t) => t.into_bytes(version),
-            BoltType::Bytes(t) => t.into_bytes(version),
-            BoltType::Duration(t) => t.into_bytes(version),
-            BoltType::Date(t) => t.into_bytes(version),
-            BoltType::Time(t) => t.into_bytes(version),
-            BoltType::LocalTime(t) => t.into_bytes(version),
-            BoltType::DateTime(t) => t.into_bytes(version),
-            BoltType::LocalDateTime(t) => t.into_bytes(version),
-            BoltType::DateTimeZoneId(t) => t.into_bytes(version),
-        }
-    }
-
-    fn parse(version: Version, input: Rc<RefCell<Bytes>>) -> Result<BoltType> {
+            BoltType::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        self.to_bytes(version)
+    }
+
+    pub fn parse(version: Version, input: &mut Bytes) -> Result<BoltType> {
         let bolt_type = match input {
-            input if BoltInteger::can_parse(version, input.clone()) => {
+            input if BoltInteger::can_parse(version, input) => {
                 BoltType::Integer(BoltInteger::parse(version, input)?)
             }
-            input if BoltFloat::can_parse(version, input.clone()) => {
+            input if BoltFloat::can_parse(version, input) => {
                 BoltType::Float(BoltFloat::parse(version, input)?)
             }
-            input if BoltString::can_parse(version, input.clone()) => {
+            input if BoltString::can_parse(version, input) => {
                 BoltType::String(BoltString::parse(version, input)?)
             }
-            input if BoltList::can_parse(version, input.clone()) => {
+            input if BoltList::can_parse(version, input) => {
                 BoltType::List(BoltList::parse(version, input)?)
             }
-            input if BoltMap::can_parse(version, input.clone()) => {
+            input if BoltMap::can_parse(version, input) => {
                 BoltType::Map(BoltMap::parse(version, input)?)
             }
-            input if BoltNode::can_parse(version, input.clone()) => {
+            input if BoltNode::can_parse(version, input) => {
                 BoltType::Node(BoltNode::parse(version, input)?)
             }
-            input if BoltBoolean::can_parse(version, input.clone()) => {
+            input if BoltBoolean::can_parse(version, input) => {
                 BoltType::Boolean(BoltBoolean::parse(version, input)?)
             }
-            input if BoltNull::can_parse(version, input.clone()) => {
+            input if BoltNull::can_parse(version, input) => {
                 BoltType::Null(BoltNull::parse(version, input)?)
             }
-            input if BoltPoint2D::can_parse(version, input.clone()) => {
+            input if BoltPoint2D::can_parse(version, input) => {
                 BoltType::Point2D(BoltPoint2D::parse(version, input)?)
             }
-            input if BoltPoint3D::can_parse(version, input.clone()) => {
+            input if BoltPoint3D::can_parse(version, input) => {
                 BoltType::Point3D(BoltPoint3D::parse(version, input)?)
             }
-            input if BoltBytes::can_parse(version, input.clone()) => {
+            input if BoltBytes::can_parse(version, input) => {
                 BoltType::Bytes(BoltBytes::parse(version, input)?)
             }
-            input if BoltPath::can_parse(version, input.clone()) => {
+            input if BoltPath::can_parse(version, input) => {
                 BoltType::Path(BoltPath::parse(version, input)?)
             }
-            input if BoltDuration::can_parse(version, input.clone()) => {
+            input if BoltDuration::can_parse(version, input) => {
                 BoltType::Duration(BoltDuration::parse(version, input)?)
             }
-            input if BoltDate::can_parse(version, input.clone()) => {
+            input if BoltDate::can_parse(version, input) => {
                 BoltType::Date(BoltDate::parse(version, input)?)
             }
-            input if BoltTime::can_parse(version, input.clone()) => {
+            input if BoltTime::can_parse(version, input) => {
                 BoltType::Time(BoltTime::parse(version, input)?)
             }
-            input if BoltLocalTime::can_parse(version, input.clone()) => {
+            input if BoltLocalTime::can_parse(version, input) => {
                 BoltType::LocalTime(BoltLocalTime::parse(version, input)?)
             }
-            input if BoltDateTime::can_parse(version, input.clone()) => {
+            input if BoltDateTime::can_parse(version, input) => {
                 BoltType::DateTime(BoltDateTime::parse(version, input)?)
             }
-            input if BoltLocalDateTime::can_parse(version, input.clone()) => {
+            input if BoltLocalDateTime::can_parse(version, input) => {
                 BoltType::LocalDateTime(BoltLocalDateTime::parse(version, input)?)
             }
-            input if BoltDateTimeZoneId::can_parse(version, input.clone()) => {
+            input if BoltDateTimeZoneId::can_parse(version, input) => {
                 BoltType::DateTimeZoneId(BoltDateTimeZoneId::parse(version, input)?)
             }
-            input if BoltUnboundedRelation::can_parse(version, input.clone()) => {
+            input if BoltUnboundedRelation::can_parse(version, input) => {
                 BoltType::UnboundedRelation(BoltUnboundedRelation::parse(version, input)?)
             }
-            input if BoltRelation::can_parse(version, input.clone()) => {
+            input if BoltRelation::can_parse(version, input) => {
                 BoltType::Relation(BoltRelation::parse(version, input)?)
             }
-            _ => return Err(Error::UnknownType(format!("{:#04X?}", input.borrow()))),
+            input if BoltStructure::can_parse(version, input) => {
+                BoltType::Structure(BoltStructure::parse(version, input)?)
+            }
+            _ => return Err(Error::UnknownType(format!("{:#04X?}", input))),
         };
         Ok(bolt_type)
     }
 }
+
+/// The size of the marker and the length of a string, list or map with `length` bytes or entries
+pub(crate) fn header_len(length: usize) -> usize {
+    match length {
+        0..=15 => 1,
+        16..=255 => 2,
+        256..=65_535 => 3,
+        _ => 5,
+    }
+}
+
+/// Splits the next `n` bytes off the input, failing instead of panicking when the input is
+/// truncated or a length prefix claims more bytes than were received.
+pub(crate) fn read_exact(input: &mut Bytes, n: usize) -> Result<Bytes> {
+    if input.remaining() < n {
+        return Err(Error::DeserializationError(format!(
+            "expected {} more bytes but only {} are available",
+            n,
+            input.remaining()
+        )));
+    }
+    Ok(input.split_to(n))
+}
+
+impl BoltEncode for BoltType {
+    fn encode(&self, encoding: Encoding, dst: &mut BytesMut) -> Result<()> {
+        match self {
+            BoltType::Null(t) => t.encode(encoding, dst),
+            BoltType::Boolean(t) => t.encode(encoding, dst),
+            BoltType::Integer(t) => t.encode(encoding, dst),
+            BoltType::Float(t) => t.encode(encoding, dst),
+            BoltType::String(t) => t.encode(encoding, dst),
+            BoltType::List(t) => t.encode(encoding, dst),
+            BoltType::Point2D(t) => t.encode(encoding, dst),
+            BoltType::Point3D(t) => t.encode(encoding, dst),
+            BoltType::Map(t) => t.encode(encoding, dst),
+            BoltType::Node(t) => t.encode(encoding, dst),
+            BoltType::Path(t) => t.encode(encoding, dst),
+            BoltType::Relation(t) => t.encode(encoding, dst),
+            BoltType::UnboundedRelation(t) => t.encode(encoding, dst),
+            BoltType::Bytes(t) => t.encode(encoding, dst),
+            BoltType::Duration(t) => t.encode(encoding, dst),
+            BoltType::Date(t) => t.encode(encoding, dst),
+            BoltType::Time(t) => t.encode(encoding, dst),
+            BoltType::LocalTime(t) => t.encode(encoding, dst),
+            BoltType::DateTime(t) => t.encode(encoding, dst),
+            BoltType::LocalDateTime(t) => t.encode(encoding, dst),
+            BoltType::DateTimeZoneId(t) => t.encode(encoding, dst),
+            BoltType::Structure(t) => t.encode(encoding, dst),
+        }
+    }
+
+    fn encoded_len(&self, encoding: Encoding) -> usize {
+        match self {
+            BoltType::Null(t) => t.encoded_len(encoding),
+            BoltType::Boolean(t) => t.encoded_len(encoding),
+            BoltType::Integer(t) => t.encoded_len(encoding),
+            BoltType::Float(t) => t.encoded_len(encoding),
+            BoltType::String(t) => t.encoded_len(encoding),
+            BoltType::List(t) => t.encoded_len(encoding),
+            BoltType::Point2D(t) => t.encoded_len(encoding),
+            BoltType::Point3D(t) => t.encoded_len(encoding),
+            BoltType::Map(t) => t.encoded_len(encoding),
+            BoltType::Node(t) => t.encoded_len(encoding),
+            BoltType::Path(t) => t.encoded_len(encoding),
+            BoltType::Relation(t) => t.encoded_len(encoding),
+            BoltType::UnboundedRelation(t) => t.encoded_len(encoding),
+            BoltType::Bytes(t) => t.encoded_len(encoding),
+            BoltType::Duration(t) => t.encoded_len(encoding),
+            BoltType::Date(t) => t.encoded_len(encoding),
+            BoltType::Time(t) => t.encoded_len(encoding),
+            BoltType::LocalTime(t) => t.encoded_len(encoding),
+            BoltType::DateTime(t) => t.encoded_len(encoding),
+            BoltType::LocalDateTime(t) => t.encoded_len(encoding),
+            BoltType::DateTimeZoneId(t) => t.encoded_len(encoding),
+            BoltType::Structure(t) => t.encoded_len(encoding),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BufMut;
+    use std::collections::HashSet;
+
+    #[test]
+    fn should_parse_concatenated_values() {
+        let values: Vec<BoltType> = vec![
+            BoltType::Integer(BoltInteger::new(42)),
+            BoltType::String(BoltString::new("hello")),
+            BoltType::Float(BoltFloat::new(1.5)),
+            BoltType::Boolean(BoltBoolean::new(true)),
+            BoltType::Null(BoltNull),
+            BoltType::List(vec![1.into(), "a".into()].into()),
+            BoltType::Map(vec![("k".into(), "v".into())].into_iter().collect()),
+            BoltType::Bytes(BoltBytes::new(Bytes::from_static(&[1, 2, 3]))),
+        ];
+
+        let mut stream = BytesMut::new();
+        for value in values.iter() {
+            stream.put(value.clone().into_bytes(Version::V4_1).unwrap());
+        }
+
+        let mut input = stream.freeze();
+        for expected in values.into_iter() {
+            let parsed = BoltType::parse(Version::V4_1, &mut input).unwrap();
+            assert_eq!(parsed, expected);
+        }
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn should_access_matching_variant() {
+        let list = BoltList::from(vec![BoltType::from(1)]);
+        let map: BoltMap = vec![("k".into(), "v".into())].into_iter().collect();
+
+        assert_eq!(BoltType::from("hello").as_str(), Some("hello"));
+        assert_eq!(BoltType::from(42).as_i64(), Some(42));
+        assert_eq!(BoltType::from(1.5).as_f64(), Some(1.5));
+        assert_eq!(BoltType::from(true).as_bool(), Some(true));
+        assert_eq!(BoltType::List(list.clone()).as_list(), Some(&list));
+        assert_eq!(BoltType::Map(map.clone()).as_map(), Some(&map));
+    }
+
+    #[test]
+    fn should_construct_and_detect_null() {
+        assert_eq!(BoltType::null(), BoltType::Null(BoltNull));
+        assert!(BoltType::null().is_null());
+        assert!(!BoltType::from(0).is_null());
+        assert!(!BoltType::from("").is_null());
+    }
+
+    #[test]
+    fn should_access_nothing_on_other_variant() {
+        let null = BoltType::Null(BoltNull);
+
+        assert_eq!(BoltType::from(42).as_str(), None);
+        assert_eq!(BoltType::from(1.5).as_i64(), None);
+        assert_eq!(BoltType::from(42).as_f64(), None);
+        assert_eq!(BoltType::from("true").as_bool(), None);
+        assert_eq!(null.as_list(), None);
+        assert_eq!(null.as_map(), None);
+        assert_eq!(null.as_str().unwrap_or_default(), "");
+    }
+
+    #[test]
+    fn should_display_nested_values_as_literals() {
+        let inner: BoltMap = vec![("active".into(), true.into()), ("score".into(), 1.0.into())]
+            .into_iter()
+            .collect();
+        let value = BoltType::Map(
+            vec![
+                ("name".into(), "Mark \"M\"".into()),
+                (
+                    "tags".into(),
+                    BoltType::List(vec![1.into(), BoltType::Null(BoltNull)].into()),
+                ),
+                ("inner".into(), inner.into()),
+                ("empty".into(), BoltType::List(BoltList::new())),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        assert_eq!(
+            value.to_string(),
+            r#"{name: "Mark \"M\"", tags: [1, null], inner: {active: true, score: 1.0}, empty: []}"#
+        );
+    }
+
+    #[test]
+    fn should_display_nodes_and_relations_as_patterns() {
+        let node = BoltNode::new(
+            1.into(),
+            vec!["Person".into(), "Admin".into()].into(),
+            vec![("name".into(), "Mark".into())].into_iter().collect(),
+        );
+        let rel = BoltType::UnboundedRelation(BoltUnboundedRelation::new(
+            2.into(),
+            "KNOWS".into(),
+            BoltMap::default(),
+        ));
+
+        assert_eq!(
+            BoltType::Node(node).to_string(),
+            r#"(:Person:Admin {name: "Mark"})"#
+        );
+        assert_eq!(rel.to_string(), "[:KNOWS]");
+        assert_eq!(
+            BoltType::Bytes(BoltBytes::new(Bytes::from_static(&[0x0A, 0xFF]))).to_string(),
+            "0x0aff"
+        );
+    }
+
+    #[test]
+    fn should_use_values_as_set_keys() {
+        let node = |id: i64, name: &str| {
+            BoltType::Node(BoltNode::new(
+                id.into(),
+                vec!["Person".into()].into(),
+                vec![("name".into(), name.into())].into_iter().collect(),
+            ))
+        };
+        let map = |entries: Vec<(&str, i64)>| {
+            BoltType::Map(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k.into(), v.into()))
+                    .collect(),
+            )
+        };
+
+        let values: HashSet<BoltType> = vec![
+            1.into(),
+            1.into(),
+            "1".into(),
+            0.0.into(),
+            (-0.0).into(),
+            BoltType::Null(BoltNull),
+            BoltType::List(vec![1.into(), true.into()].into()),
+            BoltType::List(vec![1.into(), true.into()].into()),
+            map(vec![("a", 1), ("b", 2)]),
+            map(vec![("b", 2), ("a", 1)]),
+            node(7, "Mark"),
+            node(7, "Mark"),
+            node(8, "Mark"),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(values.len(), 8);
+        assert!(values.contains(&BoltType::from(1)));
+        assert!(values.contains(&map(vec![("b", 2), ("a", 1)])));
+        assert!(values.contains(&node(8, "Mark")));
+        assert!(!values.contains(&BoltType::from(2)));
+    }
+
+    #[test]
+    fn should_encode_into_a_shared_buffer() {
+        let values: Vec<BoltType> = vec![
+            42.into(),
+            "hello".into(),
+            BoltType::List(vec![1.into(), "a".into()].into()),
+            BoltNode::new(1.into(), vec!["Person".into()].into(), BoltMap::default()).into(),
+        ];
+
+        let mut shared = BytesMut::new();
+        let mut separate = BytesMut::new();
+        for value in values.iter() {
+            value.encode(Version::V5_0.into(), &mut shared).unwrap();
+            separate.put(value.clone().into_bytes(Version::V5_0).unwrap());
+        }
+
+        assert_eq!(shared, separate);
+    }
+
+    #[test]
+    fn should_decode_from_a_slice() {
+        fn decode<T: BoltDecode>(input: &[u8]) -> Result<T> {
+            T::from_slice(Version::V4_1, input)
+        }
+
+        assert_eq!(
+            decode::<BoltInteger>(&[0x2A]).unwrap(),
+            BoltInteger::new(42)
+        );
+        assert_eq!(
+            decode::<BoltString>(&[0x81, 0x61]).unwrap(),
+            BoltString::new("a")
+        );
+        assert_eq!(
+            decode::<BoltType>(&[0x92, 0x01, 0xC0]).unwrap(),
+            BoltType::List(vec![1.into(), BoltType::Null(BoltNull)].into())
+        );
+        assert!(matches!(
+            decode::<BoltInteger>(&[0x2A, 0x2B]),
+            Err(Error::DeserializationError(_))
+        ));
+    }
+
+    #[test]
+    fn should_reject_unknown_marker() {
+        let mut input = Bytes::from_static(&[0xE0]);
+        assert!(matches!(
+            BoltType::parse(Version::V4_1, &mut input),
+            Err(Error::UnknownType(_))
+        ));
+    }
+
+    #[test]
+    fn should_reject_empty_input() {
+        let mut input = Bytes::new();
+        assert!(matches!(
+            BoltType::parse(Version::V4_1, &mut input),
+            Err(Error::UnknownType(_))
+        ));
+    }
+
+    #[test]
+    fn should_parse_on_another_thread() {
+        let bytes = BoltType::List(vec![1.into(), "a".into()].into())
+            .into_bytes(Version::V4_1)
+            .unwrap();
+
+        let parsed = std::thread::spawn(move || {
+            let mut input = bytes;
+            BoltType::parse(Version::V4_1, &mut input).unwrap()
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(parsed, BoltType::List(vec![1.into(), "a".into()].into()));
+    }
+
+    #[test]
+    fn should_reject_truncated_and_corrupt_input() {
+        let node = BoltNode::new(
+            7.into(),
+            vec!["Person".into()].into(),
+            vec![("name".into(), "x".repeat(300).into())]
+                .into_iter()
+                .collect(),
+        );
+        let value = BoltType::List(
+            vec![
+                BoltType::Node(node),
+                BoltType::Float(BoltFloat::new(1.5)),
+                i64::MAX.into(),
+                BoltType::Bytes(BoltBytes::new(Bytes::from(vec![0; 300]))),
+                BoltType::Map(
+                    vec![("k".into(), vec![1, 2, 3].into())]
+                        .into_iter()
+                        .collect(),
+                ),
+                BoltType::Point2D(BoltPoint2D {
+                    sr_id: 4326.into(),
+                    x: BoltFloat::new(1.0),
+                    y: BoltFloat::new(2.0),
+                }),
+            ]
+            .into(),
+        );
+        let encoded = value.clone().into_bytes(Version::V4_1).unwrap();
+        assert_eq!(
+            BoltType::parse(Version::V4_1, &mut encoded.clone()).unwrap(),
+            value
+        );
+
+        for length in 0..encoded.len() {
+            let mut truncated = encoded.slice(..length);
+            assert!(
+                BoltType::parse(Version::V4_1, &mut truncated).is_err(),
+                "parsed input truncated to {} bytes",
+                length
+            );
+        }
+
+        // xorshift, decoding random corruptions must fail or succeed but never panic
+        let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+        for _ in 0..2000 {
+            let mut corrupt = encoded.to_vec();
+            for _ in 0..3 {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let index = (state % corrupt.len() as u64) as usize;
+                corrupt[index] = (state >> 32) as u8;
+            }
+            let length = (state >> 40) as usize % (corrupt.len() + 1);
+            let _ = BoltType::parse(Version::V4_1, &mut Bytes::from(corrupt).slice(..length));
+        }
+    }
+
+    #[test]
+    fn should_compute_encoded_len_without_encoding() {
+        let node = BoltNode::new(
+            (1_i64 << 40).into(),
+            vec!["Person".into()].into(),
+            vec![("name".into(), "x".repeat(70_000).into())]
+                .into_iter()
+                .collect(),
+        );
+        let rel = BoltUnboundedRelation::new(300.into(), "KNOWS".into(), BoltMap::default());
+        let values: Vec<BoltType> = vec![
+            BoltType::Null(BoltNull),
+            (-17).into(),
+            BoltType::Bytes(BoltBytes::new(Bytes::from(vec![0; 256]))),
+            BoltType::List((0..300).map(BoltType::from).collect::<Vec<_>>().into()),
+            BoltType::Map(
+                (0..20)
+                    .map(|i| (i.to_string().into(), BoltType::from(i as f64)))
+                    .collect(),
+            ),
+            BoltType::List(vec![BoltType::Node(node), BoltType::UnboundedRelation(rel)].into()),
+            BoltType::Duration(BoltDuration::new(1.into(), 2.into(), 3.into(), 4.into())),
+            BoltType::Structure(BoltStructure::new(0x7A, vec![true.into()].into())),
+        ];
+
+        for version in [Version::V4_1, Version::V5_0].iter() {
+            for value in values.iter() {
+                assert_eq!(
+                    value.encoded_len((*version).into()),
+                    value.to_bytes(*version).unwrap().len(),
+                    "{:?}",
+                    value
+                );
+            }
+        }
+    }
+}
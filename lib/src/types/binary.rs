@@ -1,9 +1,9 @@
 use crate::errors::*;
+use crate::types::marker::{peek_kind, BoltKind};
+use crate::types::{read_exact, BoltEncode, Encoding};
 use crate::version::Version;
 use bytes::*;
-use std::cell::RefCell;
 use std::mem;
-use std::rc::Rc;
 
 pub const SMALL: u8 = 0xCC;
 pub const MEDIUM: u8 = 0xCD;
@@ -23,52 +23,62 @@ impl BoltBytes {
         self.value.len()
     }
 
-    pub fn can_parse(_: Version, input: Rc<RefCell<Bytes>>) -> bool {
-        let marker = input.borrow()[0];
-        [SMALL, MEDIUM, LARGE].contains(&marker)
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    pub fn can_parse(_: Version, input: &Bytes) -> bool {
+        peek_kind(input) == Some(BoltKind::Bytes)
     }
 }
 
 impl BoltBytes {
-    pub fn into_bytes(self, _: Version) -> Result<Bytes> {
-        let mut bytes = BytesMut::with_capacity(
-            mem::size_of::<u8>() + mem::size_of::<u32>() + self.value.len(),
-        );
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        self.to_bytes(version)
+    }
 
-        match &self.value {
-            value if value.len() <= 255 => {
-                bytes.put_u8(SMALL);
-                bytes.put_u8(value.len() as u8);
+    pub fn parse(_: Version, input: &mut Bytes) -> Result<BoltBytes> {
+        let marker = read_exact(input, mem::size_of::<u8>())?.get_u8();
+        let size = match marker {
+            SMALL => read_exact(input, mem::size_of::<u8>())?.get_u8() as usize,
+            MEDIUM => read_exact(input, mem::size_of::<u16>())?.get_u16() as usize,
+            LARGE => read_exact(input, mem::size_of::<u32>())?.get_u32() as usize,
+            _ => return Err(unexpected_marker(BoltKind::Bytes, marker)),
+        };
+
+        Ok(BoltBytes::new(read_exact(input, size)?))
+    }
+}
+
+impl BoltEncode for BoltBytes {
+    fn encode(&self, _: Encoding, dst: &mut BytesMut) -> Result<()> {
+        dst.reserve(mem::size_of::<u8>() + mem::size_of::<u32>() + self.value.len());
+        match self.value.len() {
+            0..=255 => {
+                dst.put_u8(SMALL);
+                dst.put_u8(self.value.len() as u8);
             }
-            value if value.len() > 255 && value.len() <= 65_535 => {
-                bytes.put_u8(MEDIUM);
-                bytes.put_u16(value.len() as u16);
+            256..=65_535 => {
+                dst.put_u8(MEDIUM);
+                dst.put_u16(self.value.len() as u16);
             }
-            value if value.len() > 65_535 && value.len() <= 2_147_483_648 => {
-                bytes.put_u8(LARGE);
-                bytes.put_u32(value.len() as u32);
+            65_536..=4_294_967_295 => {
+                dst.put_u8(LARGE);
+                dst.put_u32(self.value.len() as u32);
             }
             _ => return Err(Error::BytesTooBig),
         }
-        bytes.put(self.value);
-        Ok(bytes.freeze())
+        dst.put_slice(&self.value);
+        Ok(())
     }
 
-    pub fn parse(_: Version, input: Rc<RefCell<Bytes>>) -> Result<BoltBytes> {
-        let marker = input.borrow_mut().get_u8();
-        let size = match marker {
-            SMALL => input.borrow_mut().get_u8() as usize,
-            MEDIUM => input.borrow_mut().get_u16() as usize,
-            LARGE => input.borrow_mut().get_u32() as usize,
-            _ => {
-                return Err(Error::InvalidTypeMarker(format!(
-                    "invalid bytes marker {}",
-                    marker
-                )))
-            }
+    fn encoded_len(&self, _: Encoding) -> usize {
+        let header = match self.value.len() {
+            0..=255 => mem::size_of::<u8>(),
+            256..=65_535 => mem::size_of::<u16>(),
+            _ => mem::size_of::<u32>(),
         };
-
-        Ok(BoltBytes::new(input.borrow_mut().split_to(size)))
+        1 + header + self.value.len()
     }
 }
 
@@ -76,19 +86,36 @@ impl BoltBytes {
 mod tests {
     use super::*;
 
+    #[test]
+    fn should_serialize_empty_bytes() {
+        let bolt_bytes = BoltBytes::new(Bytes::new());
+
+        let serialized: Bytes = bolt_bytes.into_bytes(Version::V4_1).unwrap();
+
+        assert_eq!(&serialized[..], Bytes::from_static(&[SMALL, 0x00]));
+    }
+
+    #[test]
+    fn should_deserialize_empty_bytes() {
+        let mut input = Bytes::from_static(&[SMALL, 0x00]);
+
+        let deserialized: BoltBytes = BoltBytes::parse(Version::V4_1, &mut input).unwrap();
+
+        assert!(deserialized.is_empty());
+    }
+
     #[test]
     fn should_serialize_small_bytes() {
         let bolt_bytes = BoltBytes::new(Bytes::from_static("hello".as_bytes()));
 
-        let serialized: Bytes = bolt_bytes.into_bytes(Version::V4_1).unwrap();
+        let mut serialized: Bytes = bolt_bytes.into_bytes(Version::V4_1).unwrap();
 
         assert_eq!(
             &serialized[..],
             Bytes::from_static(&[SMALL, 0x05, b'h', b'e', b'l', b'l', b'o'])
         );
 
-        let deserialized: BoltBytes =
-            BoltBytes::parse(Version::V4_1, Rc::new(RefCell::new(serialized))).unwrap();
+        let deserialized: BoltBytes = BoltBytes::parse(Version::V4_1, &mut serialized).unwrap();
 
         assert_eq!(
             String::from_utf8(deserialized.value.to_vec()).unwrap(),
@@ -96,17 +123,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_serialize_small_bytes_at_boundary() {
+        let raw_bytes = Bytes::copy_from_slice(&[0; 255]);
+        let bolt_bytes = BoltBytes::new(raw_bytes.clone());
+        let mut serialized: Bytes = bolt_bytes.into_bytes(Version::V4_1).unwrap();
+
+        assert_eq!(serialized[0], SMALL);
+        assert_eq!(serialized[1], 0xFF);
+
+        let deserialized: BoltBytes = BoltBytes::parse(Version::V4_1, &mut serialized).unwrap();
+        assert_eq!(deserialized.value, raw_bytes);
+    }
+
     #[test]
     fn should_serialize_medium_bytes() {
         let raw_bytes = Bytes::copy_from_slice(&vec![0; 256]);
         let bolt_bytes = BoltBytes::new(raw_bytes.clone());
-        let serialized: Bytes = bolt_bytes.into_bytes(Version::V4_1).unwrap();
+        let mut serialized: Bytes = bolt_bytes.into_bytes(Version::V4_1).unwrap();
 
         assert_eq!(serialized[0], MEDIUM);
         assert_eq!(u16::from_be_bytes([serialized[1], serialized[2]]), 256);
 
-        let deserialized: BoltBytes =
-            BoltBytes::parse(Version::V4_1, Rc::new(RefCell::new(serialized))).unwrap();
+        let deserialized: BoltBytes = BoltBytes::parse(Version::V4_1, &mut serialized).unwrap();
+        assert_eq!(deserialized.value, raw_bytes);
+    }
+
+    #[test]
+    fn should_serialize_medium_bytes_at_boundary() {
+        let raw_bytes = Bytes::copy_from_slice(&vec![0; 65_535]);
+        let bolt_bytes = BoltBytes::new(raw_bytes.clone());
+        let mut serialized: Bytes = bolt_bytes.into_bytes(Version::V4_1).unwrap();
+
+        assert_eq!(serialized[0], MEDIUM);
+        assert_eq!(u16::from_be_bytes([serialized[1], serialized[2]]), 65_535);
+
+        let deserialized: BoltBytes = BoltBytes::parse(Version::V4_1, &mut serialized).unwrap();
         assert_eq!(deserialized.value, raw_bytes);
     }
 
@@ -114,7 +166,7 @@ mod tests {
     fn should_serialize_large_bytes() {
         let raw_bytes = Bytes::copy_from_slice(&vec![0; 65_537]);
         let bolt_bytes = BoltBytes::new(raw_bytes.clone());
-        let serialized: Bytes = bolt_bytes.into_bytes(Version::V4_1).unwrap();
+        let mut serialized: Bytes = bolt_bytes.into_bytes(Version::V4_1).unwrap();
 
         assert_eq!(serialized[0], LARGE);
         assert_eq!(
@@ -122,8 +174,7 @@ mod tests {
             65_537
         );
 
-        let deserialized: BoltBytes =
-            BoltBytes::parse(Version::V4_1, Rc::new(RefCell::new(serialized))).unwrap();
+        let deserialized: BoltBytes = BoltBytes::parse(Version::V4_1, &mut serialized).unwrap();
         assert_eq!(deserialized.value, raw_bytes);
     }
 }
@@ -1,13 +1,14 @@
 use crate::errors::*;
+use crate::types::marker::{peek_kind, BoltKind};
+use crate::types::{read_exact, BoltEncode, Encoding};
 use crate::version::Version;
 use bytes::*;
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::mem;
 
 pub const FALSE: u8 = 0xC2;
 pub const TRUE: u8 = 0xC3;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct BoltBoolean {
     pub value: bool,
 }
@@ -17,31 +18,37 @@ impl BoltBoolean {
         BoltBoolean { value }
     }
 
-    pub fn can_parse(_: Version, input: Rc<RefCell<Bytes>>) -> bool {
-        let input = input.borrow()[0];
-        input == TRUE || input == FALSE
+    pub fn can_parse(_: Version, input: &Bytes) -> bool {
+        peek_kind(input) == Some(BoltKind::Boolean)
     }
 }
 
 impl BoltBoolean {
-    pub fn into_bytes(self, _: Version) -> Result<Bytes> {
-        if self.value {
-            Ok(Bytes::copy_from_slice(&[TRUE]))
-        } else {
-            Ok(Bytes::copy_from_slice(&[FALSE]))
-        }
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        self.to_bytes(version)
     }
 
-    pub fn parse(_: Version, input: Rc<RefCell<Bytes>>) -> Result<BoltBoolean> {
-        let value = input.borrow_mut().get_u8();
-        match value {
+    pub fn parse(_: Version, input: &mut Bytes) -> Result<BoltBoolean> {
+        let marker = read_exact(input, mem::size_of::<u8>())?.get_u8();
+        match marker {
             TRUE => Ok(BoltBoolean::new(true)),
             FALSE => Ok(BoltBoolean::new(false)),
-            _ => Err(Error::InvalidTypeMarker("invalid boolean marker".into())),
+            _ => Err(unexpected_marker(BoltKind::Boolean, marker)),
         }
     }
 }
 
+impl BoltEncode for BoltBoolean {
+    fn encode(&self, _: Encoding, dst: &mut BytesMut) -> Result<()> {
+        dst.put_u8(if self.value { TRUE } else { FALSE });
+        Ok(())
+    }
+
+    fn encoded_len(&self, _: Encoding) -> usize {
+        1
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -59,12 +66,30 @@ mod tests {
 
     #[test]
     fn should_deserialize_boolean() {
-        let b = Rc::new(RefCell::new(Bytes::copy_from_slice(&[TRUE])));
-        let bolt_boolean: BoltBoolean = BoltBoolean::parse(Version::V4_1, b).unwrap();
-        assert_eq!(bolt_boolean.value, true);
+        let mut b = Bytes::copy_from_slice(&[TRUE]);
+        let bolt_boolean: BoltBoolean = BoltBoolean::parse(Version::V4_1, &mut b).unwrap();
+        assert!(bolt_boolean.value);
 
-        let b = Rc::new(RefCell::new(Bytes::copy_from_slice(&[FALSE])));
-        let bolt_boolean: BoltBoolean = BoltBoolean::parse(Version::V4_1, b).unwrap();
-        assert_eq!(bolt_boolean.value, false);
+        let mut b = Bytes::copy_from_slice(&[FALSE]);
+        let bolt_boolean: BoltBoolean = BoltBoolean::parse(Version::V4_1, &mut b).unwrap();
+        assert!(!bolt_boolean.value);
+    }
+
+    #[test]
+    fn should_round_trip_boolean() {
+        for value in [true, false].iter() {
+            let b: Bytes = BoltBoolean::new(*value).into_bytes(Version::V4_1).unwrap();
+            let mut input = b;
+            assert!(BoltBoolean::can_parse(Version::V4_1, &input));
+            let bolt_boolean = BoltBoolean::parse(Version::V4_1, &mut input).unwrap();
+            assert_eq!(bolt_boolean.value, *value);
+        }
+    }
+
+    #[test]
+    fn should_not_parse_null_as_boolean() {
+        let mut b = Bytes::from_static(&[0xC0]);
+        assert!(!BoltBoolean::can_parse(Version::V4_1, &b));
+        assert!(BoltBoolean::parse(Version::V4_1, &mut b).is_err());
     }
 }
@@ -1,8 +1,12 @@
-use crate::errors::Error;
 use crate::types::*;
-use chrono::{Duration, NaiveDate};
+#[cfg(feature = "chrono")]
+use chrono::{Datelike, NaiveDate};
 use neo4rs_macros::BoltStruct;
-use std::convert::TryInto;
+#[cfg(feature = "chrono")]
+use {
+    crate::errors::Error,
+    std::convert::{TryFrom, TryInto},
+};
 
 #[derive(Debug, PartialEq, Clone, BoltStruct)]
 #[signature(0xB1, 0x44)]
@@ -10,22 +14,40 @@ pub struct BoltDate {
     days: BoltInteger,
 }
 
-impl Into<BoltDate> for NaiveDate {
-    fn into(self) -> BoltDate {
-        let epoch = NaiveDate::from_ymd(1970, 1, 1);
-        let days = (self - epoch).num_days().into();
-        BoltDate { days }
+impl BoltDate {
+    pub fn new(days: i64) -> Self {
+        BoltDate {
+            days: BoltInteger::new(days),
+        }
+    }
+
+    /// Days since 1970-01-01, negative for earlier dates
+    pub fn days(&self) -> i64 {
+        self.days.value
+    }
+}
+
+/// 1970-01-01 counted in days from 0001-01-01
+#[cfg(feature = "chrono")]
+const EPOCH_DAYS_FROM_CE: i64 = 719_163;
+
+#[cfg(feature = "chrono")]
+impl From<NaiveDate> for BoltDate {
+    fn from(date: NaiveDate) -> BoltDate {
+        BoltDate::new(date.num_days_from_ce() as i64 - EPOCH_DAYS_FROM_CE)
     }
 }
 
+#[cfg(feature = "chrono")]
 impl TryInto<NaiveDate> for BoltDate {
     type Error = Error;
 
     fn try_into(self) -> Result<NaiveDate> {
-        let epoch = NaiveDate::from_ymd(1970, 1, 1);
-        let days = Duration::days(self.days.value);
-        epoch
-            .checked_add_signed(days)
+        self.days
+            .value
+            .checked_add(EPOCH_DAYS_FROM_CE)
+            .and_then(|days| i32::try_from(days).ok())
+            .and_then(NaiveDate::from_num_days_from_ce_opt)
             .ok_or(Error::ConverstionError)
     }
 }
@@ -35,29 +57,71 @@ mod tests {
     use super::*;
     use crate::version::Version;
     use bytes::*;
-    use std::cell::RefCell;
-    use std::rc::Rc;
 
+    #[test]
+    fn should_serialize_days_before_epoch() {
+        assert_eq!(
+            BoltDate::new(-1).into_bytes(Version::V4_1).unwrap(),
+            Bytes::from_static(&[0xB1, 0x44, 0xFF])
+        );
+        let mut bytes = Bytes::from_static(&[0xB1, 0x44, 0xC9, 0x9D, 0xD6]);
+        assert_eq!(
+            BoltDate::parse(Version::V4_1, &mut bytes).unwrap().days(),
+            -25130
+        );
+    }
+
+    #[cfg(feature = "chrono")]
     #[test]
     fn should_serialize_a_date() {
-        let date: BoltDate = NaiveDate::from_ymd(2010, 1, 1).into();
+        let date: BoltDate = NaiveDate::from_ymd_opt(2010, 1, 1).unwrap().into();
         assert_eq!(
             date.into_bytes(Version::V4_1).unwrap(),
             Bytes::from_static(&[0xB1, 0x44, 0xC9, 0x39, 0x12])
         );
     }
 
+    #[cfg(feature = "chrono")]
     #[test]
     fn should_deserialize_a_date() {
-        let bytes = Rc::new(RefCell::new(Bytes::from_static(&[
-            0xB1, 0x44, 0xC9, 0x39, 0x12,
-        ])));
+        let mut bytes = Bytes::from_static(&[0xB1, 0x44, 0xC9, 0x39, 0x12]);
 
-        let date: NaiveDate = BoltDate::parse(Version::V4_1, bytes)
+        let date: NaiveDate = BoltDate::parse(Version::V4_1, &mut bytes)
             .unwrap()
             .try_into()
             .unwrap();
 
         assert_eq!(date.to_string(), "2010-01-01");
     }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn should_round_trip_dates() {
+        let dates = [
+            ((1970, 1, 1), 0),
+            ((2020, 2, 29), 18321),
+            ((1969, 12, 31), -1),
+            ((1901, 3, 14), -25130),
+        ];
+        for ((year, month, day), days) in dates.iter() {
+            let date = NaiveDate::from_ymd_opt(*year, *month, *day).unwrap();
+
+            let bolt_date: BoltDate = date.into();
+            assert_eq!(bolt_date.days(), *days);
+
+            let mut bytes = bolt_date.into_bytes(Version::V4_1).unwrap();
+            let parsed: NaiveDate = BoltDate::parse(Version::V4_1, &mut bytes)
+                .unwrap()
+                .try_into()
+                .unwrap();
+            assert_eq!(parsed, date);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn should_reject_days_out_of_range() {
+        let result: Result<NaiveDate> = BoltDate::new(i64::MAX).try_into();
+        assert!(matches!(result, Err(Error::ConverstionError)));
+    }
 }
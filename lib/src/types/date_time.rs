@@ -1,11 +1,24 @@
-use crate::errors::Error;
+use crate::errors::*;
 use crate::types::*;
-use chrono::{DateTime, FixedOffset, NaiveDateTime, Offset, Timelike};
+use crate::version::Version;
+use bytes::*;
 use neo4rs_macros::BoltStruct;
-use std::convert::TryInto;
+#[cfg(feature = "chrono-tz")]
+use {chrono::TimeZone, chrono_tz::Tz};
+#[cfg(feature = "chrono")]
+use {
+    chrono::{DateTime, FixedOffset, NaiveDateTime, Offset, Timelike},
+    std::convert::TryInto,
+};
 
-#[derive(Debug, PartialEq, Clone, BoltStruct)]
-#[signature(0xB3, 0x46)]
+pub const SIGNATURE: u8 = Signature::LegacyDateTime as u8;
+pub const UTC_SIGNATURE: u8 = Signature::DateTime as u8;
+pub const ZONE_ID_SIGNATURE: u8 = Signature::LegacyDateTimeZoneId as u8;
+pub const UTC_ZONE_ID_SIGNATURE: u8 = Signature::DateTimeZoneId as u8;
+
+/// A datetime with a fixed offset from UTC, `seconds` are counted in the local time of the
+/// offset. Since bolt 5.0 the seconds are sent in UTC and converted when encoding or decoding.
+#[derive(Debug, PartialEq, Clone)]
 pub struct BoltDateTime {
     seconds: BoltInteger,
     nanoseconds: BoltInteger,
@@ -19,93 +32,279 @@ pub struct BoltLocalDateTime {
     nanoseconds: BoltInteger,
 }
 
-#[derive(Debug, PartialEq, Clone, BoltStruct)]
-#[signature(0xB3, 0x66)]
+/// A datetime in a named time zone like `Europe/Berlin`. Bolt 5.0 counts the seconds in UTC while
+/// older versions count them in the local time of the zone, the seconds are kept as they were
+/// received and only converted if the other encoding is needed. Converting between local and
+/// UTC seconds requires the time zone database of the `chrono-tz` feature.
+#[derive(Debug, PartialEq, Clone)]
 pub struct BoltDateTimeZoneId {
     seconds: BoltInteger,
     nanoseconds: BoltInteger,
     tz_id: BoltString,
+    utc: bool,
 }
 
-impl Into<BoltDateTimeZoneId> for (NaiveDateTime, &str) {
-    fn into(self) -> BoltDateTimeZoneId {
-        let seconds = self.0.timestamp().into();
-        let nanoseconds = (self.0.timestamp_subsec_nanos() as i64).into();
-        BoltDateTimeZoneId {
+fn has_tiny_header(input: &Bytes, fields: u8, signature: Signature) -> bool {
+    input.len() >= 2 && input[0] == structure::TINY | fields && input[1] == signature as u8
+}
+
+impl BoltDateTime {
+    fn signature(version: Version) -> Signature {
+        if version.has_utc_datetimes() {
+            Signature::DateTime
+        } else {
+            Signature::LegacyDateTime
+        }
+    }
+
+    pub fn can_parse(version: Version, input: &Bytes) -> bool {
+        has_tiny_header(input, 3, Self::signature(version))
+    }
+
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        self.to_bytes(version)
+    }
+
+    pub fn parse(version: Version, input: &mut Bytes) -> Result<BoltDateTime> {
+        signature::read_signature(input, Self::signature(version))?;
+        let mut seconds = BoltInteger::parse(version, input)?;
+        let nanoseconds = BoltInteger::parse(version, input)?;
+        let tz_offset_seconds = BoltInteger::parse(version, input)?;
+        if version.has_utc_datetimes() {
+            seconds = BoltInteger::new(seconds.value + tz_offset_seconds.value);
+        }
+        Ok(BoltDateTime {
             seconds,
             nanoseconds,
-            tz_id: self.1.into(),
+            tz_offset_seconds,
+        })
+    }
+}
+
+impl BoltEncode for BoltDateTime {
+    fn encode(&self, encoding: Encoding, dst: &mut BytesMut) -> Result<()> {
+        let version = encoding.version;
+        let mut seconds = self.seconds.value;
+        if version.has_utc_datetimes() {
+            seconds -= self.tz_offset_seconds.value;
+        }
+        dst.put_u8(structure::TINY | 3);
+        dst.put_u8(Self::signature(version).into());
+        BoltInteger::new(seconds).encode(encoding, dst)?;
+        self.nanoseconds.encode(encoding, dst)?;
+        self.tz_offset_seconds.encode(encoding, dst)
+    }
+
+    fn encoded_len(&self, encoding: Encoding) -> usize {
+        let version = encoding.version;
+        let mut seconds = self.seconds.value;
+        if version.has_utc_datetimes() {
+            seconds -= self.tz_offset_seconds.value;
+        }
+        2 + BoltInteger::new(seconds).encoded_len(encoding)
+            + self.nanoseconds.encoded_len(encoding)
+            + self.tz_offset_seconds.encoded_len(encoding)
+    }
+}
+
+impl BoltDateTimeZoneId {
+    fn signature(version: Version) -> Signature {
+        if version.has_utc_datetimes() {
+            Signature::DateTimeZoneId
+        } else {
+            Signature::LegacyDateTimeZoneId
+        }
+    }
+
+    /// The IANA name of the time zone
+    pub fn tz_id(&self) -> &str {
+        &self.tz_id.value
+    }
+
+    pub fn can_parse(version: Version, input: &Bytes) -> bool {
+        has_tiny_header(input, 3, Self::signature(version))
+    }
+
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        self.to_bytes(version)
+    }
+
+    pub fn parse(version: Version, input: &mut Bytes) -> Result<BoltDateTimeZoneId> {
+        signature::read_signature(input, Self::signature(version))?;
+        Ok(BoltDateTimeZoneId {
+            seconds: BoltInteger::parse(version, input)?,
+            nanoseconds: BoltInteger::parse(version, input)?,
+            tz_id: BoltString::parse(version, input)?,
+            utc: version.has_utc_datetimes(),
+        })
+    }
+
+    /// The seconds counted in UTC or in local time of the zone.
+    fn seconds_in(&self, utc: bool) -> Result<i64> {
+        if self.utc == utc {
+            return Ok(self.seconds.value);
         }
+        #[cfg(feature = "chrono-tz")]
+        {
+            let datetime = self.to_zoned().map_err(|_| {
+                Error::SerializationError(format!("invalid datetime in {}", self.tz_id.value))
+            })?;
+            if utc {
+                Ok(datetime.timestamp())
+            } else {
+                Ok(datetime.naive_local().and_utc().timestamp())
+            }
+        }
+        #[cfg(not(feature = "chrono-tz"))]
+        Err(Error::SerializationError(format!(
+            "converting a datetime in {} between local time and UTC requires the chrono-tz feature",
+            self.tz_id.value
+        )))
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    fn to_zoned(&self) -> Result<DateTime<Tz>> {
+        let tz: Tz = self
+            .tz_id
+            .value
+            .parse()
+            .map_err(|_| Error::ConverstionError)?;
+        let nanoseconds = self.nanoseconds.narrow()?;
+        let datetime = if self.utc {
+            tz.timestamp_opt(self.seconds.value, nanoseconds).single()
+        } else {
+            let local = DateTime::from_timestamp(self.seconds.value, nanoseconds)
+                .ok_or(Error::ConverstionError)?
+                .naive_utc();
+            // a local time repeated when clocks are set back is resolved to the earlier instant
+            tz.from_local_datetime(&local).earliest()
+        };
+        datetime.ok_or(Error::ConverstionError)
+    }
+}
+
+impl BoltEncode for BoltDateTimeZoneId {
+    fn encode(&self, encoding: Encoding, dst: &mut BytesMut) -> Result<()> {
+        let version = encoding.version;
+        let seconds = self.seconds_in(version.has_utc_datetimes())?;
+        dst.put_u8(structure::TINY | 3);
+        dst.put_u8(Self::signature(version).into());
+        BoltInteger::new(seconds).encode(encoding, dst)?;
+        self.nanoseconds.encode(encoding, dst)?;
+        self.tz_id.encode(encoding, dst)
+    }
+
+    /// Falls back to the received seconds if the encoding needs a conversion that fails, which
+    /// makes `encode` fail as well
+    fn encoded_len(&self, encoding: Encoding) -> usize {
+        let version = encoding.version;
+        let seconds = self
+            .seconds_in(version.has_utc_datetimes())
+            .unwrap_or(self.seconds.value);
+        2 + BoltInteger::new(seconds).encoded_len(encoding)
+            + self.nanoseconds.encoded_len(encoding)
+            + self.tz_id.encoded_len(encoding)
     }
 }
 
+#[cfg(feature = "chrono")]
+impl From<(NaiveDateTime, &str)> for BoltDateTimeZoneId {
+    fn from((datetime, tz_id): (NaiveDateTime, &str)) -> BoltDateTimeZoneId {
+        BoltDateTimeZoneId {
+            seconds: datetime.and_utc().timestamp().into(),
+            nanoseconds: (datetime.and_utc().timestamp_subsec_nanos() as i64).into(),
+            tz_id: tz_id.into(),
+            utc: false,
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
 impl TryInto<(NaiveDateTime, String)> for BoltDateTimeZoneId {
     type Error = Error;
 
     fn try_into(self) -> Result<(NaiveDateTime, String)> {
-        let datetime =
-            NaiveDateTime::from_timestamp(self.seconds.value, self.nanoseconds.value as u32);
+        let seconds = self
+            .seconds_in(false)
+            .map_err(|_| Error::ConverstionError)?;
+        let datetime = DateTime::from_timestamp(seconds, self.nanoseconds.narrow()?)
+            .ok_or(Error::ConverstionError)?
+            .naive_utc();
         Ok((datetime, self.tz_id.into()))
     }
 }
 
-impl Into<BoltLocalDateTime> for NaiveDateTime {
-    fn into(self) -> BoltLocalDateTime {
-        let seconds = self.timestamp().into();
-        let nanoseconds = (self.nanosecond() as i64).into();
+#[cfg(feature = "chrono-tz")]
+impl From<DateTime<Tz>> for BoltDateTimeZoneId {
+    fn from(datetime: DateTime<Tz>) -> BoltDateTimeZoneId {
+        BoltDateTimeZoneId {
+            seconds: datetime.timestamp().into(),
+            nanoseconds: (datetime.timestamp_subsec_nanos() as i64).into(),
+            tz_id: datetime.timezone().name().into(),
+            utc: true,
+        }
+    }
+}
+
+#[cfg(feature = "chrono-tz")]
+impl TryInto<DateTime<Tz>> for BoltDateTimeZoneId {
+    type Error = Error;
+
+    fn try_into(self) -> Result<DateTime<Tz>> {
+        self.to_zoned()
+    }
+}
 
+#[cfg(feature = "chrono")]
+impl From<NaiveDateTime> for BoltLocalDateTime {
+    fn from(datetime: NaiveDateTime) -> BoltLocalDateTime {
         BoltLocalDateTime {
-            seconds,
-            nanoseconds,
+            seconds: datetime.and_utc().timestamp().into(),
+            nanoseconds: (datetime.nanosecond() as i64).into(),
         }
     }
 }
 
+#[cfg(feature = "chrono")]
 impl TryInto<NaiveDateTime> for BoltLocalDateTime {
     type Error = Error;
 
     fn try_into(self) -> Result<NaiveDateTime> {
-        Ok(NaiveDateTime::from_timestamp(
-            self.seconds.value,
-            self.nanoseconds.value as u32,
-        ))
+        DateTime::from_timestamp(self.seconds.value, self.nanoseconds.narrow()?)
+            .map(|datetime| datetime.naive_utc())
+            .ok_or(Error::ConverstionError)
     }
 }
 
-impl Into<BoltDateTime> for DateTime<FixedOffset> {
-    fn into(self) -> BoltDateTime {
-        let seconds = (self.timestamp() + self.offset().fix().local_minus_utc() as i64).into();
-        let nanoseconds = (self.nanosecond() as i64).into();
-        let tz_offset_seconds = self.offset().fix().local_minus_utc().into();
-
+#[cfg(feature = "chrono")]
+impl From<DateTime<FixedOffset>> for BoltDateTime {
+    fn from(datetime: DateTime<FixedOffset>) -> BoltDateTime {
+        let offset = datetime.offset().fix().local_minus_utc() as i64;
         BoltDateTime {
-            seconds,
-            nanoseconds,
-            tz_offset_seconds,
+            seconds: (datetime.timestamp() + offset).into(),
+            nanoseconds: (datetime.nanosecond() as i64).into(),
+            tz_offset_seconds: offset.into(),
         }
     }
 }
 
+#[cfg(feature = "chrono")]
 impl TryInto<DateTime<FixedOffset>> for BoltDateTime {
     type Error = Error;
 
     fn try_into(self) -> Result<DateTime<FixedOffset>> {
         let seconds = self.seconds.value - self.tz_offset_seconds.value;
-        let datetime = NaiveDateTime::from_timestamp(seconds, self.nanoseconds.value as u32);
-        Ok(DateTime::from_utc(
-            datetime,
-            FixedOffset::east(self.tz_offset_seconds.value as i32),
-        ))
+        let datetime = DateTime::from_timestamp(seconds, self.nanoseconds.narrow()?)
+            .ok_or(Error::ConverstionError)?;
+        let offset = FixedOffset::east_opt(self.tz_offset_seconds.narrow()?)
+            .ok_or(Error::ConverstionError)?;
+        Ok(datetime.with_timezone(&offset))
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "chrono"))]
 mod tests {
     use super::*;
-    use crate::version::Version;
-    use bytes::*;
-    use std::cell::RefCell;
-    use std::rc::Rc;
 
     #[test]
     fn should_serialize_a_datetime() {
@@ -123,11 +322,11 @@ mod tests {
 
     #[test]
     fn should_deserialize_a_datetime() {
-        let bytes = Rc::new(RefCell::new(Bytes::from_static(&[
+        let mut bytes = Bytes::from_static(&[
             0xB3, 0x46, 0xCA, 0x55, 0x8A, 0xA7, 0x9B, 0x00, 0xC9, 0x0E, 0x10,
-        ])));
+        ]);
 
-        let datetime: DateTime<FixedOffset> = BoltDateTime::parse(Version::V4_1, bytes)
+        let datetime: DateTime<FixedOffset> = BoltDateTime::parse(Version::V4_1, &mut bytes)
             .unwrap()
             .try_into()
             .unwrap();
@@ -135,6 +334,29 @@ mod tests {
         assert_eq!(datetime.to_rfc2822(), "Wed, 24 Jun 2015 12:50:35 +0100");
     }
 
+    #[test]
+    fn should_serialize_a_v5_datetime_in_utc() {
+        let date: BoltDateTime = DateTime::parse_from_rfc2822("Wed, 24 Jun 2015 12:50:35 +0100")
+            .unwrap()
+            .into();
+
+        let mut bytes = date.into_bytes(Version::V5_0).unwrap();
+
+        assert_eq!(
+            bytes,
+            Bytes::from_static(&[
+                0xB3, 0x49, 0xCA, 0x55, 0x8A, 0x99, 0x8B, 0x00, 0xC9, 0x0E, 0x10,
+            ])
+        );
+        assert!(!BoltDateTime::can_parse(Version::V4_4, &bytes));
+        assert!(BoltDateTime::can_parse(Version::V5_0, &bytes));
+        let datetime: DateTime<FixedOffset> = BoltDateTime::parse(Version::V5_0, &mut bytes)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(datetime.to_rfc2822(), "Wed, 24 Jun 2015 12:50:35 +0100");
+    }
+
     #[test]
     fn should_serialize_a_localdatetime() {
         let date: BoltLocalDateTime =
@@ -151,11 +373,11 @@ mod tests {
 
     #[test]
     fn should_deserialize_a_localdatetime() {
-        let bytes = Rc::new(RefCell::new(Bytes::from_static(&[
+        let mut bytes = Bytes::from_static(&[
             0xB2, 0x64, 0xCA, 0x55, 0x93, 0xAC, 0x0F, 0xCA, 0x42, 0xEF, 0x9E, 0xC0,
-        ])));
+        ]);
 
-        let datetime: NaiveDateTime = BoltLocalDateTime::parse(Version::V4_1, bytes)
+        let datetime: NaiveDateTime = BoltLocalDateTime::parse(Version::V4_1, &mut bytes)
             .unwrap()
             .try_into()
             .unwrap();
@@ -182,12 +404,12 @@ mod tests {
 
     #[test]
     fn should_deserialize_a_datetime_with_zoneid() {
-        let bytes = Rc::new(RefCell::new(Bytes::from_static(&[
+        let mut bytes = Bytes::from_static(&[
             0xB3, 0x66, 0xCA, 0x55, 0x93, 0xAC, 0x0F, 0xCA, 0x42, 0xEF, 0x9E, 0xC0, 0x8C, 0x45,
             0x75, 0x72, 0x6F, 0x70, 0x65, 0x2F, 0x50, 0x61, 0x72, 0x69, 0x73,
-        ])));
+        ]);
 
-        let (datetime, zone_id) = BoltDateTimeZoneId::parse(Version::V4_1, bytes)
+        let (datetime, zone_id) = BoltDateTimeZoneId::parse(Version::V4_1, &mut bytes)
             .unwrap()
             .try_into()
             .unwrap();
@@ -195,4 +417,54 @@ mod tests {
         assert_eq!(datetime.to_string(), "2015-07-01 08:59:60.123");
         assert_eq!(zone_id, "Europe/Paris");
     }
+
+    #[cfg(not(feature = "chrono-tz"))]
+    #[test]
+    fn should_require_time_zones_to_convert_utc_seconds() {
+        let mut bytes = Bytes::from_static(&[
+            0xB3, 0x69, 0x00, 0x00, 0x8C, 0x45, 0x75, 0x72, 0x6F, 0x70, 0x65, 0x2F, 0x50, 0x61,
+            0x72, 0x69, 0x73,
+        ]);
+        let datetime = BoltDateTimeZoneId::parse(Version::V5_0, &mut bytes).unwrap();
+
+        assert!(matches!(
+            datetime.into_bytes(Version::V4_4),
+            Err(Error::SerializationError(_))
+        ));
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn should_round_trip_named_zones_across_dst() {
+        // 2021-03-28 01:00 UTC, clocks in Berlin jump from 02:00 CET to 03:00 CEST
+        let before: DateTime<Tz> = Tz::Europe__Berlin.timestamp_opt(1_616_893_199, 0).unwrap();
+        let after: DateTime<Tz> = Tz::Europe__Berlin.timestamp_opt(1_616_893_200, 0).unwrap();
+        assert_eq!(before.to_rfc3339(), "2021-03-28T01:59:59+01:00");
+        assert_eq!(after.to_rfc3339(), "2021-03-28T03:00:00+02:00");
+
+        for datetime in [before, after].iter() {
+            for version in [Version::V4_4, Version::V5_0].iter() {
+                let bolt: BoltDateTimeZoneId = (*datetime).into();
+                let mut bytes = bolt.into_bytes(*version).unwrap();
+                let parsed: DateTime<Tz> = BoltDateTimeZoneId::parse(*version, &mut bytes)
+                    .unwrap()
+                    .try_into()
+                    .unwrap();
+                assert_eq!(parsed, *datetime);
+                assert_eq!(parsed.timezone(), Tz::Europe__Berlin);
+            }
+        }
+    }
+
+    #[cfg(feature = "chrono-tz")]
+    #[test]
+    fn should_encode_local_seconds_before_bolt_5() {
+        let datetime = Tz::Europe__Berlin.timestamp_opt(1_616_893_200, 0).unwrap();
+        let bolt: BoltDateTimeZoneId = datetime.into();
+
+        let (local, tz_id): (NaiveDateTime, String) = bolt.try_into().unwrap();
+
+        assert_eq!(local.to_string(), "2021-03-28 03:00:00");
+        assert_eq!(tz_id, "Europe/Berlin");
+    }
 }
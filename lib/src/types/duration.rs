@@ -1,5 +1,7 @@
+use crate::errors::*;
 use crate::types::*;
 use neo4rs_macros::BoltStruct;
+use std::convert::TryFrom;
 
 #[derive(Debug, PartialEq, Clone, BoltStruct)]
 #[signature(0xB4, 0x45)]
@@ -26,25 +28,53 @@ impl BoltDuration {
     }
 }
 
-impl Into<BoltDuration> for std::time::Duration {
-    fn into(self) -> BoltDuration {
-        let seconds = self.as_secs();
-        let nanos = self.subsec_nanos();
+impl BoltDuration {
+    pub fn months(&self) -> i64 {
+        self.months.value
+    }
+
+    pub fn days(&self) -> i64 {
+        self.days.value
+    }
+
+    /// Seconds of the duration, negative for durations going back in time
+    pub fn seconds(&self) -> i64 {
+        self.seconds.value
+    }
+
+    /// Nanoseconds added to `seconds`, always within 0..1_000_000_000
+    pub fn nanoseconds(&self) -> i64 {
+        self.nanoseconds.value
+    }
+}
+
+impl From<std::time::Duration> for BoltDuration {
+    fn from(duration: std::time::Duration) -> BoltDuration {
         BoltDuration::new(
             0.into(),
             0.into(),
-            (seconds as i64).into(),
-            (nanos as i64).into(),
+            (duration.as_secs() as i64).into(),
+            (duration.subsec_nanos() as i64).into(),
         )
     }
 }
 
-impl Into<std::time::Duration> for BoltDuration {
-    fn into(self) -> std::time::Duration {
-        //TODO: clarify month issue
-        let seconds =
-            self.seconds.value + (self.days.value * 24 * 3600) + (self.months.value * 2_629_800);
-        std::time::Duration::new(seconds as u64, self.nanoseconds.value as u32)
+/// Months and days have no fixed length, only durations made of seconds and nanoseconds can be
+/// converted. Negative durations can not be represented either.
+impl TryFrom<BoltDuration> for std::time::Duration {
+    type Error = Error;
+
+    fn try_from(duration: BoltDuration) -> Result<std::time::Duration> {
+        if duration.months.value != 0 || duration.days.value != 0 {
+            return Err(Error::ConverstionError);
+        }
+        let seconds = u64::try_from(duration.seconds.value).map_err(|_| Error::ConverstionError)?;
+        let nanoseconds =
+            u32::try_from(duration.nanoseconds.value).map_err(|_| Error::ConverstionError)?;
+        if nanoseconds >= 1_000_000_000 {
+            return Err(Error::ConverstionError);
+        }
+        Ok(std::time::Duration::new(seconds, nanoseconds))
     }
 }
 
@@ -53,8 +83,6 @@ mod tests {
     use super::*;
     use crate::version::Version;
     use bytes::*;
-    use std::cell::RefCell;
-    use std::rc::Rc;
 
     #[test]
     fn should_serialize_a_duration() {
@@ -70,15 +98,56 @@ mod tests {
 
     #[test]
     fn should_deserialize_a_duration() {
-        let bytes = Rc::new(RefCell::new(Bytes::from_static(&[
-            0xB4, 0x45, 0x0C, 0x02, 0x1E, 0xC9, 0x02, 0xBC,
-        ])));
+        let mut bytes = Bytes::from_static(&[0xB4, 0x45, 0x0C, 0x02, 0x1E, 0xC9, 0x02, 0xBC]);
 
-        let duration: BoltDuration = BoltDuration::parse(Version::V4_1, bytes).unwrap();
+        let duration: BoltDuration = BoltDuration::parse(Version::V4_1, &mut bytes).unwrap();
 
         assert_eq!(duration.months.value, 12);
         assert_eq!(duration.days.value, 2);
         assert_eq!(duration.seconds.value, 30);
         assert_eq!(duration.nanoseconds.value, 700);
     }
+
+    #[test]
+    fn should_round_trip_a_negative_duration() {
+        let duration = BoltDuration::new((-14).into(), (-3).into(), (-2).into(), 500.into());
+
+        let mut bytes = duration.clone().into_bytes(Version::V4_1).unwrap();
+        let parsed = BoltDuration::parse(Version::V4_1, &mut bytes).unwrap();
+
+        assert_eq!(parsed, duration);
+        assert_eq!(parsed.months(), -14);
+        assert_eq!(parsed.days(), -3);
+        assert_eq!(parsed.seconds(), -2);
+        assert_eq!(parsed.nanoseconds(), 500);
+    }
+
+    #[test]
+    fn should_convert_seconds_to_std_duration() {
+        let std_duration = std::time::Duration::new(5_259_600, 7);
+
+        let duration: BoltDuration = std_duration.into();
+
+        assert_eq!(duration.months(), 0);
+        assert_eq!(duration.days(), 0);
+        assert_eq!(
+            std::time::Duration::try_from(duration).unwrap(),
+            std_duration
+        );
+    }
+
+    #[test]
+    fn should_not_convert_months_days_or_negative_seconds() {
+        let durations = [
+            BoltDuration::new(1.into(), 0.into(), 0.into(), 0.into()),
+            BoltDuration::new(0.into(), 1.into(), 0.into(), 0.into()),
+            BoltDuration::new(0.into(), 0.into(), (-1).into(), 0.into()),
+        ];
+        for duration in durations.iter() {
+            assert!(matches!(
+                std::time::Duration::try_from(duration.clone()),
+                Err(Error::ConverstionError)
+            ));
+        }
+    }
 }
@@ -1,12 +1,13 @@
 use crate::errors::*;
+use crate::types::marker::{peek_kind, BoltKind};
+use crate::types::{read_exact, BoltEncode, Encoding};
 use crate::version::Version;
 use bytes::*;
-use std::cell::RefCell;
 use std::mem;
-use std::rc::Rc;
 
 pub const MARKER: u8 = 0xC1;
 
+/// A 64 bit float, which deliberately is neither `Eq` nor `Hash` as NaN is not equal to itself.
 #[derive(Debug, PartialEq, Clone)]
 pub struct BoltFloat {
     pub value: f64,
@@ -17,24 +18,33 @@ impl BoltFloat {
         BoltFloat { value }
     }
 
-    pub fn can_parse(_: Version, input: Rc<RefCell<Bytes>>) -> bool {
-        input.borrow()[0] == MARKER
+    pub fn can_parse(_: Version, input: &Bytes) -> bool {
+        peek_kind(input) == Some(BoltKind::Float)
     }
 }
 
 impl BoltFloat {
-    pub fn parse(_: Version, input: Rc<RefCell<Bytes>>) -> Result<BoltFloat> {
-        let mut input = input.borrow_mut();
-        let _marker = input.get_u8();
-        let value = input.get_f64();
+    pub fn parse(_: Version, input: &mut Bytes) -> Result<BoltFloat> {
+        read_exact(input, mem::size_of::<u8>())?;
+        let value = read_exact(input, mem::size_of::<f64>())?.get_f64();
         Ok(BoltFloat::new(value))
     }
 
-    pub fn into_bytes(self, _: Version) -> Result<Bytes> {
-        let mut bytes = BytesMut::with_capacity(mem::size_of::<u8>() + mem::size_of::<f64>());
-        bytes.put_u8(MARKER);
-        bytes.put_f64(self.value);
-        Ok(bytes.freeze())
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        self.to_bytes(version)
+    }
+}
+
+impl BoltEncode for BoltFloat {
+    fn encode(&self, _: Encoding, dst: &mut BytesMut) -> Result<()> {
+        dst.reserve(mem::size_of::<u8>() + mem::size_of::<f64>());
+        dst.put_u8(MARKER);
+        dst.put_f64(self.value);
+        Ok(())
+    }
+
+    fn encoded_len(&self, _: Encoding) -> usize {
+        mem::size_of::<u8>() + mem::size_of::<f64>()
     }
 }
 
@@ -59,16 +69,57 @@ mod tests {
 
     #[test]
     fn should_deserialize_float() {
-        let input = Rc::new(RefCell::new(Bytes::from_static(&[
-            0xC1, 0x3F, 0xF3, 0xAE, 0x14, 0x7A, 0xE1, 0x47, 0xAE,
-        ])));
-        let bolt_float: BoltFloat = BoltFloat::parse(Version::V4_1, input).unwrap();
+        let mut input = Bytes::from_static(&[0xC1, 0x3F, 0xF3, 0xAE, 0x14, 0x7A, 0xE1, 0x47, 0xAE]);
+        let bolt_float: BoltFloat = BoltFloat::parse(Version::V4_1, &mut input).unwrap();
         assert_eq!(bolt_float.value, 1.23);
 
-        let input = Rc::new(RefCell::new(Bytes::from_static(&[
-            0xC1, 0xBF, 0xF3, 0xAE, 0x14, 0x7A, 0xE1, 0x47, 0xAE,
-        ])));
-        let bolt_float: BoltFloat = BoltFloat::parse(Version::V4_1, input).unwrap();
+        let mut input = Bytes::from_static(&[0xC1, 0xBF, 0xF3, 0xAE, 0x14, 0x7A, 0xE1, 0x47, 0xAE]);
+        let bolt_float: BoltFloat = BoltFloat::parse(Version::V4_1, &mut input).unwrap();
         assert_eq!(bolt_float.value, -1.23);
     }
+
+    #[test]
+    fn should_serialize_known_floats() {
+        let b: Bytes = BoltFloat::new(1.1).into_bytes(Version::V4_1).unwrap();
+        assert_eq!(
+            &b[..],
+            &[0xC1, 0x3F, 0xF1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9A]
+        );
+
+        let b: Bytes = BoltFloat::new(0.0).into_bytes(Version::V4_1).unwrap();
+        assert_eq!(
+            &b[..],
+            &[0xC1, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+
+        let b: Bytes = BoltFloat::new(-0.0).into_bytes(Version::V4_1).unwrap();
+        assert_eq!(
+            &b[..],
+            &[0xC1, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn should_round_trip_special_floats() {
+        let values = [
+            f64::NAN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            0.0,
+            -0.0,
+            f64::MIN_POSITIVE / 2.0,
+            f64::MIN,
+            f64::MAX,
+        ];
+
+        for value in values.iter() {
+            let b: Bytes = BoltFloat::new(*value).into_bytes(Version::V4_1).unwrap();
+            assert_eq!(b.len(), 9);
+            assert_eq!(&b[1..], &value.to_bits().to_be_bytes());
+
+            let mut input = b;
+            let bolt_float: BoltFloat = BoltFloat::parse(Version::V4_1, &mut input).unwrap();
+            assert_eq!(bolt_float.value.to_bits(), value.to_bits());
+        }
+    }
 }
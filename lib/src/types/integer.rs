@@ -1,17 +1,18 @@
 use crate::errors::*;
+use crate::types::marker::{peek_kind, BoltKind};
+use crate::types::{read_exact, BoltEncode, Encoding};
 use crate::version::Version;
 use bytes::*;
-use std::cell::RefCell;
+use std::convert::TryFrom;
 use std::mem;
 use std::ops::{Add, Sub};
-use std::rc::Rc;
 
 pub const INT_8: u8 = 0xC8;
 pub const INT_16: u8 = 0xC9;
 pub const INT_32: u8 = 0xCA;
 pub const INT_64: u8 = 0xCB;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct BoltInteger {
     pub value: i64,
 }
@@ -37,54 +38,92 @@ impl BoltInteger {
         BoltInteger { value }
     }
 
-    pub fn can_parse(_: Version, input: Rc<RefCell<Bytes>>) -> bool {
-        let marker = input.borrow()[0];
-        (-16..=127).contains(&(marker as i8))
-            || marker == INT_8
-            || marker == INT_16
-            || marker == INT_32
-            || marker == INT_64
+    pub fn can_parse(_: Version, input: &Bytes) -> bool {
+        peek_kind(input) == Some(BoltKind::Integer)
     }
 }
 
 impl BoltInteger {
-    pub fn parse(_: Version, input: Rc<RefCell<Bytes>>) -> Result<BoltInteger> {
-        let mut input = input.borrow_mut();
-        let value: i64 = match input.get_u8() {
-            marker if (-16..=127).contains(&(marker as i8)) => marker as i64,
-            INT_8 => input.get_i8() as i64,
-            INT_16 => input.get_i16() as i64,
-            INT_32 => input.get_i32() as i64,
-            INT_64 => input.get_i64() as i64,
-            _ => return Err(Error::InvalidTypeMarker("invalid integer marker".into())),
+    pub fn parse(_: Version, input: &mut Bytes) -> Result<BoltInteger> {
+        let value: i64 = match read_exact(input, mem::size_of::<u8>())?.get_u8() {
+            marker if (-16..=127).contains(&(marker as i8)) => marker as i8 as i64,
+            INT_8 => read_exact(input, mem::size_of::<i8>())?.get_i8() as i64,
+            INT_16 => read_exact(input, mem::size_of::<i16>())?.get_i16() as i64,
+            INT_32 => read_exact(input, mem::size_of::<i32>())?.get_i32() as i64,
+            INT_64 => read_exact(input, mem::size_of::<i64>())?.get_i64(),
+            marker => return Err(unexpected_marker(BoltKind::Integer, marker)),
         };
 
         Ok(BoltInteger::new(value))
     }
 
-    pub fn into_bytes(self, _: Version) -> Result<Bytes> {
-        let mut bytes = BytesMut::with_capacity(mem::size_of::<u8>() + mem::size_of::<i64>());
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        self.to_bytes(version)
+    }
+}
+
+impl BoltEncode for BoltInteger {
+    fn encode(&self, encoding: Encoding, dst: &mut BytesMut) -> Result<()> {
+        dst.reserve(mem::size_of::<u8>() + mem::size_of::<i64>());
+        if encoding.integers == IntegerEncoding::AlwaysI64 {
+            dst.put_u8(INT_64);
+            dst.put_i64(self.value);
+            return Ok(());
+        }
         match self.value {
-            -16..=127 => bytes.put_u8(self.value as u8),
+            -16..=127 => dst.put_u8(self.value as u8),
             -128..=-17 => {
-                bytes.put_u8(INT_8);
-                bytes.put_i8(self.value as i8);
+                dst.put_u8(INT_8);
+                dst.put_i8(self.value as i8);
             }
             128..=32_767 | -32_768..=-129 => {
-                bytes.put_u8(INT_16);
-                bytes.put_i16(self.value as i16);
+                dst.put_u8(INT_16);
+                dst.put_i16(self.value as i16);
             }
             32_768..=2_147_483_647 | -2_147_483_648..=-32_769 => {
-                bytes.put_u8(INT_32);
-                bytes.put_i32(self.value as i32);
+                dst.put_u8(INT_32);
+                dst.put_i32(self.value as i32);
             }
-            2_147_483_648..=9_223_372_036_854_775_807
-            | -9_223_372_036_854_775_808..=-2_147_483_649 => {
-                bytes.put_u8(INT_64);
-                bytes.put_i64(self.value as i64);
+            i64::MIN..=-2_147_483_649 | 2_147_483_648..=i64::MAX => {
+                dst.put_u8(INT_64);
+                dst.put_i64(self.value);
             }
         }
-        Ok(bytes.freeze())
+        Ok(())
+    }
+
+    fn encoded_len(&self, encoding: Encoding) -> usize {
+        if encoding.integers == IntegerEncoding::AlwaysI64 {
+            return 1 + mem::size_of::<i64>();
+        }
+        match self.value {
+            -16..=127 => 1,
+            -128..=-17 => 1 + mem::size_of::<i8>(),
+            -32_768..=32_767 => 1 + mem::size_of::<i16>(),
+            -2_147_483_648..=2_147_483_647 => 1 + mem::size_of::<i32>(),
+            _ => 1 + mem::size_of::<i64>(),
+        }
+    }
+}
+
+/// How the integers of requests are encoded, see
+/// [`ConfigBuilder::integer_encoding`](crate::ConfigBuilder::integer_encoding).
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum IntegerEncoding {
+    /// The smallest marker the value fits into, a single byte from -16 to 127
+    #[default]
+    Minimal,
+    /// Always [`INT_64`] followed by eight bytes, for tooling which expects a fixed width
+    AlwaysI64,
+}
+
+impl BoltInteger {
+    /// The value as a narrower integer type, checked instead of truncated
+    pub fn narrow<T: TryFrom<i64>>(&self) -> Result<T> {
+        T::try_from(self.value).map_err(|_| Error::IntegerOverflow {
+            value: self.value,
+            target: std::any::type_name::<T>(),
+        })
     }
 }
 
@@ -110,6 +149,47 @@ impl Into<BoltInteger> for i32 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::{BoltList, BoltMap, BoltType};
+
+    fn always_i64() -> Encoding {
+        Encoding {
+            version: Version::V4_1,
+            integers: IntegerEncoding::AlwaysI64,
+        }
+    }
+
+    #[test]
+    fn should_encode_integers_as_configured() {
+        let five = BoltInteger::new(5);
+
+        assert_eq!(&five.to_bytes(Version::V4_1).unwrap()[..], &[0x05]);
+        let wide = five.to_bytes(always_i64()).unwrap();
+        assert_eq!(&wide[..], &[INT_64, 0, 0, 0, 0, 0, 0, 0, 0x05]);
+        assert_eq!(five.encoded_len(always_i64()), wide.len());
+    }
+
+    #[test]
+    fn should_widen_nested_integers() {
+        let mut map = BoltMap::default();
+        map.put("n".into(), BoltType::from(-128));
+        let value = BoltType::List(BoltList::from(vec![
+            BoltType::from(5),
+            BoltType::from("a"),
+            BoltType::Map(map),
+        ]));
+
+        let wide = value.to_bytes(always_i64()).unwrap();
+
+        let expected: Vec<u8> = [
+            &[0x93, INT_64, 0, 0, 0, 0, 0, 0, 0, 0x05, 0x81, b'a'][..],
+            &[
+                0xA1, 0x81, b'n', INT_64, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x80,
+            ],
+        ]
+        .concat();
+        assert_eq!(&wide[..], &expected[..]);
+        assert_eq!(value.encoded_len(always_i64()), wide.len());
+    }
 
     #[test]
     fn should_serialize_integer() {
@@ -139,28 +219,61 @@ mod tests {
 
     #[test]
     fn should_deserialize_integer() {
-        let b = Rc::new(RefCell::new(Bytes::from_static(&[0x2A])));
-        let bolt_int: BoltInteger = BoltInteger::parse(Version::V4_1, b).unwrap();
+        let mut b = Bytes::from_static(&[0x2A]);
+        let bolt_int: BoltInteger = BoltInteger::parse(Version::V4_1, &mut b).unwrap();
         assert_eq!(bolt_int.value, 42);
 
-        let b = Rc::new(RefCell::new(Bytes::from_static(&[INT_8, 0x81])));
-        let bolt_int: BoltInteger = BoltInteger::parse(Version::V4_1, b).unwrap();
+        let mut b = Bytes::from_static(&[INT_8, 0x81]);
+        let bolt_int: BoltInteger = BoltInteger::parse(Version::V4_1, &mut b).unwrap();
         assert_eq!(bolt_int.value, -127);
 
-        let b = Rc::new(RefCell::new(Bytes::from_static(&[INT_16, 0x00, 0x81])));
-        let bolt_int: BoltInteger = BoltInteger::parse(Version::V4_1, b).unwrap();
+        let mut b = Bytes::from_static(&[INT_16, 0x00, 0x81]);
+        let bolt_int: BoltInteger = BoltInteger::parse(Version::V4_1, &mut b).unwrap();
         assert_eq!(bolt_int.value, 129);
 
-        let b = Rc::new(RefCell::new(Bytes::from_static(&[
-            INT_32, 0x00, 0x00, 0x80, 0x00,
-        ])));
-        let bolt_int: BoltInteger = BoltInteger::parse(Version::V4_1, b).unwrap();
+        let mut b = Bytes::from_static(&[INT_32, 0x00, 0x00, 0x80, 0x00]);
+        let bolt_int: BoltInteger = BoltInteger::parse(Version::V4_1, &mut b).unwrap();
         assert_eq!(bolt_int.value, 32_768);
 
-        let b = Rc::new(RefCell::new(Bytes::from_static(&[
-            INT_64, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00,
-        ])));
-        let bolt_int: BoltInteger = BoltInteger::parse(Version::V4_1, b).unwrap();
+        let mut b = Bytes::from_static(&[INT_64, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00]);
+        let bolt_int: BoltInteger = BoltInteger::parse(Version::V4_1, &mut b).unwrap();
         assert_eq!(bolt_int.value, 2_147_483_648);
     }
+
+    #[test]
+    fn should_serialize_integer_boundaries() {
+        let cases: Vec<(i64, &[u8])> = vec![
+            (-16, &[0xF0]),
+            (-17, &[INT_8, 0xEF]),
+            (127, &[0x7F]),
+            (128, &[INT_16, 0x00, 0x80]),
+            (-128, &[INT_8, 0x80]),
+            (-129, &[INT_16, 0xFF, 0x7F]),
+            (32_767, &[INT_16, 0x7F, 0xFF]),
+            (-32_768, &[INT_16, 0x80, 0x00]),
+            (i32::MAX as i64, &[INT_32, 0x7F, 0xFF, 0xFF, 0xFF]),
+            (i32::MIN as i64, &[INT_32, 0x80, 0x00, 0x00, 0x00]),
+            (
+                i32::MIN as i64 - 1,
+                &[INT_64, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F, 0xFF, 0xFF, 0xFF],
+            ),
+            (
+                i64::MAX,
+                &[INT_64, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+            ),
+            (
+                i64::MIN,
+                &[INT_64, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+        ];
+
+        for (value, expected) in cases {
+            let b: Bytes = BoltInteger::new(value).into_bytes(Version::V4_1).unwrap();
+            assert_eq!(&b[..], expected, "serializing {}", value);
+
+            let mut input = b;
+            let parsed = BoltInteger::parse(Version::V4_1, &mut input).unwrap();
+            assert_eq!(parsed.value, value);
+        }
+    }
 }
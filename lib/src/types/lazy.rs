@@ -0,0 +1,210 @@
+use crate::errors::*;
+use crate::types::marker::{peek_kind, BoltKind};
+use crate::types::*;
+use crate::version::Version;
+use bytes::*;
+use std::convert::TryFrom;
+use std::mem;
+
+/// A map which keeps its entries encoded and decodes a value only when it is accessed, for
+/// large maps where just a few entries are read.
+///
+/// Parsing walks over the entries once to find the end of the map, without allocating. Every
+/// access scans the keys from the start until it finds a match, so reading most of the entries
+/// is cheaper with an eagerly decoded [`BoltMap`]. [`BoltNode::parse_lazy`] keeps the properties
+/// of a node in a lazy map.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LazyMap {
+    version: Version,
+    len: usize,
+    entries: Bytes,
+}
+
+impl LazyMap {
+    pub fn can_parse(version: Version, input: &Bytes) -> bool {
+        BoltMap::can_parse(version, input)
+    }
+
+    pub fn parse(version: Version, input: &mut Bytes) -> Result<LazyMap> {
+        let marker = read_exact(input, mem::size_of::<u8>())?.get_u8();
+        let len = match read_size(input, marker, map::TINY, map::SMALL)? {
+            Some(len) => len,
+            None => return Err(unexpected_marker(BoltKind::Map, marker)),
+        };
+        let mut rest = input.clone();
+        for _ in 0..len {
+            read_key(&mut rest)?;
+            skip_value(&mut rest)?;
+        }
+        let consumed = input.len() - rest.len();
+        Ok(LazyMap {
+            version,
+            len,
+            entries: input.split_to(consumed),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Decodes the value of `key` and converts it, `None` if the key is missing or the value
+    /// does not convert into `T`
+    pub fn get<T: TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
+        self.value(key)
+            .ok()
+            .flatten()
+            .and_then(|value| T::try_from(value).ok())
+    }
+
+    /// Decodes the value of `key`, `None` if the key is missing
+    pub fn value(&self, key: &str) -> Result<Option<BoltType>> {
+        let mut input = self.entries.clone();
+        for _ in 0..self.len {
+            if read_key(&mut input)? == key.as_bytes() {
+                return BoltType::parse(self.version, &mut input).map(Some);
+            }
+            skip_value(&mut input)?;
+        }
+        Ok(None)
+    }
+
+    /// Decodes all entries into a [`BoltMap`]
+    pub fn decode(&self) -> Result<BoltMap> {
+        let mut input = self.entries.clone();
+        let mut map = BoltMap::with_capacity(self.len);
+        for _ in 0..self.len {
+            let key = BoltString::parse(self.version, &mut input)?;
+            map.put(key, BoltType::parse(self.version, &mut input)?);
+        }
+        Ok(map)
+    }
+}
+
+/// The size of a string, bytes, list or map after its `marker` was read. The size is in the
+/// marker for the `tiny` family and follows as 8, 16 or 32 bit integer for the markers from
+/// `small` on. `None` if the marker belongs to neither.
+fn read_size(input: &mut Bytes, marker: u8, tiny: u8, small: u8) -> Result<Option<usize>> {
+    let size = match marker {
+        _ if marker & 0xF0 == tiny => (marker & 0x0F) as usize,
+        _ if marker == small => read_exact(input, 1)?.get_u8() as usize,
+        _ if marker == small + 1 => read_exact(input, 2)?.get_u16() as usize,
+        _ if marker == small + 2 => read_exact(input, 4)?.get_u32() as usize,
+        _ => return Ok(None),
+    };
+    Ok(Some(size))
+}
+
+/// The encoded bytes of a string key, without decoding it
+fn read_key(input: &mut Bytes) -> Result<Bytes> {
+    if peek_kind(input) != Some(BoltKind::String) {
+        return Err(Error::DeserializationError(
+            "map key is not a string".to_owned(),
+        ));
+    }
+    let marker = read_exact(input, 1)?.get_u8();
+    let size = read_size(input, marker, string::TINY, string::SMALL)?.unwrap_or_default();
+    read_exact(input, size)
+}
+
+/// Advances the input past the next value without decoding it
+pub(crate) fn skip_value(input: &mut Bytes) -> Result<()> {
+    let marker = read_exact(input, 1)?.get_u8();
+    let (bytes, values) = match marker {
+        0x00..=0x7F | 0xF0..=0xFF | 0xC0 | 0xC2 | 0xC3 => (0, 0),
+        0xC1 | 0xCB => (8, 0),
+        0xC8 => (1, 0),
+        0xC9 => (2, 0),
+        0xCA => (4, 0),
+        0xCC => (read_exact(input, 1)?.get_u8() as usize, 0),
+        0xCD => (read_exact(input, 2)?.get_u16() as usize, 0),
+        0xCE => (read_exact(input, 4)?.get_u32() as usize, 0),
+        0xB0..=0xBF => (1, (marker & 0x0F) as usize),
+        _ => {
+            if let Some(size) = read_size(input, marker, string::TINY, string::SMALL)? {
+                (size, 0)
+            } else if let Some(size) = read_size(input, marker, list::TINY, list::SMALL)? {
+                (0, size)
+            } else if let Some(size) = read_size(input, marker, map::TINY, map::SMALL)? {
+                (0, 2 * size)
+            } else {
+                return Err(Error::InvalidTypeMarker(format!(
+                    "reserved marker {:#04X}",
+                    marker
+                )));
+            }
+        }
+    };
+    read_exact(input, bytes)?;
+    for _ in 0..values {
+        skip_value(input)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wide_node_properties() -> BoltMap {
+        (0..50)
+            .map(|i| {
+                let value = match i % 5 {
+                    0 => BoltType::from(i << 40),
+                    1 => BoltType::from(format!("value {}", i).repeat(20)),
+                    2 => BoltType::List(vec![1.5.into(), BoltType::Null(BoltNull)].into()),
+                    3 => BoltType::Date(BoltDate::new(i)),
+                    _ => BoltType::Bytes(BoltBytes::new(vec![0; 300].into())),
+                };
+                (format!("p{}", i).into(), value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_decode_values_on_access() {
+        let properties = wide_node_properties();
+        let mut input = properties.clone().into_bytes(Version::V4_1).unwrap();
+        input = [&input[..], &[0x2A]].concat().into();
+
+        let lazy = LazyMap::parse(Version::V4_1, &mut input).unwrap();
+
+        assert_eq!(input, Bytes::from_static(&[0x2A]));
+        assert_eq!(lazy.len(), 50);
+        assert_eq!(lazy.get::<i64>("p45"), Some(45 << 40));
+        assert_eq!(lazy.get::<String>("p1"), properties.get::<String>("p1"));
+        assert_eq!(lazy.value("p3").unwrap(), properties.get::<BoltType>("p3"));
+        assert_eq!(lazy.value("missing").unwrap(), None);
+        assert_eq!(lazy.decode().unwrap(), properties);
+    }
+
+    #[test]
+    fn should_skip_nested_values() {
+        let node = BoltNode::new(
+            1.into(),
+            vec!["Person".into()].into(),
+            wide_node_properties(),
+        );
+        let value = BoltType::List(vec![BoltType::Node(node), 3.25.into(), true.into()].into());
+        let mut input = value.into_bytes(Version::V4_1).unwrap();
+
+        skip_value(&mut input).unwrap();
+
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn should_reject_truncated_lazy_map() {
+        let bytes = wide_node_properties().into_bytes(Version::V4_1).unwrap();
+        let mut input = bytes.slice(..bytes.len() - 1);
+
+        assert!(matches!(
+            LazyMap::parse(Version::V4_1, &mut input),
+            Err(Error::DeserializationError(_))
+        ));
+    }
+}
@@ -1,17 +1,17 @@
 use crate::errors::*;
+use crate::types::marker::{peek_kind, BoltKind};
 use crate::types::*;
 use crate::version::Version;
 use bytes::*;
-use std::cell::RefCell;
+use std::iter::FromIterator;
 use std::mem;
-use std::rc::Rc;
 
 pub const TINY: u8 = 0x90;
 pub const SMALL: u8 = 0xD4;
 pub const MEDIUM: u8 = 0xD5;
 pub const LARGE: u8 = 0xD6;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct BoltList {
     pub value: Vec<BoltType>,
 }
@@ -37,6 +37,10 @@ impl BoltList {
         self.value.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
     pub fn push(&mut self, value: BoltType) {
         self.value.push(value);
     }
@@ -45,12 +49,8 @@ impl BoltList {
         self.value.get(index)
     }
 
-    pub fn can_parse(_: Version, input: Rc<RefCell<Bytes>>) -> bool {
-        let marker = input.borrow()[0];
-        (TINY..=(TINY | 0x0F)).contains(&marker)
-            || marker == SMALL
-            || marker == MEDIUM
-            || marker == LARGE
+    pub fn can_parse(_: Version, input: &Bytes) -> bool {
+        peek_kind(input) == Some(BoltKind::List)
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &BoltType> {
@@ -68,7 +68,13 @@ impl IntoIterator for BoltList {
 
 impl Into<Vec<String>> for BoltList {
     fn into(self) -> Vec<String> {
-        self.value.into_iter().map(|x| x.to_string()).collect()
+        self.value
+            .into_iter()
+            .map(|x| match x {
+                BoltType::String(s) => s.value,
+                x => x.to_string(),
+            })
+            .collect()
     }
 }
 
@@ -82,60 +88,87 @@ impl From<Vec<BoltType>> for BoltList {
     }
 }
 
+/// Collects plain rust values into a list, e.g. for `UNWIND $rows`
+///
+/// ```
+/// use neo4rs::types::{BoltList, BoltType};
+/// use std::iter::FromIterator;
+///
+/// let mut ids = BoltList::from_iter(1..4);
+/// ids.extend(vec![10, 20]);
+/// assert_eq!(ids.len(), 5);
+/// assert_eq!(ids.get(3), Some(&BoltType::from(10)));
+///
+/// let names: BoltList = vec!["a", "b"].into_iter().collect();
+/// assert_eq!(names.get(0), Some(&BoltType::from("a")));
+/// ```
+impl<T: Into<BoltType>> FromIterator<T> for BoltList {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        BoltList {
+            value: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<T: Into<BoltType>> Extend<T> for BoltList {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.value.extend(iter.into_iter().map(Into::into));
+    }
+}
+
 impl BoltList {
     pub fn into_bytes(self, version: Version) -> Result<Bytes> {
-        let mut values = BytesMut::new();
-        let length = self.value.len();
+        self.to_bytes(version)
+    }
 
-        for elem in self.value {
-            values.put(elem.into_bytes(version)?);
+    pub fn parse(version: Version, input: &mut Bytes) -> Result<BoltList> {
+        let marker = read_exact(input, mem::size_of::<u8>())?.get_u8();
+        let size = match marker {
+            0x90..=0x9F => 0x0F & marker as usize,
+            SMALL => read_exact(input, mem::size_of::<u8>())?.get_u8() as usize,
+            MEDIUM => read_exact(input, mem::size_of::<u16>())?.get_u16() as usize,
+            LARGE => read_exact(input, mem::size_of::<u32>())?.get_u32() as usize,
+            _ => return Err(unexpected_marker(BoltKind::List, marker)),
+        };
+
+        // every element takes at least a byte, which bounds what a corrupt size can allocate
+        let mut list = BoltList::with_capacity(size.min(input.remaining()));
+        for _ in 0..size {
+            list.push(BoltType::parse(version, input)?);
         }
 
-        let mut bytes =
-            BytesMut::with_capacity(mem::size_of::<u8>() + mem::size_of::<u32>() + values.len());
+        Ok(list)
+    }
+}
 
+impl BoltEncode for BoltList {
+    fn encode(&self, encoding: Encoding, dst: &mut BytesMut) -> Result<()> {
+        let length = self.value.len();
         match length {
-            0..=15 => bytes.put_u8(TINY | length as u8),
+            0..=15 => dst.put_u8(TINY | length as u8),
             16..=255 => {
-                bytes.put_u8(SMALL);
-                bytes.put_u8(length as u8);
+                dst.put_u8(SMALL);
+                dst.put_u8(length as u8);
             }
             256..=65_535 => {
-                bytes.put_u8(MEDIUM);
-                bytes.put_u16(length as u16);
+                dst.put_u8(MEDIUM);
+                dst.put_u16(length as u16);
             }
-            65_536..=2_147_483_648 => {
-                bytes.put_u8(LARGE);
-                bytes.put_u32(length as u32);
+            65_536..=4_294_967_295 => {
+                dst.put_u8(LARGE);
+                dst.put_u32(length as u32);
             }
             _ => return Err(Error::ListTooLong),
         }
-
-        bytes.put(values);
-        Ok(bytes.freeze())
-    }
-
-    pub fn parse(version: Version, input: Rc<RefCell<Bytes>>) -> Result<BoltList> {
-        let marker = input.borrow_mut().get_u8();
-        let size = match marker {
-            0x90..=0x9F => 0x0F & marker as usize,
-            SMALL => input.borrow_mut().get_u8() as usize,
-            MEDIUM => input.borrow_mut().get_u16() as usize,
-            LARGE => input.borrow_mut().get_u32() as usize,
-            _ => {
-                return Err(Error::InvalidTypeMarker(format!(
-                    "invalid list marker {}",
-                    marker
-                )))
-            }
-        };
-
-        let mut list = BoltList::with_capacity(size);
-        for _ in 0..size {
-            list.push(BoltType::parse(version, input.clone())?);
+        for elem in &self.value {
+            elem.encode(encoding, dst)?;
         }
+        Ok(())
+    }
 
-        Ok(list)
+    fn encoded_len(&self, encoding: Encoding) -> usize {
+        let elements: usize = self.value.iter().map(|e| e.encoded_len(encoding)).sum();
+        header_len(self.value.len()) + elements
     }
 }
 
@@ -171,9 +204,9 @@ mod tests {
 
     #[test]
     fn should_deserialize_list() {
-        let b = Rc::new(RefCell::new(Bytes::from_static(&[0x92, 0x81, 0x61, 0x01])));
+        let mut b = Bytes::from_static(&[0x92, 0x81, 0x61, 0x01]);
 
-        let bolt_list: BoltList = BoltList::parse(Version::V4_1, b).unwrap();
+        let bolt_list: BoltList = BoltList::parse(Version::V4_1, &mut b).unwrap();
 
         assert_eq!(bolt_list.len(), 2);
         match bolt_list.get(0).unwrap() {
@@ -186,4 +219,85 @@ mod tests {
             _ => unreachable!("error deserialiisation integer in list"),
         }
     }
+
+    #[test]
+    fn should_deserialize_empty_list() {
+        let mut b = Bytes::from_static(&[TINY]);
+
+        let bolt_list: BoltList = BoltList::parse(Version::V4_1, &mut b).unwrap();
+
+        assert!(bolt_list.is_empty());
+    }
+
+    #[test]
+    fn should_deserialize_nested_list() {
+        let mut b = Bytes::from_static(&[0x92, 0x92, 0x01, 0x02, 0x91, 0x81, 0x61]);
+
+        let bolt_list: BoltList = BoltList::parse(Version::V4_1, &mut b).unwrap();
+
+        assert_eq!(
+            bolt_list,
+            vec![
+                BoltType::List(vec![1.into(), 2.into()].into()),
+                BoltType::List(vec!["a".into()].into()),
+            ]
+            .into()
+        );
+    }
+
+    #[test]
+    fn should_deserialize_mixed_list_leaving_trailing_bytes() {
+        let mut b = Bytes::from_static(&[
+            0x93, 0x81, 0x61, 0x01, 0xC1, 0x3F, 0xF1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9A, 0x2A,
+        ]);
+
+        let bolt_list: BoltList = BoltList::parse(Version::V4_1, &mut b).unwrap();
+
+        assert_eq!(
+            bolt_list,
+            vec!["a".into(), 1.into(), BoltType::Float(BoltFloat::new(1.1))].into()
+        );
+        assert_eq!(&b[..], &[0x2A]);
+    }
+
+    #[test]
+    fn should_deserialize_list_of_nodes() {
+        let mut b = Bytes::from_static(&[
+            0x92, 0xB3, 0x4E, 0x01, 0x91, 0x86, b'P', b'e', b'r', b's', b'o', b'n', 0xA1, 0x84,
+            b'n', b'a', b'm', b'e', 0x83, b'A', b'n', b'n', 0xB3, 0x4E, 0x02, 0x90, 0xA1, 0x83,
+            b'a', b'g', b'e', 0x2A, 0x2A,
+        ]);
+
+        let bolt_list: BoltList = BoltList::parse(Version::V4_1, &mut b).unwrap();
+
+        let nodes: Vec<&BoltNode> = bolt_list
+            .iter()
+            .map(|value| match value {
+                BoltType::Node(node) => node,
+                _ => unreachable!("error deserialisation of node in list"),
+            })
+            .collect();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].id(), 1);
+        assert_eq!(nodes[0].labels(), vec!["Person"]);
+        assert_eq!(nodes[0].get::<String>("name"), Some("Ann".to_owned()));
+        assert_eq!(nodes[1].id(), 2);
+        assert!(nodes[1].labels().is_empty());
+        assert_eq!(nodes[1].get::<i64>("age"), Some(42));
+        assert_eq!(&b[..], &[0x2A]);
+    }
+
+    #[test]
+    fn should_serialize_small_list() {
+        let mut list = BoltList::new();
+        for i in 0..16 {
+            list.push(i.into());
+        }
+
+        let mut b: Bytes = list.clone().into_bytes(Version::V4_1).unwrap();
+
+        assert_eq!(&b[..2], &[SMALL, 0x10]);
+        let deserialized = BoltList::parse(Version::V4_1, &mut b).unwrap();
+        assert_eq!(deserialized, list);
+    }
 }
@@ -1,29 +1,47 @@
 use crate::errors::*;
+use crate::types::marker::{peek_kind, BoltKind};
 use crate::types::*;
 use crate::version::Version;
 use bytes::*;
-use std::cell::RefCell;
+use indexmap::IndexMap;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
+use std::hash::{Hash, Hasher};
 use std::iter::FromIterator;
-
 use std::mem;
-use std::rc::Rc;
 
 pub const TINY: u8 = 0xA0;
 pub const SMALL: u8 = 0xD8;
 pub const MEDIUM: u8 = 0xD9;
 pub const LARGE: u8 = 0xDA;
 
-#[derive(Debug, PartialEq, Clone)]
+/// A packstream dictionary, entries are kept in the order they were inserted (or received from
+/// the server) so that serialization is deterministic.
+///
+/// Maps compare equal regardless of the order of their entries, so they are hashed independent
+/// of the order as well.
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct BoltMap {
-    pub value: HashMap<BoltString, BoltType>,
+    pub value: IndexMap<BoltString, BoltType>,
+}
+
+impl Hash for BoltMap {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let entries = self.value.iter().fold(0u64, |sum, entry| {
+            let mut hasher = DefaultHasher::new();
+            entry.hash(&mut hasher);
+            sum.wrapping_add(hasher.finish())
+        });
+        self.value.len().hash(state);
+        entries.hash(state);
+    }
 }
 
 impl Default for BoltMap {
     fn default() -> Self {
         BoltMap {
-            value: HashMap::new(),
+            value: IndexMap::new(),
         }
     }
 }
@@ -31,7 +49,7 @@ impl Default for BoltMap {
 impl BoltMap {
     pub fn with_capacity(capacity: usize) -> Self {
         BoltMap {
-            value: HashMap::with_capacity(capacity),
+            value: IndexMap::with_capacity(capacity),
         }
     }
 
@@ -39,6 +57,10 @@ impl BoltMap {
         self.value.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
     pub fn put(&mut self, key: BoltString, value: BoltType) {
         self.value.insert(key, value);
     }
@@ -56,12 +78,31 @@ impl BoltMap {
         }
     }
 
-    pub fn can_parse(_: Version, input: Rc<RefCell<Bytes>>) -> bool {
-        let marker = input.borrow()[0];
-        (TINY..=(TINY | 0x0F)).contains(&marker)
-            || marker == SMALL
-            || marker == MEDIUM
-            || marker == LARGE
+    /// Converts every value into `T`, failing with the error of the first value which does not
+    /// convert
+    pub fn try_into_map<T: TryFrom<BoltType, Error = Error>>(self) -> Result<HashMap<String, T>> {
+        self.into_iter()
+            .map(|(key, value)| Ok((key, T::try_from(value)?)))
+            .collect()
+    }
+
+    pub fn can_parse(_: Version, input: &Bytes) -> bool {
+        peek_kind(input) == Some(BoltKind::Map)
+    }
+}
+
+/// Iterates over the entries in their order, with the keys as plain strings
+impl IntoIterator for BoltMap {
+    type Item = (String, BoltType);
+    type IntoIter = std::iter::Map<
+        indexmap::map::IntoIter<BoltString, BoltType>,
+        fn((BoltString, BoltType)) -> (String, BoltType),
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.value
+            .into_iter()
+            .map(|(key, value)| (key.value, value))
     }
 }
 
@@ -80,59 +121,26 @@ impl FromIterator<(BoltString, BoltType)> for BoltMap {
 
 impl BoltMap {
     pub fn into_bytes(self, version: Version) -> Result<Bytes> {
-        let mut key_value_bytes = BytesMut::new();
-        let length = self.value.len();
-        for (key, value) in self.value {
-            let key_bytes: Bytes = key.into_bytes(version)?;
-            let value_bytes: Bytes = value.into_bytes(version)?;
-            key_value_bytes.put(key_bytes);
-            key_value_bytes.put(value_bytes);
-        }
-
-        let mut bytes = BytesMut::with_capacity(
-            mem::size_of::<u8>() + mem::size_of::<u32>() + key_value_bytes.len(),
-        );
-
-        match length {
-            0..=15 => bytes.put_u8(TINY | length as u8),
-            16..=255 => {
-                bytes.put_u8(SMALL);
-                bytes.put_u8(length as u8);
-            }
-            256..=65_535 => {
-                bytes.put_u8(MEDIUM);
-                bytes.put_u16(length as u16);
-            }
-            65_536..=4_294_967_295 => {
-                bytes.put_u8(LARGE);
-                bytes.put_u32(length as u32);
-            }
-            _ => return Err(Error::MapTooBig),
-        }
-
-        bytes.put(key_value_bytes);
-        Ok(bytes.freeze())
+        self.to_bytes(version)
     }
 
-    pub fn parse(version: Version, input: Rc<RefCell<Bytes>>) -> Result<BoltMap> {
-        let marker = input.borrow_mut().get_u8();
+    pub fn parse(version: Version, input: &mut Bytes) -> Result<BoltMap> {
+        let marker = read_exact(input, mem::size_of::<u8>())?.get_u8();
         let size = match marker {
             0xA0..=0xAF => 0x0F & marker as usize,
-            SMALL => input.borrow_mut().get_u8() as usize,
-            MEDIUM => input.borrow_mut().get_u16() as usize,
-            LARGE => input.borrow_mut().get_u32() as usize,
-            _ => {
-                return Err(Error::InvalidTypeMarker(format!(
-                    "invalid map marker {}",
-                    marker
-                )))
-            }
+            SMALL => read_exact(input, mem::size_of::<u8>())?.get_u8() as usize,
+            MEDIUM => read_exact(input, mem::size_of::<u16>())?.get_u16() as usize,
+            LARGE => read_exact(input, mem::size_of::<u32>())?.get_u32() as usize,
+            _ => return Err(unexpected_marker(BoltKind::Map, marker)),
         };
 
         let mut map = BoltMap::default();
-        for _ in 0..size {
-            let key: BoltString = BoltString::parse(version, input.clone())?;
-            let value: BoltType = BoltType::parse(version, input.clone())?;
+        for index in 0..size {
+            if peek_kind(input) != Some(BoltKind::String) {
+                return Err(skip_invalid_key(version, input, index, size));
+            }
+            let key: BoltString = BoltString::parse(version, input)?;
+            let value: BoltType = BoltType::parse(version, input)?;
             map.put(key, value);
         }
 
@@ -140,6 +148,65 @@ impl BoltMap {
     }
 }
 
+/// Skips the remaining entries of a map whose key at `index` is not a string, so the input
+/// continues after the map and the values following it can still be decoded.
+fn skip_invalid_key(version: Version, input: &mut Bytes, index: usize, size: usize) -> Error {
+    let found = peek_marker(input).and_then(marker_kind).map_or_else(
+        || "a reserved marker".to_owned(),
+        |kind| format!("{:?}", kind),
+    );
+    for _ in index..size {
+        if let Err(e) = BoltType::parse(version, input).and(BoltType::parse(version, input)) {
+            return e;
+        }
+    }
+    Error::DeserializationError(format!(
+        "map key {} is {} instead of a string",
+        index, found
+    ))
+}
+
+impl BoltEncode for BoltMap {
+    fn encode(&self, encoding: Encoding, dst: &mut BytesMut) -> Result<()> {
+        encode_header(self.value.len(), dst)?;
+        for (key, value) in &self.value {
+            key.encode(encoding, dst)?;
+            value.encode(encoding, dst)?;
+        }
+        Ok(())
+    }
+
+    fn encoded_len(&self, encoding: Encoding) -> usize {
+        let entries: usize = self
+            .value
+            .iter()
+            .map(|(key, value)| key.encoded_len(encoding) + value.encoded_len(encoding))
+            .sum();
+        header_len(self.value.len()) + entries
+    }
+}
+
+/// Appends the marker and size of a map with `length` entries
+pub(crate) fn encode_header(length: usize, dst: &mut BytesMut) -> Result<()> {
+    match length {
+        0..=15 => dst.put_u8(TINY | length as u8),
+        16..=255 => {
+            dst.put_u8(SMALL);
+            dst.put_u8(length as u8);
+        }
+        256..=65_535 => {
+            dst.put_u8(MEDIUM);
+            dst.put_u16(length as u16);
+        }
+        65_536..=4_294_967_295 => {
+            dst.put_u8(LARGE);
+            dst.put_u32(length as u32);
+        }
+        _ => return Err(Error::MapTooBig),
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -165,11 +232,9 @@ mod tests {
 
     #[test]
     fn should_deserialize_map_of_strings() {
-        let input = Rc::new(RefCell::new(Bytes::from_static(&[
-            0xA1, 0x81, 0x61, 0x81, 0x62,
-        ])));
+        let mut input = Bytes::from_static(&[0xA1, 0x81, 0x61, 0x81, 0x62]);
 
-        let map: BoltMap = BoltMap::parse(Version::V4_1, input).unwrap();
+        let map: BoltMap = BoltMap::parse(Version::V4_1, &mut input).unwrap();
 
         assert_eq!(map.value.len(), 1);
     }
@@ -181,10 +246,9 @@ mod tests {
             map.put(i.to_string().into(), i.to_string().into());
         }
 
-        let bytes: Rc<RefCell<Bytes>> =
-            Rc::new(RefCell::new(map.clone().into_bytes(Version::V4_1).unwrap()));
-        assert_eq!(bytes.borrow()[0], SMALL);
-        let deserialized_map: BoltMap = BoltMap::parse(Version::V4_1, bytes).unwrap();
+        let mut bytes = map.clone().into_bytes(Version::V4_1).unwrap();
+        assert_eq!(bytes[0], SMALL);
+        let deserialized_map: BoltMap = BoltMap::parse(Version::V4_1, &mut bytes).unwrap();
         assert_eq!(map, deserialized_map);
     }
 
@@ -195,10 +259,9 @@ mod tests {
             map.put(i.to_string().into(), i.to_string().into());
         }
 
-        let bytes: Rc<RefCell<Bytes>> =
-            Rc::new(RefCell::new(map.clone().into_bytes(Version::V4_1).unwrap()));
-        assert_eq!(bytes.borrow()[0], MEDIUM);
-        let deserialized_map: BoltMap = BoltMap::parse(Version::V4_1, bytes).unwrap();
+        let mut bytes = map.clone().into_bytes(Version::V4_1).unwrap();
+        assert_eq!(bytes[0], MEDIUM);
+        let deserialized_map: BoltMap = BoltMap::parse(Version::V4_1, &mut bytes).unwrap();
         assert_eq!(map, deserialized_map);
     }
 
@@ -209,10 +272,74 @@ mod tests {
             map.put(i.to_string().into(), i.to_string().into());
         }
 
-        let bytes: Rc<RefCell<Bytes>> =
-            Rc::new(RefCell::new(map.clone().into_bytes(Version::V4_1).unwrap()));
-        assert_eq!(bytes.borrow()[0], LARGE);
-        let deserialized_map: BoltMap = BoltMap::parse(Version::V4_1, bytes).unwrap();
+        let mut bytes = map.clone().into_bytes(Version::V4_1).unwrap();
+        assert_eq!(bytes[0], LARGE);
+        let deserialized_map: BoltMap = BoltMap::parse(Version::V4_1, &mut bytes).unwrap();
         assert_eq!(map, deserialized_map);
     }
+
+    #[test]
+    fn should_preserve_insertion_order() {
+        let mut map = BoltMap::default();
+        for i in (0..20).rev() {
+            map.put(format!("key{}", i).into(), i.into());
+        }
+
+        let mut bytes: Bytes = map.clone().into_bytes(Version::V4_1).unwrap();
+        assert_eq!(&bytes[..2], &[SMALL, 20]);
+        assert_eq!(&bytes[2..8], &[0x85, b'k', b'e', b'y', b'1', b'9']);
+
+        let deserialized_map: BoltMap = BoltMap::parse(Version::V4_1, &mut bytes).unwrap();
+        let keys: Vec<&str> = deserialized_map
+            .value
+            .keys()
+            .map(|k| k.value.as_str())
+            .collect();
+        let expected: Vec<String> = (0..20).rev().map(|i| format!("key{}", i)).collect();
+        assert_eq!(keys, expected);
+        assert_eq!(deserialized_map.get::<i64>("key7"), Some(7));
+    }
+
+    #[test]
+    fn should_convert_into_hash_map() {
+        let names: BoltMap = vec![
+            ("first".into(), "Mark".into()),
+            ("last".into(), "Twain".into()),
+        ]
+        .into_iter()
+        .collect();
+        let entries: Vec<(String, BoltType)> = names.clone().into_iter().collect();
+        assert_eq!(entries[0], ("first".to_owned(), "Mark".into()));
+
+        let names = names.try_into_map::<String>().unwrap();
+        assert_eq!(names.len(), 2);
+        assert_eq!(names["last"], "Twain");
+
+        let mixed: BoltMap = vec![("name".into(), "Mark".into()), ("age".into(), 74.into())]
+            .into_iter()
+            .collect();
+        assert!(matches!(
+            mixed.try_into_map::<String>(),
+            Err(Error::UnexpectedType {
+                expected: BoltKind::String,
+                found: BoltKind::Integer
+            })
+        ));
+    }
+
+    #[test]
+    fn should_skip_map_with_integer_key() {
+        let mut input = Bytes::from_static(&[0xA2, 0x81, b'a', 0x01, 0x2A, 0x81, b'b', 0x2B]);
+
+        match BoltMap::parse(Version::V4_1, &mut input) {
+            Err(Error::DeserializationError(detail)) => {
+                assert_eq!(detail, "map key 1 is Integer instead of a string")
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert_eq!(
+            BoltType::parse(Version::V4_1, &mut input).unwrap(),
+            43.into()
+        );
+    }
 }
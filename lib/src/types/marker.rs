@@ -0,0 +1,76 @@
+/// The category of a packstream value, as determined by its marker byte.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum BoltKind {
+    Null,
+    Boolean,
+    Integer,
+    Float,
+    Bytes,
+    String,
+    List,
+    Map,
+    Structure,
+}
+
+/// Returns the marker byte of the next value without consuming it, or `None` if the input is
+/// empty.
+pub fn peek_marker(input: &[u8]) -> Option<u8> {
+    input.first().copied()
+}
+
+/// Classifies a marker byte into the kind of value it introduces.
+///
+/// Returns `None` for the markers packstream reserves.
+pub fn marker_kind(marker: u8) -> Option<BoltKind> {
+    match marker {
+        0x00..=0x7F | 0xF0..=0xFF | 0xC8..=0xCB => Some(BoltKind::Integer),
+        0x80..=0x8F | 0xD0..=0xD2 => Some(BoltKind::String),
+        0x90..=0x9F | 0xD4..=0xD6 => Some(BoltKind::List),
+        0xA0..=0xAF | 0xD8..=0xDA => Some(BoltKind::Map),
+        0xB0..=0xBF => Some(BoltKind::Structure),
+        0xC0 => Some(BoltKind::Null),
+        0xC1 => Some(BoltKind::Float),
+        0xC2 | 0xC3 => Some(BoltKind::Boolean),
+        0xCC..=0xCE => Some(BoltKind::Bytes),
+        _ => None,
+    }
+}
+
+/// Convenience for classifying the next value of the input, `None` if the input is empty or
+/// starts with a reserved marker.
+pub(crate) fn peek_kind(input: &[u8]) -> Option<BoltKind> {
+    peek_marker(input).and_then(marker_kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_peek_marker() {
+        assert_eq!(peek_marker(&[0xC0, 0x01]), Some(0xC0));
+        assert_eq!(peek_marker(&[]), None);
+    }
+
+    #[test]
+    fn should_classify_markers() {
+        assert_eq!(marker_kind(0x00), Some(BoltKind::Integer));
+        assert_eq!(marker_kind(0x7F), Some(BoltKind::Integer));
+        assert_eq!(marker_kind(0xF0), Some(BoltKind::Integer));
+        assert_eq!(marker_kind(0xCB), Some(BoltKind::Integer));
+        assert_eq!(marker_kind(0x85), Some(BoltKind::String));
+        assert_eq!(marker_kind(0xD2), Some(BoltKind::String));
+        assert_eq!(marker_kind(0x9F), Some(BoltKind::List));
+        assert_eq!(marker_kind(0xD4), Some(BoltKind::List));
+        assert_eq!(marker_kind(0xA3), Some(BoltKind::Map));
+        assert_eq!(marker_kind(0xDA), Some(BoltKind::Map));
+        assert_eq!(marker_kind(0xB3), Some(BoltKind::Structure));
+        assert_eq!(marker_kind(0xC0), Some(BoltKind::Null));
+        assert_eq!(marker_kind(0xC1), Some(BoltKind::Float));
+        assert_eq!(marker_kind(0xC2), Some(BoltKind::Boolean));
+        assert_eq!(marker_kind(0xCD), Some(BoltKind::Bytes));
+        for reserved in [0xC4, 0xC7, 0xCF, 0xD3, 0xD7, 0xDB, 0xE0, 0xEF].iter() {
+            assert_eq!(marker_kind(*reserved), None);
+        }
+    }
+}
@@ -1,12 +1,22 @@
+use crate::errors::*;
 use crate::types::*;
-use neo4rs_macros::BoltStruct;
+use crate::version::Version;
+use bytes::*;
 
-#[derive(Debug, PartialEq, Clone, BoltStruct)]
-#[signature(0xB3, 0x4E)]
+pub const SIGNATURE: u8 = Signature::Node as u8;
+
+/// A node, since bolt 5.0 it carries a string `element_id` next to the legacy integer id.
+///
+/// Nodes are equal if they are the same node in the database, regardless of their labels and
+/// properties: they are compared by their element ids if both have one and by their ids
+/// otherwise. Ids are only unique within a database, nodes of different databases can compare
+/// equal, and the server may reuse the id of a deleted node.
+#[derive(Debug, Clone)]
 pub struct BoltNode {
     pub id: BoltInteger,
     pub labels: BoltList,
     pub properties: BoltMap,
+    pub element_id: Option<BoltString>,
 }
 
 impl BoltNode {
@@ -15,14 +25,170 @@ impl BoltNode {
             id,
             labels,
             properties,
+            element_id: None,
+        }
+    }
+
+    fn fields(version: Version) -> u8 {
+        if version.has_element_ids() {
+            4
+        } else {
+            3
         }
     }
+
+    pub fn can_parse(version: Version, input: &Bytes) -> bool {
+        input.len() >= 2
+            && input[0] == structure::TINY | Self::fields(version)
+            && input[1] == SIGNATURE
+    }
+
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        self.to_bytes(version)
+    }
+
+    pub fn parse(version: Version, input: &mut Bytes) -> Result<BoltNode> {
+        signature::read_signature(input, Signature::Node)?;
+        let id = BoltInteger::parse(version, input)?;
+        let labels = BoltList::parse(version, input)?;
+        let properties = BoltMap::parse(version, input)?;
+        let element_id = if version.has_element_ids() {
+            Some(BoltString::parse(version, input)?)
+        } else {
+            None
+        };
+        Ok(BoltNode {
+            id,
+            labels,
+            properties,
+            element_id,
+        })
+    }
+
+    /// Parses a node but keeps its properties encoded, for wide nodes where only a few
+    /// properties are read
+    pub fn parse_lazy(version: Version, input: &mut Bytes) -> Result<LazyNode> {
+        signature::read_signature(input, Signature::Node)?;
+        let id = BoltInteger::parse(version, input)?;
+        let labels = BoltList::parse(version, input)?;
+        let properties = LazyMap::parse(version, input)?;
+        let element_id = if version.has_element_ids() {
+            Some(BoltString::parse(version, input)?)
+        } else {
+            None
+        };
+        Ok(LazyNode {
+            id,
+            labels,
+            properties,
+            element_id,
+        })
+    }
+}
+
+/// A node parsed with [`BoltNode::parse_lazy`], its properties are decoded on access
+#[derive(Debug, Clone, PartialEq)]
+pub struct LazyNode {
+    pub id: BoltInteger,
+    pub labels: BoltList,
+    pub properties: LazyMap,
+    pub element_id: Option<BoltString>,
+}
+
+impl LazyNode {
+    /// Decodes the property `key` and converts it, see [`LazyMap::get`]
+    pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
+        self.properties.get(key)
+    }
+
+    /// Decodes all properties into a [`BoltNode`]
+    pub fn decode(&self) -> Result<BoltNode> {
+        Ok(BoltNode {
+            id: self.id.clone(),
+            labels: self.labels.clone(),
+            properties: self.properties.decode()?,
+            element_id: self.element_id.clone(),
+        })
+    }
+}
+
+impl PartialEq for BoltNode {
+    fn eq(&self, other: &BoltNode) -> bool {
+        match (&self.element_id, &other.element_id) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.id == other.id,
+        }
+    }
+}
+
+impl BoltEncode for BoltNode {
+    fn encode(&self, encoding: Encoding, dst: &mut BytesMut) -> Result<()> {
+        let version = encoding.version;
+        dst.put_u8(structure::TINY | Self::fields(version));
+        dst.put_u8(SIGNATURE);
+        self.id.encode(encoding, dst)?;
+        self.labels.encode(encoding, dst)?;
+        self.properties.encode(encoding, dst)?;
+        if version.has_element_ids() {
+            match &self.element_id {
+                Some(element_id) => element_id.encode(encoding, dst)?,
+                None => BoltString::from(self.id.value.to_string()).encode(encoding, dst)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn encoded_len(&self, encoding: Encoding) -> usize {
+        let version = encoding.version;
+        let mut len = 2
+            + self.id.encoded_len(encoding)
+            + self.labels.encoded_len(encoding)
+            + self.properties.encoded_len(encoding);
+        if version.has_element_ids() {
+            len += match &self.element_id {
+                Some(element_id) => element_id.encoded_len(encoding),
+                None => BoltString::from(self.id.value.to_string()).encoded_len(encoding),
+            };
+        }
+        len
+    }
 }
 
 impl BoltNode {
     pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
         self.properties.get(key)
     }
+
+    /// The legacy integer id, which the server may reuse once the node is deleted
+    pub fn id(&self) -> i64 {
+        self.id.value
+    }
+
+    /// The element id, only sent by servers speaking bolt 5.0 or later
+    pub fn element_id(&self) -> Option<&str> {
+        self.element_id.as_ref().map(|id| id.value.as_str())
+    }
+
+    /// The labels of the node, labels which are not strings are skipped
+    pub fn labels(&self) -> Vec<&str> {
+        self.labels
+            .value
+            .iter()
+            .filter_map(|label| match label {
+                BoltType::String(label) => Some(label.value.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn properties(&self) -> &BoltMap {
+        &self.properties
+    }
+
+    /// Takes the properties out of the node without cloning them
+    pub fn into_properties(self) -> BoltMap {
+        self.properties
+    }
 }
 
 impl Into<BoltType> for BoltNode {
@@ -34,19 +200,15 @@ impl Into<BoltType> for BoltNode {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::version::Version;
-    use bytes::*;
-    use std::cell::RefCell;
-    use std::rc::Rc;
 
     #[test]
     fn should_deserialize_a_node() {
-        let input = Rc::new(RefCell::new(Bytes::from_static(&[
+        let mut input = Bytes::from_static(&[
             0xB3, 0x4E, 0x13, 0x91, 0x86, 0x50, 0x65, 0x72, 0x73, 0x6F, 0x6E, 0xA1, 0x84, 0x6E,
             0x61, 0x6D, 0x65, 0x84, 0x4D, 0x61, 0x72, 0x6B,
-        ])));
+        ]);
 
-        let node: BoltNode = BoltNode::parse(Version::V4_1, input).unwrap();
+        let node: BoltNode = BoltNode::parse(Version::V4_1, &mut input).unwrap();
 
         assert_eq!(node.id, BoltInteger::new(19));
         assert_eq!(node.labels, vec!["Person".into()].into());
@@ -56,16 +218,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_move_properties_out_of_a_node() {
+        let mut input = Bytes::from_static(&[
+            0xB4, 0x4E, 0x13, 0x91, 0x86, 0x50, 0x65, 0x72, 0x73, 0x6F, 0x6E, 0xA1, 0x84, 0x6E,
+            0x61, 0x6D, 0x65, 0x84, 0x4D, 0x61, 0x72, 0x6B, 0x84, b'4', b':', b'a', b'b',
+        ]);
+        let node = BoltNode::parse(Version::V5_0, &mut input).unwrap();
+
+        assert_eq!(node.id(), 19);
+        assert_eq!(node.element_id(), Some("4:ab"));
+        assert_eq!(node.labels(), vec!["Person"]);
+        let properties = node.into_properties();
+        assert_eq!(properties.get::<String>("name"), Some("Mark".to_owned()));
+    }
+
+    #[test]
+    fn should_read_one_property_of_a_wide_node_lazily() {
+        let properties: BoltMap = (0..50)
+            .map(|i| {
+                (
+                    format!("p{}", i).into(),
+                    format!("value {}", i).repeat(20).into(),
+                )
+            })
+            .collect();
+        let mut node = BoltNode::new(19.into(), vec!["Person".into()].into(), properties);
+        node.element_id = Some("4:ab".into());
+        let mut input = node.clone().into_bytes(Version::V5_0).unwrap();
+
+        let lazy = BoltNode::parse_lazy(Version::V5_0, &mut input).unwrap();
+
+        assert!(input.is_empty());
+        assert_eq!(lazy.element_id, node.element_id);
+        assert_eq!(lazy.properties.len(), 50);
+        assert_eq!(lazy.get::<String>("p42"), node.get::<String>("p42"));
+        assert_eq!(lazy.get::<String>("missing"), None);
+        let decoded = lazy.decode().unwrap();
+        assert_eq!(decoded.labels, node.labels);
+        assert_eq!(decoded.properties, node.properties);
+    }
+
     #[test]
     fn should_serialize_a_node() {
         let id = BoltInteger::new(19);
         let labels = vec!["Person".into()].into();
         let properties = vec![("name".into(), "Mark".into())].into_iter().collect();
-        let node = BoltNode {
-            id,
-            labels,
-            properties,
-        };
+        let node = BoltNode::new(id, labels, properties);
 
         let bytes: Bytes = node.into_bytes(Version::V4_1).unwrap();
 
@@ -77,4 +276,61 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn should_deserialize_a_v5_node() {
+        let mut input = Bytes::from_static(&[
+            0xB4, 0x4E, 0x13, 0x91, 0x86, 0x50, 0x65, 0x72, 0x73, 0x6F, 0x6E, 0xA0, 0x84, b'4',
+            b':', b'a', b'b',
+        ]);
+
+        assert!(!BoltNode::can_parse(Version::V4_4, &input));
+        assert!(BoltNode::can_parse(Version::V5_0, &input));
+        let node = BoltNode::parse(Version::V5_0, &mut input).unwrap();
+
+        assert_eq!(node.id, BoltInteger::new(19));
+        assert_eq!(node.element_id, Some("4:ab".into()));
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn should_round_trip_a_v5_node() {
+        let mut node = BoltNode::new(19.into(), vec!["Person".into()].into(), BoltMap::default());
+        node.element_id = Some("4:ab".into());
+
+        let mut bytes = node.clone().into_bytes(Version::V5_0).unwrap();
+        assert_eq!(bytes[0], 0xB4);
+        let parsed = BoltNode::parse(Version::V5_0, &mut bytes).unwrap();
+
+        assert_eq!(parsed, node);
+        assert_eq!(parsed.labels, node.labels);
+        assert_eq!(parsed.properties, node.properties);
+    }
+
+    #[test]
+    fn should_compare_nodes_by_element_id() {
+        let bytes = Bytes::from_static(&[
+            0xB4, 0x4E, 0x13, 0x91, 0x86, 0x50, 0x65, 0x72, 0x73, 0x6F, 0x6E, 0xA0, 0x84, b'4',
+            b':', b'a', b'b',
+        ]);
+        let first = BoltNode::parse(Version::V5_0, &mut bytes.clone()).unwrap();
+        let mut second = BoltNode::parse(Version::V5_0, &mut bytes.clone()).unwrap();
+        second.properties.put("name".into(), "Mark".into());
+        assert_eq!(first, second);
+
+        let mut other = first.clone();
+        other.element_id = Some("4:cd".into());
+        assert_ne!(first, other);
+        other.id = 20.into();
+        other.element_id = None;
+        assert_ne!(first, other);
+        other.id = 19.into();
+        assert_eq!(first, other);
+
+        let unique: std::collections::HashSet<BoltType> =
+            vec![first.into(), second.into(), other.into()]
+                .into_iter()
+                .collect();
+        assert_eq!(unique.len(), 1);
+    }
 }
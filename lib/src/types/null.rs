@@ -1,16 +1,10 @@
 use bytes::*;
 use neo4rs_macros::BoltStruct;
 
-#[derive(Debug, PartialEq, Eq, Clone, BoltStruct)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default, BoltStruct)]
 #[signature(0xC0)]
 pub struct BoltNull;
 
-impl Default for BoltNull {
-    fn default() -> Self {
-        BoltNull
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -18,8 +12,23 @@ mod tests {
 
     #[test]
     fn should_serialize_null() {
-        let null = BoltNull::default();
+        let null = BoltNull;
         let b: Bytes = null.into_bytes(Version::V4_1).unwrap();
         assert_eq!(&b[..], &[0xC0]);
     }
+
+    #[test]
+    fn should_deserialize_null() {
+        let mut input = Bytes::from_static(&[0xC0]);
+        assert!(BoltNull::can_parse(Version::V4_1, &input));
+        let null: BoltNull = BoltNull::parse(Version::V4_1, &mut input).unwrap();
+        assert_eq!(null, BoltNull);
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn should_not_parse_other_markers_as_null() {
+        let input = Bytes::from_static(&[0xC2]);
+        assert!(!BoltNull::can_parse(Version::V4_1, &input));
+    }
 }
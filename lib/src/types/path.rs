@@ -1,5 +1,7 @@
+use crate::errors::*;
 use crate::types::*;
 use neo4rs_macros::BoltStruct;
+use std::convert::TryFrom;
 
 #[derive(Debug, PartialEq, Clone, BoltStruct)]
 #[signature(0xB3, 0x50)]
@@ -39,6 +41,48 @@ impl BoltPath {
         }
         ids
     }
+
+    /// The walk along the path as `(start, relationship, end)` steps, the relationships are bound
+    /// to the nodes they connect in their own direction.
+    ///
+    /// `ids` holds a pair for every step, the index of the relationship into `rels` starting at 1
+    /// and the index of the next node into `nodes`. The relationship index is negative if the
+    /// relationship is traversed against its direction. The walk starts at the first node.
+    pub fn segments(&self) -> Result<Vec<(BoltNode, BoltRelation, BoltNode)>> {
+        let nodes = self.nodes();
+        let rels = self.rels();
+        let ids = self.ids();
+        let steps = ids.chunks_exact(2);
+        if !steps.remainder().is_empty() {
+            return Err(invalid_path("odd number of indices"));
+        }
+        let mut current = nodes.first().ok_or_else(|| invalid_path("no start node"))?;
+        let mut segments = Vec::with_capacity(steps.len());
+        for step in steps {
+            let rel_index = step[0].value;
+            let rel = usize::try_from(rel_index.unsigned_abs())
+                .ok()
+                .and_then(|index| index.checked_sub(1))
+                .and_then(|index| rels.get(index))
+                .ok_or_else(|| invalid_path("relationship index out of bounds"))?;
+            let next = usize::try_from(step[1].value)
+                .ok()
+                .and_then(|index| nodes.get(index))
+                .ok_or_else(|| invalid_path("node index out of bounds"))?;
+            let relation = if rel_index > 0 {
+                rel.bind(current, next)
+            } else {
+                rel.bind(next, current)
+            };
+            segments.push((current.clone(), relation, next.clone()));
+            current = next;
+        }
+        Ok(segments)
+    }
+}
+
+fn invalid_path(reason: &str) -> Error {
+    Error::DeserializationError(format!("invalid path: {}", reason))
 }
 
 #[cfg(test)]
@@ -46,8 +90,6 @@ mod tests {
     use super::*;
     use crate::version::Version;
     use bytes::*;
-    use std::cell::RefCell;
-    use std::rc::Rc;
 
     #[test]
     fn should_serialize_a_path() {
@@ -90,16 +132,16 @@ mod tests {
 
     #[test]
     fn should_deserialize_a_path() {
-        let input = Rc::new(RefCell::new(Bytes::from_static(&[
+        let mut input = Bytes::from_static(&[
             0xB3, 0x50, 0x92, 0xB3, 0x4E, 0x2A, 0x91, 0x86, 0x50, 0x65, 0x72, 0x73, 0x6F, 0x6E,
             0xA1, 0x84, 0x6E, 0x61, 0x6D, 0x65, 0x84, 0x4D, 0x61, 0x72, 0x6B, 0xB3, 0x4E, 0x2B,
             0x91, 0x86, 0x50, 0x65, 0x72, 0x73, 0x6F, 0x6E, 0xA1, 0x84, 0x6E, 0x61, 0x6D, 0x65,
             0x85, 0x4A, 0x61, 0x6D, 0x65, 0x73, 0x91, 0xB3, 0x72, 0x16, 0x86, 0x66, 0x72, 0x69,
             0x65, 0x6E, 0x64, 0xA1, 0x83, 0x6B, 0x65, 0x79, 0x85, 0x76, 0x61, 0x6C, 0x75, 0x65,
             0x92, 0x16, 0x2A,
-        ])));
+        ]);
 
-        let path: BoltPath = BoltPath::parse(Version::V4_1, input).unwrap();
+        let path: BoltPath = BoltPath::parse(Version::V4_1, &mut input).unwrap();
 
         let nodes = path.nodes();
         let rels = path.rels();
@@ -132,4 +174,106 @@ mod tests {
         );
         assert_eq!(ids, vec![22.into(), 42.into()]);
     }
+
+    #[test]
+    fn should_deserialize_a_v5_path() {
+        let mut input = Bytes::from_static(&[
+            0xB3, 0x50, 0x92, 0xB4, 0x4E, 0x01, 0x90, 0xA0, 0x82, b'n', b'1', 0xB4, 0x4E, 0x02,
+            0x90, 0xA0, 0x82, b'n', b'2', 0x91, 0xB4, 0x72, 0x0A, 0x83, 0x72, 0x65, 0x6C, 0xA0,
+            0x82, b'r', b'1', 0x92, 0xFF, 0x01,
+        ]);
+
+        let path = BoltPath::parse(Version::V5_0, &mut input).unwrap();
+
+        assert!(input.is_empty());
+        assert_eq!(path.rels()[0].element_id, Some("r1".into()));
+        let segments = path.segments().unwrap();
+        let (start, rel, end) = &segments[0];
+        assert_eq!(start.element_id, Some("n1".into()));
+        assert_eq!(end.element_id, Some("n2".into()));
+        assert_eq!(rel.element_id, Some("r1".into()));
+        assert_eq!(rel.start_node_element_id, Some("n2".into()));
+        assert_eq!(rel.end_node_element_id, Some("n1".into()));
+    }
+
+    #[test]
+    fn should_walk_a_path_with_a_reversed_relationship() {
+        let node = |id: i64| BoltNode::new(id.into(), BoltList::new(), BoltMap::default());
+        let rel =
+            |id: i64| BoltUnboundedRelation::new(id.into(), "KNOWS".into(), BoltMap::default());
+        // (1)-[10]->(2)<-[11]-(3)-[12]->(4)
+        let path = BoltPath {
+            nodes: vec![
+                node(1).into(),
+                node(2).into(),
+                node(3).into(),
+                node(4).into(),
+            ]
+            .into(),
+            rels: vec![rel(10).into(), rel(11).into(), rel(12).into()].into(),
+            ids: vec![
+                1_i64.into(),
+                1_i64.into(),
+                (-2_i64).into(),
+                2_i64.into(),
+                3_i64.into(),
+                3_i64.into(),
+            ]
+            .into(),
+        };
+
+        let segments = path.segments().unwrap();
+
+        let steps: Vec<(i64, i64, i64, i64, i64)> = segments
+            .iter()
+            .map(|(from, rel, to)| {
+                (
+                    from.id.value,
+                    rel.id.value,
+                    rel.start_node_id.value,
+                    rel.end_node_id.value,
+                    to.id.value,
+                )
+            })
+            .collect();
+        assert_eq!(
+            steps,
+            vec![(1, 10, 1, 2, 2), (2, 11, 3, 2, 3), (3, 12, 3, 4, 4)]
+        );
+        assert_eq!(segments[1].1.typ, "KNOWS".into());
+    }
+
+    #[test]
+    fn should_reject_invalid_path_indices() {
+        let node = BoltNode::new(1.into(), BoltList::new(), BoltMap::default());
+        let rel = BoltUnboundedRelation::new(10.into(), "KNOWS".into(), BoltMap::default());
+        for ids in [vec![1_i64], vec![0, 0], vec![2, 0], vec![1, 5]].iter() {
+            let path = BoltPath {
+                nodes: vec![node.clone().into()].into(),
+                rels: vec![rel.clone().into()].into(),
+                ids: ids
+                    .iter()
+                    .map(|id| (*id).into())
+                    .collect::<Vec<BoltType>>()
+                    .into(),
+            };
+
+            assert!(matches!(
+                path.segments(),
+                Err(Error::DeserializationError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn should_walk_an_empty_path() {
+        let node = BoltNode::new(1.into(), BoltList::new(), BoltMap::default());
+        let path = BoltPath {
+            nodes: vec![node.into()].into(),
+            rels: BoltList::new(),
+            ids: BoltList::new(),
+        };
+
+        assert!(path.segments().unwrap().is_empty());
+    }
 }
@@ -1,6 +1,15 @@
 use crate::types::*;
 use neo4rs_macros::BoltStruct;
 
+/// SRID of geographic 2D points in WGS-84, with longitude as x and latitude as y
+pub const WGS_84_2D: i64 = 4326;
+/// SRID of geographic 3D points in WGS-84, with the height in meters as z
+pub const WGS_84_3D: i64 = 4979;
+/// SRID of 2D points in a cartesian coordinate system
+pub const CARTESIAN_2D: i64 = 7203;
+/// SRID of 3D points in a cartesian coordinate system
+pub const CARTESIAN_3D: i64 = 9157;
+
 #[derive(Debug, PartialEq, Clone, BoltStruct)]
 #[signature(0xB3, 0x58)]
 pub struct BoltPoint2D {
@@ -18,13 +27,31 @@ pub struct BoltPoint3D {
     pub z: BoltFloat,
 }
 
+impl BoltPoint2D {
+    pub fn is_wgs84(&self) -> bool {
+        self.sr_id.value == WGS_84_2D
+    }
+
+    pub fn is_cartesian(&self) -> bool {
+        self.sr_id.value == CARTESIAN_2D
+    }
+}
+
+impl BoltPoint3D {
+    pub fn is_wgs84(&self) -> bool {
+        self.sr_id.value == WGS_84_3D
+    }
+
+    pub fn is_cartesian(&self) -> bool {
+        self.sr_id.value == CARTESIAN_3D
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::version::Version;
     use bytes::*;
-    use std::cell::RefCell;
-    use std::rc::Rc;
 
     #[test]
     fn should_serialize_2d_point() {
@@ -47,12 +74,12 @@ mod tests {
 
     #[test]
     fn should_deserialize_2d_point() {
-        let input = Rc::new(RefCell::new(Bytes::from_static(&[
+        let mut input = Bytes::from_static(&[
             0xB3, 0x58, 0x2A, 0xC1, 0x3F, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC1, 0x40,
             0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        ])));
+        ]);
 
-        let point: BoltPoint2D = BoltPoint2D::parse(Version::V4_1, input).unwrap();
+        let point: BoltPoint2D = BoltPoint2D::parse(Version::V4_1, &mut input).unwrap();
 
         assert_eq!(point.sr_id, BoltInteger::new(42));
         assert_eq!(point.x, BoltFloat::new(1.0));
@@ -82,17 +109,41 @@ mod tests {
 
     #[test]
     fn should_deserialize_3d_point() {
-        let input = Rc::new(RefCell::new(Bytes::from_static(&[
+        let mut input = Bytes::from_static(&[
             0xB4, 0x59, 0x2A, 0xC1, 0x3F, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC1, 0x40,
             0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xC1, 0x40, 0x08, 0x00, 0x00, 0x00, 0x00,
             0x00, 0x00,
-        ])));
+        ]);
 
-        let point: BoltPoint3D = BoltPoint3D::parse(Version::V4_1, input).unwrap();
+        let point: BoltPoint3D = BoltPoint3D::parse(Version::V4_1, &mut input).unwrap();
 
         assert_eq!(point.sr_id, BoltInteger::new(42));
         assert_eq!(point.x, BoltFloat::new(1.0));
         assert_eq!(point.y, BoltFloat::new(2.0));
         assert_eq!(point.z, BoltFloat::new(3.0));
     }
+
+    #[test]
+    fn should_distinguish_coordinate_systems() {
+        let point = |sr_id| BoltPoint2D {
+            sr_id: BoltInteger::new(sr_id),
+            x: BoltFloat::new(12.99),
+            y: BoltFloat::new(55.6),
+        };
+        assert!(point(WGS_84_2D).is_wgs84());
+        assert!(!point(WGS_84_2D).is_cartesian());
+        assert!(point(CARTESIAN_2D).is_cartesian());
+        assert!(!point(CARTESIAN_2D).is_wgs84());
+        assert!(!point(WGS_84_3D).is_wgs84());
+
+        let point = |sr_id| BoltPoint3D {
+            sr_id: BoltInteger::new(sr_id),
+            x: BoltFloat::new(12.99),
+            y: BoltFloat::new(55.6),
+            z: BoltFloat::new(100.0),
+        };
+        assert!(point(WGS_84_3D).is_wgs84());
+        assert!(point(CARTESIAN_3D).is_cartesian());
+        assert!(!point(CARTESIAN_2D).is_cartesian());
+    }
 }
@@ -0,0 +1,193 @@
+use crate::errors::*;
+use crate::types::map::encode_header;
+use crate::types::*;
+use crate::version::Version;
+use bytes::*;
+use std::sync::Arc;
+
+/// Parameters which are encoded once and reused by every query they are passed to with
+/// [`crate::Query::prepared_params`], for queries which are sent many times with mostly the same
+/// parameters. Only the parameters bound to the query itself are encoded for each RUN.
+///
+/// The parameters are encoded ahead for both the temporal encodings of bolt 5.0 and of the
+/// versions before, so that cloning them is cheap and independent of the server. They are
+/// encoded with minimal integers, with [`IntegerEncoding::AlwaysI64`] they are encoded for each
+/// RUN instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreparedParams {
+    base: Arc<BoltMap>,
+    legacy: Bytes,
+    utc: Bytes,
+}
+
+impl PreparedParams {
+    pub fn new(base: BoltMap) -> Result<PreparedParams> {
+        let legacy = encode_entries(&base, Version::V4_4)?;
+        let utc = encode_entries(&base, Version::V5_0)?;
+        Ok(PreparedParams {
+            base: Arc::new(base),
+            legacy,
+            utc,
+        })
+    }
+
+    /// The parameters as they were prepared
+    pub fn base(&self) -> &BoltMap {
+        &self.base
+    }
+
+    /// Appends the map of the prepared parameters together with `params`. A parameter in both
+    /// takes the value in `params`, in that case the merged map is encoded from scratch.
+    pub(crate) fn encode_with(
+        &self,
+        params: &BoltMap,
+        encoding: Encoding,
+        dst: &mut BytesMut,
+    ) -> Result<()> {
+        let entries = match self.entries(encoding) {
+            Some(entries) if !self.overlaps(params) => entries,
+            _ => return self.merge(params).encode(encoding, dst),
+        };
+        encode_header(self.base.len() + params.len(), dst)?;
+        dst.put_slice(entries);
+        for (key, value) in &params.value {
+            key.encode(encoding, dst)?;
+            value.encode(encoding, dst)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn encoded_len_with(&self, params: &BoltMap, encoding: Encoding) -> usize {
+        let entries = match self.entries(encoding) {
+            Some(entries) if !self.overlaps(params) => entries,
+            _ => return self.merge(params).encoded_len(encoding),
+        };
+        let len = self.base.len() + params.len();
+        header_len(len) + entries.len() + params.encoded_len(encoding) - header_len(params.len())
+    }
+
+    /// The entries prepared for `encoding`, if there are any
+    fn entries(&self, encoding: Encoding) -> Option<&[u8]> {
+        if encoding.integers != IntegerEncoding::Minimal {
+            None
+        } else if encoding.version.has_utc_datetimes() {
+            Some(&self.utc)
+        } else {
+            Some(&self.legacy)
+        }
+    }
+
+    fn overlaps(&self, params: &BoltMap) -> bool {
+        params
+            .value
+            .keys()
+            .any(|key| self.base.value.contains_key(key))
+    }
+
+    fn merge(&self, params: &BoltMap) -> BoltMap {
+        let mut merged = (*self.base).clone();
+        for (key, value) in &params.value {
+            merged.put(key.clone(), value.clone());
+        }
+        merged
+    }
+}
+
+/// The encoded entries of `map`, without the marker and size in front of them
+fn encode_entries(map: &BoltMap, version: Version) -> Result<Bytes> {
+    let mut bytes = map.to_bytes(version)?;
+    bytes.advance(header_len(map.len()));
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(entries: Vec<(&str, BoltType)>) -> BoltMap {
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.into(), value))
+            .collect()
+    }
+
+    fn encode_with(
+        prepared: &PreparedParams,
+        params: &BoltMap,
+        encoding: impl Into<Encoding>,
+    ) -> Bytes {
+        let encoding = encoding.into();
+        let mut bytes = BytesMut::new();
+        prepared.encode_with(params, encoding, &mut bytes).unwrap();
+        assert_eq!(bytes.len(), prepared.encoded_len_with(params, encoding));
+        bytes.freeze()
+    }
+
+    #[test]
+    fn should_encode_like_the_merged_map() {
+        let base: BoltMap = (0..20)
+            .map(|i| (format!("s{}", i).into(), format!("value {}", i).into()))
+            .collect();
+        let prepared = PreparedParams::new(base.clone()).unwrap();
+        let params = map(vec![("id", 7.into()), ("name", "Mark".into())]);
+
+        let mut merged = base;
+        merged.put("id".into(), 7.into());
+        merged.put("name".into(), "Mark".into());
+        for &version in [Version::V4_1, Version::V5_0].iter() {
+            assert_eq!(
+                encode_with(&prepared, &params, version),
+                merged.to_bytes(version).unwrap()
+            );
+        }
+        assert_eq!(prepared.base().len(), 20);
+    }
+
+    #[test]
+    fn should_prefer_query_params_over_prepared_ones() {
+        let prepared = PreparedParams::new(map(vec![("limit", 10.into())])).unwrap();
+        let params = map(vec![("limit", 20.into())]);
+
+        let bytes = encode_with(&prepared, &params, Version::V4_1);
+
+        assert_eq!(bytes, params.to_bytes(Version::V4_1).unwrap());
+    }
+
+    #[test]
+    fn should_widen_prepared_integers_for_always_i64() {
+        let prepared = PreparedParams::new(map(vec![("limit", 10.into())])).unwrap();
+        let params = map(vec![("skip", 5.into())]);
+        let encoding = Encoding {
+            version: Version::V4_1,
+            integers: IntegerEncoding::AlwaysI64,
+        };
+
+        let bytes = encode_with(&prepared, &params, encoding);
+
+        assert_eq!(bytes, prepared.merge(&params).to_bytes(encoding).unwrap());
+        assert_eq!(bytes.len(), 1 + (1 + 5) + 9 + (1 + 4) + 9);
+    }
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn should_encode_temporal_values_for_the_version() {
+        use chrono::TimeZone;
+
+        let date_time = BoltType::DateTime(
+            chrono::FixedOffset::east_opt(3600)
+                .unwrap()
+                .with_ymd_and_hms(2024, 3, 1, 12, 0, 0)
+                .unwrap()
+                .into(),
+        );
+        let base = map(vec![("at", date_time)]);
+        let prepared = PreparedParams::new(base.clone()).unwrap();
+
+        for &version in [Version::V4_4, Version::V5_0].iter() {
+            assert_eq!(
+                encode_with(&prepared, &BoltMap::default(), version),
+                base.to_bytes(version).unwrap()
+            );
+        }
+    }
+}
@@ -1,22 +1,168 @@
+use crate::errors::*;
 use crate::types::*;
-use neo4rs_macros::BoltStruct;
+use crate::version::Version;
+use bytes::*;
 
-#[derive(Debug, PartialEq, Clone, BoltStruct)]
-#[signature(0xB5, 0x52)]
+pub const SIGNATURE: u8 = Signature::Relationship as u8;
+pub const UNBOUNDED_SIGNATURE: u8 = Signature::UnboundedRelationship as u8;
+
+/// A relationship, since bolt 5.0 it carries string element ids for itself and both of its nodes
+/// next to the legacy integer ids. The server may reuse legacy ids once an entity is deleted, so
+/// element ids should be preferred to identify entities where they are available.
+#[derive(Debug, PartialEq, Clone)]
 pub struct BoltRelation {
     pub id: BoltInteger,
     pub start_node_id: BoltInteger,
     pub end_node_id: BoltInteger,
     pub typ: BoltString,
     pub properties: BoltMap,
+    pub element_id: Option<BoltString>,
+    pub start_node_element_id: Option<BoltString>,
+    pub end_node_element_id: Option<BoltString>,
 }
 
-#[derive(Debug, PartialEq, Clone, BoltStruct)]
-#[signature(0xB3, 0x72)]
+/// A relationship within a path, since bolt 5.0 it carries a string `element_id` next to the
+/// legacy integer id.
+#[derive(Debug, PartialEq, Clone)]
 pub struct BoltUnboundedRelation {
     pub id: BoltInteger,
     pub typ: BoltString,
     pub properties: BoltMap,
+    pub element_id: Option<BoltString>,
+}
+
+/// The direction of a relationship seen from one of its nodes
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Direction {
+    /// The relationship starts at the node
+    Outgoing,
+    /// The relationship ends at the node
+    Incoming,
+}
+
+impl BoltUnboundedRelation {
+    /// The relationship as a relationship from `start` to `end`
+    pub fn bind(&self, start: &BoltNode, end: &BoltNode) -> BoltRelation {
+        BoltRelation {
+            id: self.id.clone(),
+            start_node_id: start.id.clone(),
+            end_node_id: end.id.clone(),
+            typ: self.typ.clone(),
+            properties: self.properties.clone(),
+            element_id: self.element_id.clone(),
+            start_node_element_id: start.element_id.clone(),
+            end_node_element_id: end.element_id.clone(),
+        }
+    }
+}
+
+/// Encodes the element id, entities that were created without one send their legacy id instead.
+fn encode_element_id(
+    element_id: &Option<BoltString>,
+    id: &BoltInteger,
+    encoding: Encoding,
+    dst: &mut BytesMut,
+) -> Result<()> {
+    match element_id {
+        Some(element_id) => element_id.encode(encoding, dst),
+        None => BoltString::from(id.value.to_string()).encode(encoding, dst),
+    }
+}
+
+fn element_id_len(element_id: &Option<BoltString>, id: &BoltInteger, encoding: Encoding) -> usize {
+    match element_id {
+        Some(element_id) => element_id.encoded_len(encoding),
+        None => BoltString::from(id.value.to_string()).encoded_len(encoding),
+    }
+}
+
+impl BoltRelation {
+    fn fields(version: Version) -> u8 {
+        if version.has_element_ids() {
+            8
+        } else {
+            5
+        }
+    }
+
+    pub fn can_parse(version: Version, input: &Bytes) -> bool {
+        input.len() >= 2
+            && input[0] == structure::TINY | Self::fields(version)
+            && input[1] == SIGNATURE
+    }
+
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        self.to_bytes(version)
+    }
+
+    pub fn parse(version: Version, input: &mut Bytes) -> Result<BoltRelation> {
+        signature::read_signature(input, Signature::Relationship)?;
+        let id = BoltInteger::parse(version, input)?;
+        let start_node_id = BoltInteger::parse(version, input)?;
+        let end_node_id = BoltInteger::parse(version, input)?;
+        let typ = BoltString::parse(version, input)?;
+        let properties = BoltMap::parse(version, input)?;
+        let (element_id, start_node_element_id, end_node_element_id) = if version.has_element_ids()
+        {
+            (
+                Some(BoltString::parse(version, input)?),
+                Some(BoltString::parse(version, input)?),
+                Some(BoltString::parse(version, input)?),
+            )
+        } else {
+            (None, None, None)
+        };
+        Ok(BoltRelation {
+            id,
+            start_node_id,
+            end_node_id,
+            typ,
+            properties,
+            element_id,
+            start_node_element_id,
+            end_node_element_id,
+        })
+    }
+}
+
+impl BoltEncode for BoltRelation {
+    fn encode(&self, encoding: Encoding, dst: &mut BytesMut) -> Result<()> {
+        let version = encoding.version;
+        dst.put_u8(structure::TINY | Self::fields(version));
+        dst.put_u8(SIGNATURE);
+        self.id.encode(encoding, dst)?;
+        self.start_node_id.encode(encoding, dst)?;
+        self.end_node_id.encode(encoding, dst)?;
+        self.typ.encode(encoding, dst)?;
+        self.properties.encode(encoding, dst)?;
+        if version.has_element_ids() {
+            encode_element_id(&self.element_id, &self.id, encoding, dst)?;
+            encode_element_id(
+                &self.start_node_element_id,
+                &self.start_node_id,
+                encoding,
+                dst,
+            )?;
+            encode_element_id(&self.end_node_element_id, &self.end_node_id, encoding, dst)?;
+        }
+        Ok(())
+    }
+
+    fn encoded_len(&self, encoding: Encoding) -> usize {
+        let version = encoding.version;
+        let mut len = 2
+            + self.id.encoded_len(encoding)
+            + self.start_node_id.encoded_len(encoding)
+            + self.end_node_id.encoded_len(encoding)
+            + self.typ.encoded_len(encoding)
+            + self.properties.encoded_len(encoding);
+        if version.has_element_ids() {
+            len += element_id_len(&self.element_id, &self.id, encoding)
+                + element_id_len(&self.start_node_element_id, &self.start_node_id, encoding)
+                + element_id_len(&self.end_node_element_id, &self.end_node_id, encoding);
+        }
+        len
+    }
 }
 
 impl BoltUnboundedRelation {
@@ -25,7 +171,71 @@ impl BoltUnboundedRelation {
             id,
             typ,
             properties,
+            element_id: None,
+        }
+    }
+
+    fn fields(version: Version) -> u8 {
+        if version.has_element_ids() {
+            4
+        } else {
+            3
+        }
+    }
+
+    pub fn can_parse(version: Version, input: &Bytes) -> bool {
+        input.len() >= 2
+            && input[0] == structure::TINY | Self::fields(version)
+            && input[1] == UNBOUNDED_SIGNATURE
+    }
+
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        self.to_bytes(version)
+    }
+
+    pub fn parse(version: Version, input: &mut Bytes) -> Result<BoltUnboundedRelation> {
+        signature::read_signature(input, Signature::UnboundedRelationship)?;
+        let id = BoltInteger::parse(version, input)?;
+        let typ = BoltString::parse(version, input)?;
+        let properties = BoltMap::parse(version, input)?;
+        let element_id = if version.has_element_ids() {
+            Some(BoltString::parse(version, input)?)
+        } else {
+            None
+        };
+        Ok(BoltUnboundedRelation {
+            id,
+            typ,
+            properties,
+            element_id,
+        })
+    }
+}
+
+impl BoltEncode for BoltUnboundedRelation {
+    fn encode(&self, encoding: Encoding, dst: &mut BytesMut) -> Result<()> {
+        let version = encoding.version;
+        dst.put_u8(structure::TINY | Self::fields(version));
+        dst.put_u8(UNBOUNDED_SIGNATURE);
+        self.id.encode(encoding, dst)?;
+        self.typ.encode(encoding, dst)?;
+        self.properties.encode(encoding, dst)?;
+        if version.has_element_ids() {
+            encode_element_id(&self.element_id, &self.id, encoding, dst)?;
+        }
+        Ok(())
+    }
+
+    fn encoded_len(&self, encoding: Encoding) -> usize {
+        let version = encoding.version;
+        let mut len = 2
+            + self.id.encoded_len(encoding)
+            + self.typ.encoded_len(encoding)
+            + self.properties.encoded_len(encoding);
+        if version.has_element_ids() {
+            len += element_id_len(&self.element_id, &self.id, encoding);
         }
+        len
     }
 }
 
@@ -33,6 +243,63 @@ impl BoltRelation {
     pub fn get<T: std::convert::TryFrom<BoltType>>(&self, key: &str) -> Option<T> {
         self.properties.get(key)
     }
+
+    pub fn id(&self) -> i64 {
+        self.id.value
+    }
+
+    pub fn element_id(&self) -> Option<&str> {
+        self.element_id.as_ref().map(|id| id.value.as_str())
+    }
+
+    pub fn start_node_id(&self) -> i64 {
+        self.start_node_id.value
+    }
+
+    pub fn start_node_element_id(&self) -> Option<&str> {
+        self.start_node_element_id
+            .as_ref()
+            .map(|id| id.value.as_str())
+    }
+
+    pub fn end_node_id(&self) -> i64 {
+        self.end_node_id.value
+    }
+
+    pub fn end_node_element_id(&self) -> Option<&str> {
+        self.end_node_element_id
+            .as_ref()
+            .map(|id| id.value.as_str())
+    }
+
+    pub fn typ(&self) -> &str {
+        &self.typ.value
+    }
+
+    pub fn properties(&self) -> &BoltMap {
+        &self.properties
+    }
+
+    /// The direction of the relationship relative to `node`, or `None` if it is not attached to
+    /// the node. Walking the segments of a path, the direction from the node a segment starts at
+    /// tells whether the relationship is traversed forwards or backwards. Nodes are compared by
+    /// element id if both sides have one and by legacy id otherwise, loops are outgoing.
+    pub fn direction_from(&self, node: &BoltNode) -> Option<Direction> {
+        let is = |id: &BoltInteger, element_id: &Option<BoltString>| match (
+            element_id,
+            &node.element_id,
+        ) {
+            (Some(element_id), Some(other)) => element_id == other,
+            _ => *id == node.id,
+        };
+        if is(&self.start_node_id, &self.start_node_element_id) {
+            Some(Direction::Outgoing)
+        } else if is(&self.end_node_id, &self.end_node_element_id) {
+            Some(Direction::Incoming)
+        } else {
+            None
+        }
+    }
 }
 
 impl BoltUnboundedRelation {
@@ -41,24 +308,21 @@ impl BoltUnboundedRelation {
     }
 }
 
-impl Into<BoltType> for BoltRelation {
-    fn into(self) -> BoltType {
-        BoltType::Relation(self)
+impl From<BoltRelation> for BoltType {
+    fn from(value: BoltRelation) -> BoltType {
+        BoltType::Relation(value)
     }
 }
 
-impl Into<BoltType> for BoltUnboundedRelation {
-    fn into(self) -> BoltType {
-        BoltType::UnboundedRelation(self)
+impl From<BoltUnboundedRelation> for BoltType {
+    fn from(value: BoltUnboundedRelation) -> BoltType {
+        BoltType::UnboundedRelation(value)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use bytes::*;
-    use std::cell::RefCell;
-    use std::rc::Rc;
 
     #[test]
     fn should_serialize_a_relation() {
@@ -74,6 +338,9 @@ mod tests {
             end_node_id,
             typ,
             properties,
+            element_id: None,
+            start_node_element_id: None,
+            end_node_element_id: None,
         };
 
         let bytes: Bytes = relation.into_bytes(Version::V4_1).unwrap();
@@ -89,12 +356,12 @@ mod tests {
 
     #[test]
     fn should_deserialize_a_relation() {
-        let input = Rc::new(RefCell::new(Bytes::from_static(&[
+        let mut input = Bytes::from_static(&[
             0xB5, 0x52, 0x2A, 0x01, 0x02, 0x83, 0x72, 0x65, 0x6C, 0xA1, 0x84, 0x6E, 0x61, 0x6D,
             0x65, 0x84, 0x4D, 0x61, 0x72, 0x6B,
-        ])));
+        ]);
 
-        let relation: BoltRelation = BoltRelation::parse(Version::V4_1, input).unwrap();
+        let relation: BoltRelation = BoltRelation::parse(Version::V4_1, &mut input).unwrap();
 
         assert_eq!(relation.id, BoltInteger::new(42));
         assert_eq!(relation.start_node_id, BoltInteger::new(1));
@@ -126,13 +393,13 @@ mod tests {
 
     #[test]
     fn should_deserialize_an_unbounded_relation() {
-        let input = Rc::new(RefCell::new(Bytes::from_static(&[
+        let mut input = Bytes::from_static(&[
             0xB3, 0x72, 0x2A, 0x83, 0x72, 0x65, 0x6C, 0xA1, 0x84, 0x6E, 0x61, 0x6D, 0x65, 0x84,
             0x4D, 0x61, 0x72, 0x6B,
-        ])));
+        ]);
 
         let relation: BoltUnboundedRelation =
-            BoltUnboundedRelation::parse(Version::V4_1, input).unwrap();
+            BoltUnboundedRelation::parse(Version::V4_1, &mut input).unwrap();
 
         assert_eq!(relation.id, BoltInteger::new(42));
         assert_eq!(relation.typ, BoltString::new("rel"));
@@ -141,4 +408,81 @@ mod tests {
             vec![("name".into(), "Mark".into())].into_iter().collect()
         );
     }
+
+    #[test]
+    fn should_deserialize_a_v5_relation() {
+        let mut input = Bytes::from_static(&[
+            0xB8, 0x52, 0x2A, 0x01, 0x02, 0x83, 0x72, 0x65, 0x6C, 0xA0, 0x82, b'r', b'1', 0x82,
+            b'n', b'1', 0x82, b'n', b'2',
+        ]);
+
+        assert!(!BoltRelation::can_parse(Version::V4_4, &input));
+        let relation = BoltRelation::parse(Version::V5_0, &mut input).unwrap();
+
+        assert_eq!(relation.id, BoltInteger::new(42));
+        assert_eq!(relation.element_id, Some("r1".into()));
+        assert_eq!(relation.start_node_element_id, Some("n1".into()));
+        assert_eq!(relation.end_node_element_id, Some("n2".into()));
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn should_read_endpoints_of_a_relation() {
+        let mut input = Bytes::from_static(&[
+            0xB8, 0x52, 0x2A, 0x01, 0x02, 0x83, 0x72, 0x65, 0x6C, 0xA0, 0x82, b'r', b'1', 0x82,
+            b'n', b'1', 0x82, b'n', b'2',
+        ]);
+        let relation = BoltRelation::parse(Version::V5_0, &mut input).unwrap();
+
+        assert_eq!(relation.id(), 42);
+        assert_eq!(relation.typ(), "rel");
+        assert_eq!(relation.start_node_id(), 1);
+        assert_eq!(relation.start_node_element_id(), Some("n1"));
+        assert_eq!(relation.end_node_id(), 2);
+        assert_eq!(relation.end_node_element_id(), Some("n2"));
+        assert!(relation.properties().value.is_empty());
+
+        let node = |id: i64, element_id: &str| {
+            let mut node = BoltNode::new(id.into(), BoltList::default(), BoltMap::default());
+            node.element_id = Some(element_id.into());
+            node
+        };
+        assert_eq!(
+            relation.direction_from(&node(1, "n1")),
+            Some(Direction::Outgoing)
+        );
+        assert_eq!(
+            relation.direction_from(&node(2, "n2")),
+            Some(Direction::Incoming)
+        );
+        assert_eq!(relation.direction_from(&node(1, "n3")), None);
+        let legacy = BoltNode::new(2.into(), BoltList::default(), BoltMap::default());
+        assert_eq!(relation.direction_from(&legacy), Some(Direction::Incoming));
+    }
+
+    #[test]
+    fn should_deserialize_a_v5_unbounded_relation() {
+        let mut input = Bytes::from_static(&[
+            0xB4, 0x72, 0x2A, 0x83, 0x72, 0x65, 0x6C, 0xA0, 0x82, b'r', b'1',
+        ]);
+
+        assert!(!BoltUnboundedRelation::can_parse(Version::V4_4, &input));
+        let relation = BoltUnboundedRelation::parse(Version::V5_0, &mut input).unwrap();
+
+        assert_eq!(relation.typ, BoltString::new("rel"));
+        assert_eq!(relation.element_id, Some("r1".into()));
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn should_serialize_legacy_id_as_element_id() {
+        let relation = BoltUnboundedRelation::new(42.into(), "rel".into(), BoltMap::default());
+
+        let bytes: Bytes = relation.into_bytes(Version::V5_0).unwrap();
+
+        assert_eq!(
+            bytes,
+            Bytes::from_static(&[0xB4, 0x72, 0x2A, 0x83, 0x72, 0x65, 0x6C, 0xA0, 0x82, b'4', b'2'])
+        );
+    }
 }
@@ -0,0 +1,118 @@
+use crate::errors::{Error, Result};
+use crate::types::read_exact;
+use bytes::*;
+use std::convert::TryFrom;
+
+/// The signatures of the structures packstream values can be encoded as, the byte after the
+/// structure marker.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[repr(u8)]
+pub enum Signature {
+    Node = 0x4E,
+    Relationship = 0x52,
+    UnboundedRelationship = 0x72,
+    Path = 0x50,
+    Date = 0x44,
+    Time = 0x54,
+    LocalTime = 0x74,
+    /// A datetime with offset in bolt 5.0, counting seconds in UTC
+    DateTime = 0x49,
+    /// A datetime with zone id in bolt 5.0, counting seconds in UTC
+    DateTimeZoneId = 0x69,
+    /// A datetime with offset before bolt 5.0, counting seconds in local time
+    LegacyDateTime = 0x46,
+    /// A datetime with zone id before bolt 5.0, counting seconds in local time
+    LegacyDateTimeZoneId = 0x66,
+    LocalDateTime = 0x64,
+    Duration = 0x45,
+    Point2D = 0x58,
+    Point3D = 0x59,
+}
+
+impl TryFrom<u8> for Signature {
+    type Error = Error;
+
+    fn try_from(signature: u8) -> Result<Signature> {
+        let signature = match signature {
+            0x4E => Signature::Node,
+            0x52 => Signature::Relationship,
+            0x72 => Signature::UnboundedRelationship,
+            0x50 => Signature::Path,
+            0x44 => Signature::Date,
+            0x54 => Signature::Time,
+            0x74 => Signature::LocalTime,
+            0x49 => Signature::DateTime,
+            0x69 => Signature::DateTimeZoneId,
+            0x46 => Signature::LegacyDateTime,
+            0x66 => Signature::LegacyDateTimeZoneId,
+            0x64 => Signature::LocalDateTime,
+            0x45 => Signature::Duration,
+            0x58 => Signature::Point2D,
+            0x59 => Signature::Point3D,
+            found => return Err(Error::InvalidStructureSignature { found }),
+        };
+        Ok(signature)
+    }
+}
+
+impl From<Signature> for u8 {
+    fn from(signature: Signature) -> u8 {
+        signature as u8
+    }
+}
+
+/// Consumes the marker and signature of a structure, failing if the signature is not `expected`.
+pub(crate) fn read_signature(input: &mut Bytes, expected: Signature) -> Result<()> {
+    let found = read_exact(input, 2)?[1];
+    if Signature::try_from(found)? != expected {
+        return Err(Error::InvalidStructureSignature { found });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_map_known_signatures() {
+        let known = [
+            (0x4E, Signature::Node),
+            (0x52, Signature::Relationship),
+            (0x72, Signature::UnboundedRelationship),
+            (0x50, Signature::Path),
+            (0x44, Signature::Date),
+            (0x54, Signature::Time),
+            (0x74, Signature::LocalTime),
+            (0x49, Signature::DateTime),
+            (0x69, Signature::DateTimeZoneId),
+            (0x46, Signature::LegacyDateTime),
+            (0x66, Signature::LegacyDateTimeZoneId),
+            (0x64, Signature::LocalDateTime),
+            (0x45, Signature::Duration),
+            (0x58, Signature::Point2D),
+            (0x59, Signature::Point3D),
+        ];
+        for &(byte, signature) in known.iter() {
+            assert_eq!(Signature::try_from(byte).unwrap(), signature);
+            assert_eq!(u8::from(signature), byte);
+        }
+        assert!(matches!(
+            Signature::try_from(0x01),
+            Err(Error::InvalidStructureSignature { found: 0x01 })
+        ));
+    }
+
+    #[test]
+    fn should_reject_unexpected_signature() {
+        let mut input = Bytes::from_static(&[0xB3, 0x52, 0x01]);
+        assert!(matches!(
+            read_signature(&mut input, Signature::Node),
+            Err(Error::InvalidStructureSignature { found: 0x52 })
+        ));
+
+        let mut input = Bytes::from_static(&[0xB3, 0x4E, 0x01]);
+        read_signature(&mut input, Signature::Node).unwrap();
+        assert_eq!(input, Bytes::from_static(&[0x01]));
+    }
+}
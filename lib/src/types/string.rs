@@ -1,11 +1,11 @@
 use crate::errors::*;
+use crate::types::marker::{peek_kind, BoltKind};
+use crate::types::{header_len, read_exact, BoltEncode, Encoding};
 use crate::version::Version;
 use bytes::*;
-use std::cell::RefCell;
-use std::convert::From;
+use std::convert::{From, TryFrom};
 use std::fmt::Display;
 use std::mem;
-use std::rc::Rc;
 
 pub const TINY: u8 = 0x80;
 pub const SMALL: u8 = 0xD0;
@@ -24,12 +24,59 @@ impl BoltString {
         }
     }
 
-    pub fn can_parse(_: Version, input: Rc<RefCell<Bytes>>) -> bool {
-        let marker = input.borrow()[0];
-        (TINY..=(TINY | 0x0F)).contains(&marker)
-            || marker == SMALL
-            || marker == MEDIUM
-            || marker == LARGE
+    pub fn can_parse(_: Version, input: &Bytes) -> bool {
+        peek_kind(input) == Some(BoltKind::String)
+    }
+}
+
+/// A string decoded without copying, it keeps a reference to the received buffer and validates
+/// the utf-8 in place.
+///
+/// Useful for large text properties where allocating a [`String`] per value is expensive, use
+/// [`BoltStr::as_str`] to read the value or convert it into a [`BoltString`] when ownership is
+/// needed.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct BoltStr {
+    value: Bytes,
+}
+
+impl BoltStr {
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.value).expect("BoltStr::parse validates the utf-8")
+    }
+
+    pub fn len(&self) -> usize {
+        self.value.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    pub fn parse(_: Version, input: &mut Bytes) -> Result<BoltStr> {
+        let marker = read_exact(input, mem::size_of::<u8>())?.get_u8();
+        let length = match marker {
+            0x80..=0x8F => 0x0F & marker as usize,
+            SMALL => read_exact(input, mem::size_of::<u8>())?.get_u8() as usize,
+            MEDIUM => read_exact(input, mem::size_of::<u16>())?.get_u16() as usize,
+            LARGE => read_exact(input, mem::size_of::<u32>())?.get_u32() as usize,
+            _ => return Err(unexpected_marker(BoltKind::String, marker)),
+        };
+        let value = read_exact(input, length)?;
+        std::str::from_utf8(&value)?;
+        Ok(BoltStr { value })
+    }
+}
+
+impl Display for BoltStr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl From<BoltStr> for BoltString {
+    fn from(v: BoltStr) -> Self {
+        BoltString::new(v.as_str())
     }
 }
 
@@ -58,55 +105,69 @@ impl Into<String> for BoltString {
 }
 
 impl BoltString {
-    pub fn into_bytes(self, _: Version) -> Result<Bytes> {
-        let mut bytes = BytesMut::with_capacity(
-            mem::size_of::<u8>() + mem::size_of::<u32>() + self.value.len(),
-        );
-        match self.value.len() {
-            0..=15 => bytes.put_u8(TINY | self.value.len() as u8),
-            16..=255 => {
-                bytes.put_u8(SMALL);
-                bytes.put_u8(self.value.len() as u8);
-            }
-            256..=65_535 => {
-                bytes.put_u8(MEDIUM);
-                bytes.put_u16(self.value.len() as u16);
-            }
-            65_536..=4_294_967_295 => {
-                bytes.put_u8(LARGE);
-                bytes.put_u32(self.value.len() as u32);
-            }
-            _ => return Err(Error::StringTooLong),
-        };
-        bytes.put_slice(self.value.as_bytes());
-        Ok(bytes.freeze())
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        self.to_bytes(version)
     }
 
-    pub fn parse(_: Version, input: Rc<RefCell<Bytes>>) -> Result<BoltString> {
-        let mut input = input.borrow_mut();
-        let marker = input.get_u8();
-        let length = match marker {
-            0x80..=0x8F => 0x0F & marker as usize,
-            SMALL => input.get_u8() as usize,
-            MEDIUM => input.get_u16() as usize,
-            LARGE => input.get_u32() as usize,
-            _ => {
-                return Err(Error::InvalidTypeMarker(format!(
-                    "invalid string marker {}",
-                    marker
-                )))
-            }
-        };
-        let byte_array = input.split_to(length).to_vec();
-        let string_value = std::string::String::from_utf8(byte_array)
-            .map_err(|e| Error::DeserializationError(e.to_string()))?;
-        Ok(string_value.into())
+    pub fn parse(version: Version, input: &mut Bytes) -> Result<BoltString> {
+        Ok(BoltStr::parse(version, input)?.into())
+    }
+}
+
+impl BoltEncode for BoltString {
+    fn encode(&self, _: Encoding, dst: &mut BytesMut) -> Result<()> {
+        let length = u32::try_from(self.value.len()).map_err(|_| Error::StringTooLong)?;
+        dst.reserve(mem::size_of::<u8>() + mem::size_of::<u32>() + self.value.len());
+        put_header(dst, length);
+        dst.put_slice(self.value.as_bytes());
+        Ok(())
+    }
+
+    fn encoded_len(&self, _: Encoding) -> usize {
+        header_len(self.value.len()) + self.value.len()
+    }
+}
+
+/// Writes the marker and the size of a string of `length` bytes in the smallest encoding.
+fn put_header(bytes: &mut BytesMut, length: u32) {
+    match length {
+        0..=15 => bytes.put_u8(TINY | length as u8),
+        16..=255 => {
+            bytes.put_u8(SMALL);
+            bytes.put_u8(length as u8);
+        }
+        256..=65_535 => {
+            bytes.put_u8(MEDIUM);
+            bytes.put_u16(length as u16);
+        }
+        _ => {
+            bytes.put_u8(LARGE);
+            bytes.put_u32(length);
+        }
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl BoltString {
+    /// Parses the string as a uuid, for uuids which are stored as string properties
+    pub fn to_uuid(&self) -> Result<uuid::Uuid> {
+        uuid::Uuid::parse_str(&self.value).map_err(|e| {
+            Error::DeserializationError(format!("invalid uuid {:?}: {}", self.value, e))
+        })
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for BoltString {
+    fn from(v: uuid::Uuid) -> Self {
+        BoltString::new(&v.to_hyphenated().to_string())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::BoltType;
 
     #[test]
     fn should_serialize_empty_string() {
@@ -117,8 +178,8 @@ mod tests {
 
     #[test]
     fn should_deserialize_empty_string() {
-        let input = Rc::new(RefCell::new(Bytes::from_static(&[TINY])));
-        let s: BoltString = BoltString::parse(Version::V4_1, input).unwrap();
+        let mut input = Bytes::from_static(&[TINY]);
+        let s: BoltString = BoltString::parse(Version::V4_1, &mut input).unwrap();
         assert_eq!(s, "".into());
     }
 
@@ -131,8 +192,8 @@ mod tests {
 
     #[test]
     fn should_deserialize_tiny_string() {
-        let serialized_bytes = Rc::new(RefCell::new(Bytes::from_static(&[0x81, 0x61])));
-        let result: BoltString = BoltString::parse(Version::V4_1, serialized_bytes).unwrap();
+        let mut serialized_bytes = Bytes::from_static(&[0x81, 0x61]);
+        let result: BoltString = BoltString::parse(Version::V4_1, &mut serialized_bytes).unwrap();
         assert_eq!(result, "a".into());
     }
 
@@ -152,8 +213,8 @@ mod tests {
 
     #[test]
     fn should_deserialize_small_string() {
-        let serialized_bytes = Rc::new(RefCell::new(Bytes::from_static(&[SMALL, 0x01, 0x61])));
-        let result: BoltString = BoltString::parse(Version::V4_1, serialized_bytes).unwrap();
+        let mut serialized_bytes = Bytes::from_static(&[SMALL, 0x01, 0x61]);
+        let result: BoltString = BoltString::parse(Version::V4_1, &mut serialized_bytes).unwrap();
         assert_eq!(result, "a".into());
     }
 
@@ -173,10 +234,8 @@ mod tests {
 
     #[test]
     fn should_deserialize_medium_string() {
-        let serialized_bytes = Rc::new(RefCell::new(Bytes::from_static(&[
-            MEDIUM, 0x00, 0x01, 0x61,
-        ])));
-        let result: BoltString = BoltString::parse(Version::V4_1, serialized_bytes).unwrap();
+        let mut serialized_bytes = Bytes::from_static(&[MEDIUM, 0x00, 0x01, 0x61]);
+        let result: BoltString = BoltString::parse(Version::V4_1, &mut serialized_bytes).unwrap();
         assert_eq!(result, "a".into());
     }
 
@@ -194,12 +253,128 @@ mod tests {
         }
     }
 
+    #[test]
+    fn should_write_header_up_to_max_length() {
+        let header = |length: u32| {
+            let mut bytes = BytesMut::new();
+            put_header(&mut bytes, length);
+            bytes.freeze()
+        };
+
+        assert_eq!(header(65_535), Bytes::from_static(&[MEDIUM, 0xFF, 0xFF]));
+        assert_eq!(
+            header(65_536),
+            Bytes::from_static(&[LARGE, 0x00, 0x01, 0x00, 0x00])
+        );
+        assert_eq!(
+            header(u32::MAX),
+            Bytes::from_static(&[LARGE, 0xFF, 0xFF, 0xFF, 0xFF])
+        );
+    }
+
     #[test]
     fn should_deserialize_large_string() {
-        let serialized_bytes = Rc::new(RefCell::new(Bytes::from_static(&[
-            LARGE, 0x00, 0x00, 0x00, 0x01, 0x61,
-        ])));
-        let result: BoltString = BoltString::parse(Version::V4_1, serialized_bytes).unwrap();
+        let mut serialized_bytes = Bytes::from_static(&[LARGE, 0x00, 0x00, 0x00, 0x01, 0x61]);
+        let result: BoltString = BoltString::parse(Version::V4_1, &mut serialized_bytes).unwrap();
         assert_eq!(result, "a".into());
     }
+
+    #[test]
+    fn should_deserialize_str_without_copying() {
+        let buffer = Bytes::from_static(&[0x85, b'h', b'e', b'l', b'l', b'o', 0x01]);
+        let mut input = buffer.clone();
+
+        let s: BoltStr = BoltStr::parse(Version::V4_1, &mut input).unwrap();
+
+        assert_eq!(s.as_str(), "hello");
+        assert_eq!(s.as_str().as_ptr(), buffer[1..].as_ptr());
+        assert_eq!(&input[..], &[0x01]);
+        assert_eq!(BoltString::from(s), "hello".into());
+    }
+
+    #[test]
+    fn should_reject_invalid_utf8() {
+        let mut input = Bytes::from_static(&[0x82, 0xC3, 0x28]);
+        assert!(matches!(
+            BoltString::parse(Version::V4_1, &mut input),
+            Err(Error::DeserializationError(_))
+        ));
+    }
+
+    #[test]
+    fn should_report_unexpected_type() {
+        let mut input = Bytes::from_static(&[0x2A]);
+        assert!(matches!(
+            BoltString::parse(Version::V4_1, &mut input),
+            Err(Error::UnexpectedType {
+                expected: BoltKind::String,
+                found: BoltKind::Integer
+            })
+        ));
+
+        let value: BoltType = 42.into();
+        assert!(matches!(
+            String::try_from(value),
+            Err(Error::UnexpectedType {
+                expected: BoltKind::String,
+                found: BoltKind::Integer
+            })
+        ));
+    }
+
+    #[test]
+    fn should_reject_truncated_string() {
+        let mut input = Bytes::from_static(&[0x85, b'h', b'e']);
+        match BoltString::parse(Version::V4_1, &mut input) {
+            Err(Error::DeserializationError(detail)) => {
+                assert_eq!(detail, "expected 5 more bytes but only 2 are available")
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let mut input = Bytes::from_static(&[LARGE, 0x00, 0x01]);
+        assert!(matches!(
+            BoltString::parse(Version::V4_1, &mut input),
+            Err(Error::DeserializationError(_))
+        ));
+
+        let mut input = Bytes::new();
+        assert!(matches!(
+            BoltString::parse(Version::V4_1, &mut input),
+            Err(Error::DeserializationError(_))
+        ));
+    }
+
+    #[test]
+    fn should_reject_declared_length_beyond_input() {
+        let mut input = Bytes::from_static(&[LARGE, 0xFF, 0xFF, 0xFF, 0xFF, b'a']);
+        match BoltString::parse(Version::V4_1, &mut input) {
+            Err(Error::DeserializationError(detail)) => {
+                assert_eq!(
+                    detail,
+                    "expected 4294967295 more bytes but only 1 are available"
+                )
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn should_round_trip_uuid() {
+        let id = uuid::Uuid::new_v4();
+
+        let s = BoltString::from(id);
+
+        assert_eq!(s.value.len(), 36);
+        assert_eq!(s.to_uuid().unwrap(), id);
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn should_reject_malformed_uuid() {
+        let s = BoltString::new("not-a-uuid");
+
+        assert!(matches!(s.to_uuid(), Err(Error::DeserializationError(_))));
+    }
 }
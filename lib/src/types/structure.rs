@@ -0,0 +1,127 @@
+use crate::errors::*;
+use crate::types::marker::{peek_kind, BoltKind};
+use crate::types::*;
+use crate::version::Version;
+use bytes::*;
+use std::mem;
+
+pub const TINY: u8 = 0xB0;
+
+/// A packstream structure that the driver has no dedicated type for.
+///
+/// Known structures like nodes or relations are decoded into their own types, anything else is
+/// read generically as a signature and its fields so the remaining input stays decodable.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BoltStructure {
+    pub signature: u8,
+    pub fields: BoltList,
+}
+
+impl BoltStructure {
+    pub fn new(signature: u8, fields: BoltList) -> Self {
+        BoltStructure { signature, fields }
+    }
+
+    pub fn can_parse(_: Version, input: &Bytes) -> bool {
+        input.len() >= 2 && peek_kind(input) == Some(BoltKind::Structure)
+    }
+}
+
+impl BoltStructure {
+    pub fn into_bytes(self, version: Version) -> Result<Bytes> {
+        self.to_bytes(version)
+    }
+
+    pub fn parse(version: Version, input: &mut Bytes) -> Result<BoltStructure> {
+        let marker = read_exact(input, mem::size_of::<u8>())?.get_u8();
+        let size = match marker {
+            0xB0..=0xBF => 0x0F & marker as usize,
+            _ => return Err(unexpected_marker(BoltKind::Structure, marker)),
+        };
+        let signature = read_exact(input, mem::size_of::<u8>())?.get_u8();
+
+        let mut fields = BoltList::with_capacity(size);
+        for _ in 0..size {
+            fields.push(BoltType::parse(version, input)?);
+        }
+
+        Ok(BoltStructure::new(signature, fields))
+    }
+}
+
+impl BoltEncode for BoltStructure {
+    fn encode(&self, encoding: Encoding, dst: &mut BytesMut) -> Result<()> {
+        let length = self.fields.len();
+        if length > 0x0F {
+            return Err(Error::StructureTooBig);
+        }
+        dst.put_u8(TINY | length as u8);
+        dst.put_u8(self.signature);
+        for field in &self.fields.value {
+            field.encode(encoding, dst)?;
+        }
+        Ok(())
+    }
+
+    fn encoded_len(&self, encoding: Encoding) -> usize {
+        let fields: usize = self
+            .fields
+            .value
+            .iter()
+            .map(|f| f.encoded_len(encoding))
+            .sum();
+        2 + fields
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_serialize_structure() {
+        let structure = BoltStructure::new(0x01, vec![42.into(), "a".into()].into());
+
+        let bytes: Bytes = structure.into_bytes(Version::V4_1).unwrap();
+
+        assert_eq!(&bytes[..], &[0xB2, 0x01, 0x2A, 0x81, 0x61]);
+    }
+
+    #[test]
+    fn should_deserialize_structure() {
+        let mut input = Bytes::from_static(&[0xB2, 0x01, 0x2A, 0x91, 0x81, 0x61, 0x01]);
+
+        let structure = BoltStructure::parse(Version::V4_1, &mut input).unwrap();
+
+        assert_eq!(structure.signature, 0x01);
+        assert_eq!(
+            structure.fields,
+            vec![42.into(), BoltType::List(vec!["a".into()].into())].into()
+        );
+        assert_eq!(&input[..], &[0x01]);
+    }
+
+    #[test]
+    fn should_parse_unknown_structure_as_bolt_type() {
+        let mut input = Bytes::from_static(&[0xB1, 0x01, 0x2A, 0xB3, 0x4E, 0x01, 0x90, 0xA0]);
+
+        let unknown = BoltType::parse(Version::V4_1, &mut input).unwrap();
+        let node = BoltType::parse(Version::V4_1, &mut input).unwrap();
+
+        assert_eq!(
+            unknown,
+            BoltType::Structure(BoltStructure::new(0x01, vec![42.into()].into()))
+        );
+        assert!(matches!(node, BoltType::Node(_)));
+    }
+
+    #[test]
+    fn should_reject_too_many_fields() {
+        let fields: Vec<BoltType> = (0..16).map(|i: i64| i.into()).collect();
+        let structure = BoltStructure::new(0x01, fields.into());
+        assert!(matches!(
+            structure.into_bytes(Version::V4_1),
+            Err(Error::StructureTooBig)
+        ));
+    }
+}
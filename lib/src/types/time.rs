@@ -1,5 +1,13 @@
 use crate::types::*;
-use chrono::{FixedOffset, NaiveTime, Offset, Timelike};
+#[cfg(feature = "chrono")]
+use {
+    crate::errors::Error,
+    chrono::{FixedOffset, NaiveTime, Offset, Timelike},
+    std::convert::{TryFrom, TryInto},
+};
+
+#[cfg(feature = "chrono")]
+const NANOSECONDS_PER_DAY: i64 = 86_400_000_000_000;
 use neo4rs_macros::BoltStruct;
 
 #[derive(Debug, PartialEq, Clone, BoltStruct)]
@@ -15,60 +23,102 @@ pub struct BoltLocalTime {
     nanoseconds: BoltInteger,
 }
 
-impl Into<BoltTime> for (NaiveTime, FixedOffset) {
-    fn into(self) -> BoltTime {
-        let seconds_from_midnight = self.0.num_seconds_from_midnight() as i64;
-        let nanoseconds = seconds_from_midnight * 1_000_000_000 + self.0.nanosecond() as i64;
+impl BoltTime {
+    pub fn new(nanoseconds: i64, tz_offset_seconds: i32) -> Self {
         BoltTime {
-            nanoseconds: nanoseconds.into(),
-            tz_offset_seconds: self.1.fix().local_minus_utc().into(),
+            nanoseconds: BoltInteger::new(nanoseconds),
+            tz_offset_seconds: BoltInteger::new(tz_offset_seconds as i64),
         }
     }
-}
 
-impl Into<(NaiveTime, FixedOffset)> for BoltTime {
-    fn into(self) -> (NaiveTime, FixedOffset) {
-        let nanos = self.nanoseconds.value;
-        let seconds = (nanos / 1_000_000_000) as u32;
-        let nanoseconds = (nanos % 1_000_000_000) as u32;
-        (
-            NaiveTime::from_num_seconds_from_midnight(seconds, nanoseconds),
-            FixedOffset::east(self.tz_offset_seconds.value as i32),
-        )
+    /// Nanoseconds since midnight in the local time of the offset
+    pub fn nanoseconds(&self) -> i64 {
+        self.nanoseconds.value
+    }
+
+    /// Offset from UTC in seconds, negative west of UTC
+    pub fn tz_offset_seconds(&self) -> i64 {
+        self.tz_offset_seconds.value
     }
 }
 
-impl Into<BoltLocalTime> for NaiveTime {
-    fn into(self) -> BoltLocalTime {
-        let seconds_from_midnight = self.num_seconds_from_midnight() as i64;
-        let nanoseconds = seconds_from_midnight * 1_000_000_000 + self.nanosecond() as i64;
+impl BoltLocalTime {
+    pub fn new(nanoseconds: i64) -> Self {
         BoltLocalTime {
-            nanoseconds: nanoseconds.into(),
+            nanoseconds: BoltInteger::new(nanoseconds),
         }
     }
+
+    /// Nanoseconds since midnight
+    pub fn nanoseconds(&self) -> i64 {
+        self.nanoseconds.value
+    }
+}
+
+#[cfg(feature = "chrono")]
+fn nanoseconds_of_day(time: NaiveTime) -> i64 {
+    time.num_seconds_from_midnight() as i64 * 1_000_000_000 + time.nanosecond() as i64
+}
+
+/// The time of day for nanoseconds since midnight, which must be less than a day.
+#[cfg(feature = "chrono")]
+fn time_of_day(nanoseconds: i64) -> Result<NaiveTime> {
+    if !(0..NANOSECONDS_PER_DAY).contains(&nanoseconds) {
+        return Err(Error::ConverstionError);
+    }
+    NaiveTime::from_num_seconds_from_midnight_opt(
+        (nanoseconds / 1_000_000_000) as u32,
+        (nanoseconds % 1_000_000_000) as u32,
+    )
+    .ok_or(Error::ConverstionError)
+}
+
+#[cfg(feature = "chrono")]
+impl From<(NaiveTime, FixedOffset)> for BoltTime {
+    fn from((time, offset): (NaiveTime, FixedOffset)) -> BoltTime {
+        BoltTime::new(nanoseconds_of_day(time), offset.fix().local_minus_utc())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryInto<(NaiveTime, FixedOffset)> for BoltTime {
+    type Error = Error;
+
+    fn try_into(self) -> Result<(NaiveTime, FixedOffset)> {
+        let offset = i32::try_from(self.tz_offset_seconds.value)
+            .ok()
+            .and_then(FixedOffset::east_opt)
+            .ok_or(Error::ConverstionError)?;
+        Ok((time_of_day(self.nanoseconds.value)?, offset))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<NaiveTime> for BoltLocalTime {
+    fn from(time: NaiveTime) -> BoltLocalTime {
+        BoltLocalTime::new(nanoseconds_of_day(time))
+    }
 }
 
-impl Into<NaiveTime> for BoltLocalTime {
-    fn into(self) -> NaiveTime {
-        let nanos = self.nanoseconds.value;
-        let seconds = (nanos / 1_000_000_000) as u32;
-        let nanoseconds = (nanos % 1_000_000_000) as u32;
-        NaiveTime::from_num_seconds_from_midnight(seconds, nanoseconds)
+#[cfg(feature = "chrono")]
+impl TryInto<NaiveTime> for BoltLocalTime {
+    type Error = Error;
+
+    fn try_into(self) -> Result<NaiveTime> {
+        time_of_day(self.nanoseconds.value)
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "chrono"))]
 mod tests {
     use super::*;
     use crate::version::Version;
     use bytes::*;
-    use std::cell::RefCell;
-    use std::rc::Rc;
 
     #[test]
     fn should_serialize_time() {
         let time = NaiveTime::from_hms_nano_opt(7, 8, 9, 100).unwrap();
-        let offset = FixedOffset::east(2 * 3600);
+        let offset = FixedOffset::east_opt(2 * 3600).unwrap();
 
         let time: BoltTime = (time, offset).into();
 
@@ -82,17 +132,17 @@ mod tests {
 
     #[test]
     fn should_deserialize_time() {
-        let bytes = Rc::new(RefCell::new(Bytes::from_static(&[
+        let mut bytes = Bytes::from_static(&[
             0xB2, 0x54, 0xCB, 0x00, 0x00, 0x17, 0x5D, 0x2F, 0xB8, 0x3A, 0x64, 0xC9, 0x1C, 0x20,
-        ])));
+        ]);
 
-        let (time, offset) = BoltTime::parse(Version::V4_1, bytes)
+        let (time, offset) = BoltTime::parse(Version::V4_1, &mut bytes)
             .unwrap()
             .try_into()
             .unwrap();
 
         assert_eq!(time.to_string(), "07:08:09.000000100");
-        assert_eq!(offset, FixedOffset::east(2 * 3600));
+        assert_eq!(offset, FixedOffset::east_opt(2 * 3600).unwrap());
     }
 
     #[test]
@@ -111,15 +161,63 @@ mod tests {
 
     #[test]
     fn should_deserialize_local_time() {
-        let bytes = Rc::new(RefCell::new(Bytes::from_static(&[
+        let mut bytes = Bytes::from_static(&[
             0xB1, 0x74, 0xCB, 0x00, 0x00, 0x17, 0x5D, 0x2F, 0xB8, 0x3A, 0x64,
-        ])));
+        ]);
 
-        let time: NaiveTime = BoltLocalTime::parse(Version::V4_1, bytes)
+        let time: NaiveTime = BoltLocalTime::parse(Version::V4_1, &mut bytes)
             .unwrap()
             .try_into()
             .unwrap();
 
         assert_eq!(time.to_string(), "07:08:09.000000100");
     }
+
+    #[test]
+    fn should_round_trip_times_of_day() {
+        let times = [
+            (NaiveTime::from_hms_opt(0, 0, 0).unwrap(), 0),
+            (
+                NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+                43_200_000_000_000,
+            ),
+            (
+                NaiveTime::from_hms_nano_opt(23, 59, 59, 999_999_999).unwrap(),
+                86_399_999_999_999,
+            ),
+        ];
+        for (time, nanoseconds) in times.iter() {
+            let offset = FixedOffset::west_opt(5 * 3600).unwrap();
+
+            let bolt_time: BoltTime = (*time, offset).into();
+            assert_eq!(bolt_time.nanoseconds(), *nanoseconds);
+            assert_eq!(bolt_time.tz_offset_seconds(), -5 * 3600);
+            let mut bytes = bolt_time.into_bytes(Version::V4_1).unwrap();
+            let parsed: (NaiveTime, FixedOffset) = BoltTime::parse(Version::V4_1, &mut bytes)
+                .unwrap()
+                .try_into()
+                .unwrap();
+            assert_eq!(parsed, (*time, offset));
+
+            let local_time: BoltLocalTime = (*time).into();
+            assert_eq!(local_time.nanoseconds(), *nanoseconds);
+            let mut bytes = local_time.into_bytes(Version::V4_1).unwrap();
+            let parsed: NaiveTime = BoltLocalTime::parse(Version::V4_1, &mut bytes)
+                .unwrap()
+                .try_into()
+                .unwrap();
+            assert_eq!(parsed, *time);
+        }
+    }
+
+    #[test]
+    fn should_reject_nanoseconds_outside_of_a_day() {
+        for nanoseconds in [-1, NANOSECONDS_PER_DAY].iter() {
+            let time: Result<NaiveTime> = BoltLocalTime::new(*nanoseconds).try_into();
+            assert!(matches!(time, Err(Error::ConverstionError)));
+
+            let time: Result<(NaiveTime, FixedOffset)> = BoltTime::new(*nanoseconds, 0).try_into();
+            assert!(matches!(time, Err(Error::ConverstionError)));
+        }
+    }
 }
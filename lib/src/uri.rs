@@ -0,0 +1,183 @@
+use crate::errors::{Error, Result};
+
+const DEFAULT_PORT: u16 = 7687;
+
+const SCHEMES: &[&str] = &[
+    "bolt",
+    "bolt+s",
+    "bolt+ssc",
+    "neo4j",
+    "neo4j+s",
+    "neo4j+ssc",
+];
+
+/// A parsed connection uri like `neo4j+s://example.com:7687`, see [`parse_uri`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionUri {
+    scheme: String,
+    host: String,
+    port: u16,
+}
+
+impl ConnectionUri {
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// The host name or ip address, without brackets for IPv6 addresses
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Whether queries are routed between the members of a cluster (`neo4j://`) or sent to the
+    /// given server only (`bolt://`)
+    pub fn is_routing(&self) -> bool {
+        self.scheme.starts_with("neo4j")
+    }
+
+    /// Whether the connection is encrypted with tls (`+s` and `+ssc`)
+    pub fn is_encrypted(&self) -> bool {
+        self.scheme.contains('+')
+    }
+
+    /// Whether any server certificate is accepted, including self signed ones (`+ssc`)
+    pub fn trusts_any_certificate(&self) -> bool {
+        self.scheme.ends_with("+ssc")
+    }
+
+    /// The `host:port` to open a tcp connection to
+    pub fn address(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+
+    /// The uri to connect directly to `address`, with the same encryption as this uri
+    pub(crate) fn direct(&self, address: &str) -> String {
+        format!("{}://{}", self.scheme.replacen("neo4j", "bolt", 1), address)
+    }
+}
+
+/// Parses a uri of the form `scheme://host[:port]`, where the scheme is one of `bolt`, `neo4j`
+/// and their encrypted variants `bolt+s`, `bolt+ssc`, `neo4j+s` and `neo4j+ssc`. The port
+/// defaults to 7687 and IPv6 addresses have to be enclosed in brackets. Uris without a scheme are
+/// treated as `bolt://`.
+pub fn parse_uri(uri: &str) -> Result<ConnectionUri> {
+    let invalid = |reason: &str| Error::InvalidUri(format!("{}: {}", uri, reason));
+    let (scheme, rest) = uri.split_once("://").unwrap_or(("bolt", uri));
+    if !SCHEMES.contains(&scheme) {
+        return Err(invalid("unsupported scheme"));
+    }
+    let authority = rest.strip_suffix('/').unwrap_or(rest);
+    if authority.contains(&['/', '?', '#', '@'][..]) {
+        return Err(invalid("only a host and a port are supported"));
+    }
+    let (host, port) = match authority.strip_prefix('[') {
+        Some(bracketed) => {
+            let (host, port) = bracketed
+                .split_once(']')
+                .ok_or_else(|| invalid("unclosed bracket"))?;
+            match port {
+                "" => (host, None),
+                port => (
+                    host,
+                    Some(port.strip_prefix(':').ok_or_else(|| invalid("bad port"))?),
+                ),
+            }
+        }
+        None => match authority.split_once(':') {
+            Some((_, port)) if port.contains(':') => {
+                return Err(invalid("IPv6 addresses must be enclosed in brackets"))
+            }
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        },
+    };
+    if host.is_empty() {
+        return Err(invalid("missing host"));
+    }
+    let port = match port {
+        None => DEFAULT_PORT,
+        Some(port) => port
+            .parse()
+            .ok()
+            .filter(|&port| port != 0)
+            .ok_or_else(|| invalid("bad port"))?,
+    };
+    Ok(ConnectionUri {
+        scheme: scheme.to_owned(),
+        host: host.to_owned(),
+        port,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags(uri: &str) -> (bool, bool, bool) {
+        let uri = parse_uri(uri).unwrap();
+        (
+            uri.is_routing(),
+            uri.is_encrypted(),
+            uri.trusts_any_certificate(),
+        )
+    }
+
+    #[test]
+    fn should_derive_flags_from_scheme() {
+        assert_eq!(flags("bolt://localhost"), (false, false, false));
+        assert_eq!(flags("bolt+s://localhost"), (false, true, false));
+        assert_eq!(flags("bolt+ssc://localhost"), (false, true, true));
+        assert_eq!(flags("neo4j://localhost"), (true, false, false));
+        assert_eq!(flags("neo4j+s://localhost"), (true, true, false));
+        assert_eq!(flags("neo4j+ssc://localhost"), (true, true, true));
+        assert_eq!(flags("localhost:7687"), (false, false, false));
+    }
+
+    #[test]
+    fn should_parse_host_and_port() {
+        let uri = parse_uri("neo4j+s://example.com:9999/").unwrap();
+        assert_eq!(uri.scheme(), "neo4j+s");
+        assert_eq!(uri.host(), "example.com");
+        assert_eq!(uri.port(), 9999);
+        assert_eq!(uri.address(), "example.com:9999");
+        assert_eq!(uri.direct("other:1"), "bolt+s://other:1");
+
+        assert_eq!(parse_uri("bolt://localhost").unwrap().port(), 7687);
+        let ipv6 = parse_uri("bolt://[::1]:7688").unwrap();
+        assert_eq!(ipv6.host(), "::1");
+        assert_eq!(ipv6.address(), "[::1]:7688");
+        assert_eq!(parse_uri("bolt://[::1]").unwrap().port(), 7687);
+    }
+
+    #[test]
+    fn should_reject_malformed_uris() {
+        for uri in &[
+            "http://localhost:7687",
+            "bolt://",
+            "bolt://:7687",
+            "bolt://localhost:",
+            "bolt://localhost:0",
+            "bolt://localhost:70000",
+            "bolt://localhost:port",
+            "bolt://::1",
+            "bolt://[::1",
+            "bolt://[::1]7687",
+            "bolt://localhost:7687/db",
+            "bolt://user@localhost",
+        ] {
+            assert!(
+                matches!(parse_uri(uri), Err(Error::InvalidUri(_))),
+                "{} should be rejected",
+                uri
+            );
+        }
+    }
+}
@@ -1,28 +1,83 @@
 use crate::errors::{Error, Result};
-use bytes::{BufMut, Bytes, BytesMut};
 use std::cmp::PartialEq;
 use std::fmt::Debug;
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum Version {
-    V4_1,
     V4,
+    V4_1,
+    V4_4,
+    V5_0,
+    V5_1,
+    V5_2,
+    V5_3,
 }
 
 impl Version {
-    pub fn supported_versions() -> Bytes {
-        let mut bytes = BytesMut::with_capacity(16);
-        let versions: [u32; 4] = [0x0104, 0x0004, 0, 0];
-        for version in versions.iter() {
-            bytes.put_u32(*version);
+    /// The versions proposed during the handshake unless configured otherwise.
+    pub fn supported_versions() -> Vec<Version> {
+        vec![Version::V4_1, Version::V4]
+    }
+
+    /// The version as it is encoded in the handshake, minor version in the third byte and major
+    /// version in the fourth.
+    pub fn as_u32(self) -> u32 {
+        match self {
+            Version::V5_3 => 0x0305,
+            Version::V5_2 => 0x0205,
+            Version::V5_1 => 0x0105,
+            Version::V5_0 => 0x0005,
+            Version::V4_4 => 0x0404,
+            Version::V4_1 => 0x0104,
+            Version::V4 => 0x0004,
         }
-        bytes.freeze()
+    }
+
+    /// Nodes and relationships carry string element ids since bolt 5.0
+    pub fn has_element_ids(self) -> bool {
+        self >= Version::V5_0
+    }
+
+    /// Datetimes are sent with seconds in UTC since bolt 5.0, before they were counted in the
+    /// local time of their zone.
+    pub fn has_utc_datetimes(self) -> bool {
+        self >= Version::V5_0
+    }
+
+    /// Routing tables are requested with the ROUTE message since bolt 4.3, older servers are
+    /// asked through the `dbms.routing.getRoutingTable` procedure.
+    pub fn has_route_message(self) -> bool {
+        self >= Version::V4_4
+    }
+
+    /// Transactions can run as another user with `imp_user` since bolt 4.4
+    pub fn has_impersonation(self) -> bool {
+        self >= Version::V4_4
+    }
+
+    /// The auth token is sent with LOGON after HELLO since bolt 5.1, so that a connection can
+    /// switch its user with LOGOFF and another LOGON
+    pub fn has_logon(self) -> bool {
+        self >= Version::V5_1
+    }
+
+    /// HELLO describes the driver in a `bolt_agent` map next to the `user_agent` since bolt 5.3
+    pub fn has_bolt_agent(self) -> bool {
+        self >= Version::V5_3
     }
 
     pub fn parse(version_bytes: [u8; 4]) -> Result<Version> {
         match u32::from_be_bytes(version_bytes) {
-            260 => Ok(Version::V4_1),
-            4 => Ok(Version::V4),
+            0x0305 => Ok(Version::V5_3),
+            0x0205 => Ok(Version::V5_2),
+            0x0105 => Ok(Version::V5_1),
+            0x0005 => Ok(Version::V5_0),
+            0x0404 => Ok(Version::V4_4),
+            0x0104 => Ok(Version::V4_1),
+            0x0004 => Ok(Version::V4),
+            0 => Err(Error::UnsupportedVersion(
+                "server supports none of the proposed versions".to_owned(),
+            )),
             v => Err(Error::UnsupportedVersion(format!(
                 "version {} is not supported",
                 v
@@ -37,7 +92,46 @@ mod tests {
 
     #[tokio::test]
     async fn should_parse_version() {
+        assert_eq!(Version::parse([0, 0, 4, 4]).unwrap(), Version::V4_4);
         assert_eq!(Version::parse([0, 0, 1, 4]).unwrap(), Version::V4_1);
         assert_eq!(Version::parse([0, 0, 0, 4]).unwrap(), Version::V4);
     }
+
+    #[test]
+    fn should_round_trip_version() {
+        for version in [
+            Version::V5_3,
+            Version::V5_2,
+            Version::V5_1,
+            Version::V5_0,
+            Version::V4_4,
+            Version::V4_1,
+            Version::V4,
+        ]
+        .iter()
+        {
+            let parsed = Version::parse(version.as_u32().to_be_bytes()).unwrap();
+            assert_eq!(parsed, *version);
+        }
+    }
+
+    #[test]
+    fn should_order_versions() {
+        assert!(Version::V4 < Version::V4_1);
+        assert!(Version::V4_4 < Version::V5_0);
+        assert!(!Version::V4_4.has_element_ids());
+        assert!(Version::V5_0.has_element_ids());
+        assert!(!Version::V5_0.has_logon());
+        assert!(Version::V5_1.has_logon());
+        assert!(!Version::V5_2.has_bolt_agent());
+        assert!(Version::V5_3.has_bolt_agent());
+    }
+
+    #[test]
+    fn should_reject_zero_version() {
+        assert!(matches!(
+            Version::parse([0, 0, 0, 0]),
+            Err(Error::UnsupportedVersion(_))
+        ));
+    }
 }
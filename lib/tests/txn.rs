@@ -0,0 +1,66 @@
+//! Runs against a live server, enable with `--features integration-tests`. The server is taken
+//! from `NEO4J_TEST_URI`, `NEO4J_TEST_USER` and `NEO4J_TEST_PASSWORD`, defaulting to
+//! `127.0.0.1:7687` with `neo4j`/`neo`.
+#![cfg(feature = "integration-tests")]
+
+use neo4rs::*;
+use uuid::Uuid;
+
+async fn graph() -> Graph {
+    let uri = std::env::var("NEO4J_TEST_URI").unwrap_or_else(|_| "127.0.0.1:7687".to_owned());
+    let user = std::env::var("NEO4J_TEST_USER").unwrap_or_else(|_| "neo4j".to_owned());
+    let password = std::env::var("NEO4J_TEST_PASSWORD").unwrap_or_else(|_| "neo".to_owned());
+    Graph::new(&uri, &user, &password).await.unwrap()
+}
+
+async fn count(graph: &Graph, id: &str) -> i64 {
+    let mut result = graph
+        .execute(query("MATCH (p:Person {id: $id}) RETURN count(p) AS n").param("id", id))
+        .await
+        .unwrap();
+    let row = result.next().await.unwrap().unwrap();
+    row.get("n").unwrap()
+}
+
+#[tokio::test]
+async fn should_commit_transaction() {
+    let graph = graph().await;
+    let id = Uuid::new_v4().to_string();
+
+    let txn = graph.start_txn().await.unwrap();
+    txn.run(query("CREATE (p:Person {id: $id})").param("id", id.clone()))
+        .await
+        .unwrap();
+    txn.commit().await.unwrap();
+
+    assert_eq!(count(&graph, &id).await, 1);
+}
+
+#[tokio::test]
+async fn should_roll_back_after_failed_query() {
+    let graph = graph().await;
+    let id = Uuid::new_v4().to_string();
+
+    let txn = graph.start_txn().await.unwrap();
+    txn.run(query("CREATE (p:Person {id: $id})").param("id", id.clone()))
+        .await
+        .unwrap();
+    assert!(txn.run(query("RETURN 1/0")).await.is_err());
+    txn.rollback().await.unwrap();
+
+    assert_eq!(count(&graph, &id).await, 0);
+}
+
+#[tokio::test]
+async fn should_roll_back_dropped_transaction() {
+    let graph = graph().await;
+    let id = Uuid::new_v4().to_string();
+
+    let txn = graph.start_txn().await.unwrap();
+    txn.run(query("CREATE (p:Person {id: $id})").param("id", id.clone()))
+        .await
+        .unwrap();
+    drop(txn);
+
+    assert_eq!(count(&graph, &id).await, 0);
+}
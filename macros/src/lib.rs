@@ -8,7 +8,7 @@ pub fn derive(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     let struct_name = &ast.ident;
 
-    let meta = ast.attrs.get(0).unwrap().parse_meta().unwrap();
+    let meta = ast.attrs.first().unwrap().parse_meta().unwrap();
 
     let values: Vec<syn::LitInt> = match meta {
         syn::Meta::List(MetaList { nested, .. }) => {
@@ -25,11 +25,11 @@ pub fn derive(input: TokenStream) -> TokenStream {
     .collect();
 
     let (struct_marker, struct_signature) = if values.len() == 2 {
-        let marker = values.get(0).unwrap();
+        let marker = values.first().unwrap();
         let sig = values.get(1).unwrap();
         (quote! { #marker}, quote! {Some(#sig)})
     } else {
-        let marker = values.get(0).unwrap();
+        let marker = values.first().unwrap();
         (quote! { #marker}, quote! { None::<u8> })
     };
 
@@ -45,24 +45,17 @@ pub fn derive(input: TokenStream) -> TokenStream {
         unimplemented!(concat!(stringify!(#name), ": not a struct"));
     };
 
-    let serialize_fields = fields.iter().map(|f| {
+    let encode_fields = fields.iter().map(|f| {
         let name = &f.ident;
         quote! {
-            let #name: bytes::Bytes = self.#name.into_bytes(version)?
+            crate::types::BoltEncode::encode(&self.#name, encoding, dst)?
         }
     });
 
-    let allocate_bytes = fields.iter().map(|f| {
+    let field_lens = fields.iter().map(|f| {
         let name = &f.ident;
         quote! {
-            total_bytes += #name.len()
-        }
-    });
-
-    let put_bytes = fields.iter().map(|f| {
-        let name = &f.ident;
-        quote! {
-            bytes.put(#name)
+            crate::types::BoltEncode::encoded_len(&self.#name, encoding)
         }
     });
 
@@ -70,7 +63,7 @@ pub fn derive(input: TokenStream) -> TokenStream {
         let name = &f.ident;
         let typ = &f.ty;
         quote! {
-            #name: #typ::parse(version, input.clone())?
+            #name: #typ::parse(version, input)?
         }
     });
 
@@ -79,30 +72,35 @@ pub fn derive(input: TokenStream) -> TokenStream {
         use bytes::*;
 
         impl #struct_name {
+            pub fn into_bytes(self, encoding: impl Into<crate::types::Encoding>) -> crate::errors::Result<bytes::Bytes> {
+                crate::types::BoltEncode::to_bytes(&self, encoding)
+            }
+        }
 
-            pub fn into_bytes(self, version: crate::version::Version) -> crate::errors::Result<bytes::Bytes> {
-                #(#serialize_fields;)*
-                let mut total_bytes = std::mem::size_of::<u8>() + std::mem::size_of::<u8>();
-                #(#allocate_bytes;)*
-                let mut bytes = BytesMut::with_capacity(total_bytes);
-                bytes.put_u8(#struct_marker);
+        impl crate::types::BoltEncode for #struct_name {
+            fn encode(&self, encoding: crate::types::Encoding, dst: &mut bytes::BytesMut) -> crate::errors::Result<()> {
+                dst.put_u8(#struct_marker);
                 if let Some(signature) = #struct_signature {
-                    bytes.put_u8(signature);
+                    dst.put_u8(signature);
                 }
-                #(#put_bytes;)*
-                Ok(bytes.freeze())
+                #(#encode_fields;)*
+                Ok(())
             }
 
+            fn encoded_len(&self, encoding: crate::types::Encoding) -> usize {
+                let header = if #struct_signature.is_some() { 2 } else { 1 };
+                header #(+ #field_lens)*
+            }
         }
 
         impl #struct_name {
-            pub fn can_parse(version: crate::version::Version, input: std::rc::Rc<std::cell::RefCell<bytes::Bytes>>) -> bool {
+            pub fn can_parse(version: crate::version::Version, input: &bytes::Bytes) -> bool {
                 match (#struct_marker, #struct_signature) {
                     (marker, Some(signature)) =>  {
-                        input.borrow().len() >= 2 && input.borrow()[0] == marker && input.borrow()[1] == signature
+                        input.len() >= 2 && input[0] == marker && input[1] == signature
                     },
                     (marker, None) => {
-                        input.borrow().len() >= 1 && input.borrow()[0] == marker
+                        input.len() >= 1 && input[0] == marker
                     }
                     _ => false
                 }
@@ -111,15 +109,14 @@ pub fn derive(input: TokenStream) -> TokenStream {
 
         impl #struct_name {
 
-            pub fn parse(version: crate::version::Version, input: std::rc::Rc<std::cell::RefCell<bytes::Bytes>>) -> crate::errors::Result<#struct_name> {
+            pub fn parse(version: crate::version::Version, input: &mut bytes::Bytes) -> crate::errors::Result<#struct_name> {
 
                 match (#struct_marker, #struct_signature) {
                     (_, Some(_)) =>  {
-                        input.borrow_mut().get_u8();
-                        input.borrow_mut().get_u8();
+                        crate::types::read_exact(input, 2)?;
                     },
                     (_, None) => {
-                        input.borrow_mut().get_u8();
+                        crate::types::read_exact(input, 1)?;
                     }
                 }
 